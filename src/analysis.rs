@@ -0,0 +1,18 @@
+pub mod cfg;
+pub mod constants;
+pub mod continuity;
+pub mod diff;
+pub mod dominators;
+pub mod frame;
+pub mod gadgets;
+pub mod hardening;
+pub mod level;
+pub mod loops;
+pub mod mix;
+pub mod pattern;
+pub mod size;
+pub mod strings;
+pub mod switches;
+pub mod syscalls;
+pub mod throughput;
+pub mod xrefs;
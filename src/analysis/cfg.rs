@@ -0,0 +1,146 @@
+use crate::analysis::switches::JumpTable;
+use crate::objdump::listing::ObjDumpFunction;
+use crate::objdump::x8664_att::X8664ATTFlow;
+use std::collections::{BTreeSet, HashMap};
+
+/// Half-open range `start..end` of instruction indices within a function.
+#[derive(Debug)]
+pub struct BasicBlock {
+    pub start: usize,
+    pub end: usize,
+    pub successors: Vec<usize>,
+    pub predecessors: Vec<usize>,
+}
+
+/// Intraprocedural control flow graph. Block 0 is the function entry.
+/// Jumps leaving the function, and indirect jumps other than recovered
+/// jump tables, have no successors.
+#[derive(Debug)]
+pub struct ControlFlowGraph {
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    pub fn build(function: &ObjDumpFunction) -> Self {
+        ControlFlowGraph::build_with_tables(function, &[])
+    }
+
+    /// Builds the graph with the entries of `tables` as the successors of
+    /// their indirect jumps.
+    pub fn build_with_tables(function: &ObjDumpFunction, tables: &[&JumpTable]) -> Self {
+        let instructions = &function.instructions;
+        let dispatches: HashMap<u64, Vec<u64>> = tables
+            .iter()
+            .map(|table| (table.jump, table.successors()))
+            .collect();
+        let index_of: HashMap<u64, usize> = instructions
+            .iter()
+            .enumerate()
+            .map(|(index, line)| (line.address, index))
+            .collect();
+
+        let mut leaders = BTreeSet::new();
+        if !instructions.is_empty() {
+            leaders.insert(0);
+        }
+        for (index, line) in instructions.iter().enumerate() {
+            match line.instruction.opcode.flow() {
+                X8664ATTFlow::Jump | X8664ATTFlow::ConditionalJump => {
+                    for target in dispatches.get(&line.address).into_iter().flatten() {
+                        leaders.extend(index_of.get(target));
+                    }
+                    if let Some(target) = line
+                        .instruction
+                        .branch_target()
+                        .and_then(|target| index_of.get(&target))
+                    {
+                        leaders.insert(*target);
+                    }
+                    leaders.insert(index + 1);
+                }
+                X8664ATTFlow::Return | X8664ATTFlow::Halt => {
+                    leaders.insert(index + 1);
+                }
+                X8664ATTFlow::Sequential | X8664ATTFlow::Call => {}
+            }
+        }
+        leaders.retain(|&leader| leader < instructions.len());
+
+        let starts: Vec<usize> = leaders.into_iter().collect();
+        let block_of: HashMap<usize, usize> = starts
+            .iter()
+            .enumerate()
+            .map(|(block, &start)| (start, block))
+            .collect();
+
+        let mut blocks: Vec<BasicBlock> = starts
+            .iter()
+            .enumerate()
+            .map(|(block, &start)| BasicBlock {
+                start,
+                end: starts.get(block + 1).copied().unwrap_or(instructions.len()),
+                successors: Vec::new(),
+                predecessors: Vec::new(),
+            })
+            .collect();
+
+        for block in 0..blocks.len() {
+            let line = &instructions[blocks[block].end - 1];
+            let last = &line.instruction;
+            let fallthrough = block_of.get(&blocks[block].end).copied();
+            let target = last
+                .branch_target()
+                .and_then(|target| index_of.get(&target))
+                .and_then(|index| block_of.get(index))
+                .copied();
+            let successors: Vec<usize> = match last.opcode.flow() {
+                X8664ATTFlow::Sequential | X8664ATTFlow::Call => fallthrough.into_iter().collect(),
+                X8664ATTFlow::ConditionalJump => target.into_iter().chain(fallthrough).collect(),
+                X8664ATTFlow::Jump => match dispatches.get(&line.address) {
+                    Some(targets) => targets
+                        .iter()
+                        .filter_map(|target| index_of.get(target))
+                        .filter_map(|index| block_of.get(index))
+                        .copied()
+                        .collect(),
+                    None => target.into_iter().collect(),
+                },
+                X8664ATTFlow::Return | X8664ATTFlow::Halt => Vec::new(),
+            };
+            for &successor in &successors {
+                if !blocks[successor].predecessors.contains(&block) {
+                    blocks[successor].predecessors.push(block);
+                }
+            }
+            blocks[block].successors = successors;
+            blocks[block].successors.dedup();
+        }
+
+        ControlFlowGraph { blocks }
+    }
+
+    /// Blocks reachable from the entry, in reverse postorder.
+    pub fn reverse_postorder(&self) -> Vec<usize> {
+        let mut order = Vec::new();
+        if self.blocks.is_empty() {
+            return order;
+        }
+        let mut visited = vec![false; self.blocks.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, next)) = stack.pop() {
+            match self.blocks[block].successors.get(next) {
+                Some(&successor) => {
+                    stack.push((block, next + 1));
+                    if !visited[successor] {
+                        visited[successor] = true;
+                        stack.push((successor, 0));
+                    }
+                }
+                None => order.push(block),
+            }
+        }
+        order.reverse();
+        order
+    }
+}
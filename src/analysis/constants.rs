@@ -0,0 +1,124 @@
+use crate::analysis::frame::access_width;
+use crate::analysis::xrefs::{XrefDatabase, XrefKind};
+use crate::objdump::listing::ObjDumpListing;
+use serde::Serialize;
+
+/// A load from an address that resolves into one of the listing's section
+/// images, with the bytes it reads.
+#[derive(Debug, Serialize)]
+pub struct ConstantLoad {
+    pub function: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub target: u64,
+    pub section: String,
+    #[serde(serialize_with = "crate::objdump::hex::bytes")]
+    pub bytes: Vec<u8>,
+    /// The bytes as the instruction reads them: an integer, or a float for
+    /// scalar SSE operations.
+    pub value: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ConstantReport {
+    pub loads: Vec<ConstantLoad>,
+}
+
+fn render(operation: &str, bytes: &[u8]) -> String {
+    let scalar = operation.strip_prefix('v').unwrap_or(operation);
+    let float = !scalar.starts_with('p') && !scalar.starts_with("cvtsi");
+    match (bytes.len(), float) {
+        (4, true) if scalar.ends_with("ss") => {
+            f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).to_string()
+        }
+        (8, true) if scalar.ends_with("sd") => {
+            let mut value = [0; 8];
+            value.copy_from_slice(bytes);
+            f64::from_le_bytes(value).to_string()
+        }
+        (1..=8, _) => {
+            let mut value = [0; 8];
+            value[..bytes.len()].copy_from_slice(bytes);
+            format!("{:#x}", u64::from_le_bytes(value))
+        }
+        _ => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+    }
+}
+
+impl ConstantReport {
+    /// Every read through a RIP-relative or absolute memory operand whose
+    /// bytes the listing has, from `objdump -s` contents or the ELF file.
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let database = XrefDatabase::build(listing);
+        let mut report = ConstantReport::default();
+        for xref in database.xrefs() {
+            if xref.kind != XrefKind::Read {
+                continue;
+            }
+            let (Some(line), Some(section)) =
+                (database.instruction(xref.from), listing.section_at(xref.to))
+            else {
+                continue;
+            };
+            let instruction = &line.instruction;
+            let width = access_width(instruction).unwrap_or(8) as usize;
+            let Some(bytes) = listing
+                .bytes_at(xref.to)
+                .and_then(|bytes| bytes.get(..width))
+            else {
+                continue;
+            };
+            report.loads.push(ConstantLoad {
+                function: database
+                    .function_containing(xref.from)
+                    .map_or(String::new(), |function| function.name.clone()),
+                address: xref.from,
+                target: xref.to,
+                section: section.name.clone(),
+                value: render(instruction.opcode.operation(), bytes),
+                bytes: bytes.to_vec(),
+            });
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+Contents of section .rodata:
+ 402000 00000000 0000f83f 00000040 2a000000  .......?...@*...
+
+Disassembly of section .text:
+
+0000000000401000 <f>:
+  401000:\tf2 0f 10 05 f8 0f 00 00 \tmovsd  0xff8(%rip),%xmm0        # 402000 <c>
+  401008:\tf3 0f 10 05 f8 0f 00 00 \tmovss  0xff8(%rip),%xmm1        # 402008 <c+0x8>
+  401010:\t8b 05 f6 0f 00 00    \tmov    0xff6(%rip),%eax        # 40200c <c+0xc>
+  401016:\tc3                   \tret
+";
+
+    #[test]
+    fn renders_loads_at_their_width() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = ConstantReport::build(&listing);
+        let loads: Vec<_> = report
+            .loads
+            .iter()
+            .map(|load| (load.address, load.target, load.value.as_str()))
+            .collect();
+        assert_eq!(
+            loads,
+            [
+                (0x401000, 0x402000, "1.5"),
+                (0x401008, 0x402008, "2"),
+                (0x401010, 0x40200c, "0x2a"),
+            ]
+        );
+        assert_eq!(report.loads[2].bytes, [0x2a, 0, 0, 0]);
+        assert!(report.loads.iter().all(|load| load.section == ".rodata"));
+    }
+}
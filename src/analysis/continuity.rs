@@ -0,0 +1,327 @@
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Instructions that are privileged, invalid in 64-bit mode or otherwise
+/// unlikely in compiled user code. A run of them usually means objdump is
+/// decoding data.
+const IMPLAUSIBLE: &[&str] = &[
+    "in", "inb", "inw", "inl", "out", "outb", "outw", "outl", "ins", "insb", "insw", "insl",
+    "outs", "outsb", "outsw", "outsl", "hlt", "cli", "sti", "iret", "iretw", "iretl", "iretq",
+    "lcall", "lcalll", "lcallq", "ljmp", "ljmpl", "ljmpq", "lret", "lretl", "lretq", "into",
+    "bound", "arpl", "aaa", "aas", "aam", "aad", "daa", "das", "les", "lds", "pusha", "popa",
+    "icebp", "int1", "sysexit", "sysret", "rsm", "invd", "wbinvd", "lgdt", "lidt", "lldt", "ltr",
+    "clts", "xlat",
+];
+
+/// Implausible instructions further apart than this end a suspicious run.
+const RUN_SLACK: usize = 3;
+/// Implausible instructions needed for a run without `(bad)` to be reported.
+const RUN_MINIMUM: usize = 3;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContinuityIssue {
+    /// Bytes between the end of one instruction and the start of the next.
+    Gap {
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        start: u64,
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        end: u64,
+    },
+    /// An instruction that starts before the previous one ends.
+    Overlap {
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        start: u64,
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        end: u64,
+    },
+    /// A branch into the function that lands inside a decoded instruction.
+    Misaligned {
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        source: u64,
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        target: u64,
+    },
+    /// A run of `(bad)` or implausible instructions.
+    Suspicious {
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        start: u64,
+        #[serde(serialize_with = "crate::objdump::hex::address")]
+        end: u64,
+        bad: usize,
+        implausible: usize,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionContinuity {
+    pub name: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub issues: Vec<ContinuityIssue>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ContinuityReport {
+    pub functions: Vec<FunctionContinuity>,
+    /// Instructions without raw bytes, whose length is unknown.
+    pub unchecked: usize,
+}
+
+fn end(line: &ObjDumpInstructionLine) -> u64 {
+    line.address + line.bytes.len() as u64
+}
+
+/// `(bad)`, or a `.byte` directive for bytes objdump would not decode.
+fn is_bad(line: &ObjDumpInstructionLine) -> bool {
+    line.instruction.is_bad() || line.instruction.opcode.is_data()
+}
+
+/// `(bad)`, an implausible opcode, or the `add %al,(%rax)` that zero bytes
+/// decode to.
+fn is_implausible(line: &ObjDumpInstructionLine) -> bool {
+    let mnemonic = line.instruction.opcode.mnemonic();
+    is_bad(line)
+        || IMPLAUSIBLE.contains(&mnemonic)
+        || IMPLAUSIBLE.contains(&line.instruction.opcode.operation())
+        || line.bytes == [0, 0]
+}
+
+/// Instruction boundaries of the whole listing, so that branches into
+/// other functions are checked too.
+struct CodeMap {
+    starts: HashSet<u64>,
+    /// glibc jumps over the `lock` prefix when running single-threaded.
+    after_lock: HashSet<u64>,
+    ranges: Vec<(u64, u64)>,
+}
+
+impl CodeMap {
+    fn new(listing: &ObjDumpListing) -> Self {
+        let lines = || {
+            listing
+                .functions
+                .iter()
+                .flat_map(|function| &function.instructions)
+        };
+        let mut ranges: Vec<(u64, u64)> = listing
+            .functions
+            .iter()
+            .filter_map(|function| {
+                let first = function.instructions.first()?;
+                Some((first.address, end(function.instructions.last()?)))
+            })
+            .collect();
+        ranges.sort();
+        CodeMap {
+            starts: lines().map(|line| line.address).collect(),
+            after_lock: lines()
+                .filter(|line| line.bytes.first() == Some(&0xf0))
+                .map(|line| line.address + 1)
+                .collect(),
+            ranges,
+        }
+    }
+
+    /// Whether `address` is within decoded code but not on a boundary.
+    fn inside(&self, address: u64) -> bool {
+        let index = self.ranges.partition_point(|range| range.0 <= address);
+        let within = index > 0 && address < self.ranges[index - 1].1;
+        within && !self.starts.contains(&address) && !self.after_lock.contains(&address)
+    }
+}
+
+fn check_function(function: &ObjDumpFunction, code: &CodeMap, issues: &mut Vec<ContinuityIssue>) {
+    let instructions = &function.instructions;
+    for pair in instructions.windows(2) {
+        let (previous, next) = (&pair[0], &pair[1]);
+        if previous.bytes.is_empty() {
+            continue;
+        }
+        if next.address > end(previous) {
+            issues.push(ContinuityIssue::Gap {
+                start: end(previous),
+                end: next.address,
+            });
+        } else if next.address < end(previous) {
+            issues.push(ContinuityIssue::Overlap {
+                start: next.address,
+                end: end(previous),
+            });
+        }
+    }
+
+    for line in instructions {
+        if let Some(target) = line.instruction.branch_target() {
+            if code.inside(target) {
+                issues.push(ContinuityIssue::Misaligned {
+                    source: line.address,
+                    target,
+                });
+            }
+        }
+    }
+
+    let mut index = 0;
+    while index < instructions.len() {
+        if !is_implausible(&instructions[index]) {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        let mut stop = index;
+        let (mut bad, mut implausible) = (0, 0);
+        while index < instructions.len() && index - stop <= RUN_SLACK {
+            if is_implausible(&instructions[index]) {
+                implausible += 1;
+                bad += usize::from(is_bad(&instructions[index]));
+                stop = index;
+            }
+            index += 1;
+        }
+        if bad > 0 || implausible >= RUN_MINIMUM {
+            issues.push(ContinuityIssue::Suspicious {
+                start: instructions[start].address,
+                end: end(&instructions[stop]).max(instructions[stop].address + 1),
+                bad,
+                implausible,
+            });
+        }
+        index = stop + 1;
+    }
+}
+
+impl ContinuityReport {
+    /// Checks that consecutive instructions of each function tile its
+    /// address range exactly, that branch targets fall on instruction
+    /// boundaries, and flags runs that look like data decoded as code.
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let mut report = ContinuityReport::default();
+        let code = CodeMap::new(listing);
+        for function in &listing.functions {
+            report.unchecked += function
+                .instructions
+                .iter()
+                .filter(|line| line.bytes.is_empty())
+                .count();
+            let mut issues = Vec::new();
+            check_function(function, &code, &mut issues);
+            if !issues.is_empty() {
+                report.functions.push(FunctionContinuity {
+                    name: function.name.clone(),
+                    address: function.address,
+                    issues,
+                });
+            }
+        }
+        report
+    }
+
+    pub fn issues(&self) -> usize {
+        self.functions
+            .iter()
+            .map(|function| function.issues.len())
+            .sum()
+    }
+}
+
+impl std::fmt::Display for ContinuityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContinuityIssue::Gap { start, end } => {
+                write!(
+                    f,
+                    "gap        {:#x}..{:#x} ({} bytes)",
+                    start,
+                    end,
+                    end - start
+                )
+            }
+            ContinuityIssue::Overlap { start, end } => {
+                write!(
+                    f,
+                    "overlap    {:#x}..{:#x} ({} bytes)",
+                    start,
+                    end,
+                    end - start
+                )
+            }
+            ContinuityIssue::Misaligned { source, target } => {
+                write!(f, "misaligned {:#x} -> {:#x}", source, target)
+            }
+            ContinuityIssue::Suspicious {
+                start,
+                end,
+                bad,
+                implausible,
+            } => write!(
+                f,
+                "suspicious {:#x}..{:#x} ({} bad, {} implausible)",
+                start, end, bad, implausible
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+0000000000401000 <f>:
+  401000:\t31 c0                \txor    %eax,%eax
+  401004:\t74 01                \tje     401007 <f+0x7>
+  401006:\t48 89 c7             \tmov    %rax,%rdi
+  401008:\tc3                   \tret
+
+0000000000401010 <g>:
+  401010:\t75 01                \tjne    401013 <g+0x3>
+  401012:\tf0 0f b1 17          \tlock cmpxchg %edx,(%rdi)
+  401016:\tc3                   \tret
+  401017:\tf4                   \thlt
+
+0000000000401020 <h>:
+  401020:\t00 00                \tadd    %al,(%rax)
+  401022:\tec                   \tin     (%dx),%al
+  401023:\t06                   \t(bad)
+  401024:\tc3                   \tret
+";
+
+    fn issues(report: &ContinuityReport) -> Vec<(&str, Vec<String>)> {
+        report
+            .functions
+            .iter()
+            .map(|function| {
+                let issues = function.issues.iter().map(|issue| issue.to_string());
+                (function.name.as_str(), issues.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_gaps_overlaps_and_misaligned_branches() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = ContinuityReport::build(&listing);
+        assert_eq!(
+            issues(&report),
+            [
+                (
+                    "f",
+                    vec![
+                        "gap        0x401002..0x401004 (2 bytes)".to_string(),
+                        "overlap    0x401008..0x401009 (1 bytes)".to_string(),
+                        "misaligned 0x401004 -> 0x401007".to_string(),
+                    ]
+                ),
+                (
+                    "h",
+                    vec!["suspicious 0x401020..0x401024 (1 bad, 3 implausible)".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(report.issues(), 4);
+        assert_eq!(report.unchecked, 0);
+    }
+}
@@ -0,0 +1,581 @@
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::x8664_att::{X8664ATTAddress, X8664ATTOperand, X8664ATTValue};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "text", rename_all = "snake_case")]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionDiff {
+    pub name: String,
+    pub change: FunctionChange,
+    /// Bytes of code, alignment padding excluded.
+    pub old_size: u64,
+    pub new_size: u64,
+    /// Bytes of alignment padding inside and after the code.
+    pub old_padding: u64,
+    pub new_padding: u64,
+    pub added: usize,
+    pub removed: usize,
+    /// Normalized instructions of both versions, merged in diff order.
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ListingDiff {
+    pub functions: Vec<FunctionDiff>,
+    pub unchanged: usize,
+}
+
+/// Bytes from the start of `function` to the end of its last instruction.
+fn extent(function: &ObjDumpFunction) -> u64 {
+    function.instructions.last().map_or(0, |last| {
+        last.address + last.bytes.len() as u64 - function.address
+    })
+}
+
+/// Whether an instruction is alignment padding: the multi-byte `nop`
+/// forms, `xchg %ax,%ax` and `int3`.
+fn is_padding(line: &ObjDumpInstructionLine) -> bool {
+    let instruction = &line.instruction;
+    match instruction.opcode.operation() {
+        "nop" | "int3" => true,
+        "xchg" => matches!(instruction.operands.as_slice(),
+            [X8664ATTOperand::Register(a), X8664ATTOperand::Register(b)]
+                if a.name() == "%ax" && b.name() == "%ax"),
+        _ => false,
+    }
+}
+
+/// Bytes of code and of padding in `function`.
+fn sizes(function: &ObjDumpFunction) -> (u64, u64) {
+    let padding: u64 = function
+        .instructions
+        .iter()
+        .filter(|line| is_padding(line))
+        .map(|line| line.bytes.len() as u64)
+        .sum();
+    (extent(function) - padding, padding)
+}
+
+/// Symbol of a `<name+0x10>` annotation, without the offset.
+fn symbol(data: &str) -> String {
+    let name = data.trim_start_matches('<').trim_end_matches('>');
+    let name = match name.rsplit_once('+') {
+        Some((name, offset)) if offset.starts_with("0x") => name,
+        _ => name,
+    };
+    match name.is_empty() {
+        true => "<?>".to_string(),
+        false => format!("<{}>", name),
+    }
+}
+
+/// Displacements that are addresses of the image rather than offsets:
+/// RIP-relative ones and absolute ones without base, index or segment.
+fn is_relocated(address: &X8664ATTAddress) -> bool {
+    let rip = matches!(&address.base, Some(X8664ATTValue::Register(register))
+        if register.name() == "%rip");
+    let absolute = address.base.is_none() && address.offset.is_none() && address.segment.is_none();
+    rip || absolute
+}
+
+/// The instructions of `function` other than padding, which local labels
+/// count so that padding added or removed does not renumber them.
+pub struct Code<'a> {
+    function: &'a ObjDumpFunction,
+    lines: Vec<&'a ObjDumpInstructionLine>,
+}
+
+impl<'a> Code<'a> {
+    pub fn new(function: &'a ObjDumpFunction) -> Self {
+        let lines = function
+            .instructions
+            .iter()
+            .filter(|line| !is_padding(line))
+            .collect();
+        Code { function, lines }
+    }
+
+    /// Position of an address inside the function: `<.L3>` for the start
+    /// of its fourth instruction, `<.+0x5>` for any other address.
+    fn local_label(&self, address: u64) -> Option<String> {
+        let function = self.function;
+        if !(function.address..function.address + extent(function)).contains(&address) {
+            return None;
+        }
+        Some(
+            match self
+                .lines
+                .binary_search_by_key(&address, |line| line.address)
+            {
+                Ok(index) => format!("<.L{}>", index),
+                Err(_) => format!("<.+{:#x}>", address - function.address),
+            },
+        )
+    }
+
+    /// The normalized instructions, padding left out.
+    fn normalized(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| normalize(line, self))
+            .collect()
+    }
+}
+
+fn render_operand(operand: &X8664ATTOperand, target: &str, code: &Code, next: u64) -> String {
+    match operand {
+        X8664ATTOperand::Target(_) => target.to_string(),
+        X8664ATTOperand::Address(address) if is_relocated(address) => {
+            let displacement = address
+                .displacement
+                .as_ref()
+                .map_or(0, |value| value.0 as u64);
+            let absolute = match address.base.is_some() {
+                true => next.wrapping_add(displacement),
+                false => displacement,
+            };
+            let mut address = address.clone();
+            address.displacement = None;
+            match (code.local_label(absolute), address.base.is_some()) {
+                (Some(label), true) => format!("{}{}", label, address),
+                (Some(label), false) => label,
+                (None, true) => address.to_string(),
+                (None, false) => "<abs>".to_string(),
+            }
+        }
+        X8664ATTOperand::Indirect(operand) => {
+            format!("*{}", render_operand(operand, target, code, next))
+        }
+        X8664ATTOperand::Decorated(operand, decoration) => {
+            format!(
+                "{}{}",
+                render_operand(operand, target, code, next),
+                decoration
+            )
+        }
+        operand => operand.to_string(),
+    }
+}
+
+/// Renders an instruction without anything that moves when code is laid
+/// out differently: addresses inside the function become the instruction
+/// they point to, so a retargeted branch still shows as a change; branch
+/// targets elsewhere become the symbol they land in, and other RIP-relative
+/// or absolute displacements are dropped.
+pub fn normalize(line: &ObjDumpInstructionLine, code: &Code) -> String {
+    let instruction = &line.instruction;
+    let next = line.address + line.bytes.len() as u64;
+    let target = match instruction.branch_target() {
+        Some(target) => code
+            .local_label(target)
+            .unwrap_or_else(|| symbol(&instruction.data)),
+        None => symbol(&instruction.data),
+    };
+    let mut text = String::new();
+    for prefix in &instruction.prefixes {
+        text.push_str(prefix.mnemonic());
+        text.push(' ');
+    }
+    text.push_str(instruction.opcode.mnemonic());
+    if !instruction.operands.is_empty() {
+        let operands: Vec<String> = instruction
+            .operands
+            .iter()
+            .map(|operand| render_operand(operand, &target, code, next))
+            .collect();
+        text = format!("{:<6} {}", text, operands.join(","));
+    }
+    text
+}
+
+/// Edit distance beyond which two versions of a function are reported as
+/// entirely replaced rather than searched for a shortest edit script.
+const MAX_EDITS: isize = 4096;
+
+/// Shortest edit script between `old` and `new` (Myers' algorithm).
+fn edit_script(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let limit = (n + m).min(MAX_EDITS);
+    let offset = limit + 1;
+    let mut frontier = vec![0isize; 2 * offset as usize + 1];
+    // Diagonals `-depth - 1..=depth + 1` of the frontier before each step.
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut found = false;
+    'search: for depth in 0..=limit {
+        trace
+            .push(frontier[(offset - depth - 1) as usize..=(offset + depth + 1) as usize].to_vec());
+        for diagonal in (-depth..=depth).step_by(2) {
+            let index = (diagonal + offset) as usize;
+            let mut x = match diagonal == -depth
+                || (diagonal != depth && frontier[index - 1] < frontier[index + 1])
+            {
+                true => frontier[index + 1],
+                false => frontier[index - 1] + 1,
+            };
+            let mut y = x - diagonal;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            frontier[index] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed(line.clone()))
+            .chain(new.iter().map(|line| DiffLine::Added(line.clone())))
+            .collect();
+    }
+
+    let mut lines = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (depth, frontier) in trace.iter().enumerate().rev() {
+        let depth = depth as isize;
+        let at = |diagonal: isize| frontier[(diagonal + depth + 1) as usize];
+        let diagonal = x - y;
+        let previous = match diagonal == -depth
+            || (diagonal != depth && at(diagonal - 1) < at(diagonal + 1))
+        {
+            true => diagonal + 1,
+            false => diagonal - 1,
+        };
+        let previous_x = at(previous);
+        let previous_y = previous_x - previous;
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            lines.push(DiffLine::Same(old[x as usize].clone()));
+        }
+        if depth > 0 {
+            match x == previous_x {
+                true => lines.push(DiffLine::Added(new[previous_y as usize].clone())),
+                false => lines.push(DiffLine::Removed(old[previous_x as usize].clone())),
+            }
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    lines.reverse();
+    lines
+}
+
+/// Functions keyed by name; repeated names (static functions of different
+/// translation units) are told apart by their order in the listing.
+fn by_name(listing: &ObjDumpListing) -> HashMap<(&str, usize), &ObjDumpFunction> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    listing
+        .functions
+        .iter()
+        .map(|function| {
+            let count = seen.entry(&function.name).or_default();
+            *count += 1;
+            ((function.name.as_str(), *count), function)
+        })
+        .collect()
+}
+
+impl ListingDiff {
+    /// Matches the functions of two listings by symbol name and diffs
+    /// their normalized instructions, so that code which only moved or was
+    /// aligned differently does not show up as changed.
+    pub fn build(old: &ObjDumpListing, new: &ObjDumpListing) -> Self {
+        let mut diff = ListingDiff::default();
+        let (old_functions, new_functions) = (by_name(old), by_name(new));
+        let mut keys: Vec<_> = old_functions.keys().copied().collect();
+        keys.sort_by_key(|key| old_functions[key].address);
+        let mut added: Vec<_> = new_functions
+            .keys()
+            .filter(|key| !old_functions.contains_key(key))
+            .copied()
+            .collect();
+        added.sort_by_key(|key| new_functions[key].address);
+        keys.extend(added);
+
+        for key in keys {
+            let old_function = old_functions.get(&key);
+            let new_function = new_functions.get(&key);
+            let normalized = |function: &&ObjDumpFunction| Code::new(function).normalized();
+            let old_lines = old_function.map_or(Vec::new(), normalized);
+            let new_lines = new_function.map_or(Vec::new(), normalized);
+            let change = match (old_function, new_function) {
+                (Some(_), None) => FunctionChange::Removed,
+                (None, Some(_)) => FunctionChange::Added,
+                _ if old_lines == new_lines => {
+                    diff.unchanged += 1;
+                    continue;
+                }
+                _ => FunctionChange::Modified,
+            };
+            let lines = edit_script(&old_lines, &new_lines);
+            let (old_size, old_padding) = old_function.map_or((0, 0), |function| sizes(function));
+            let (new_size, new_padding) = new_function.map_or((0, 0), |function| sizes(function));
+            diff.functions.push(FunctionDiff {
+                name: key.0.to_string(),
+                change,
+                old_size,
+                new_size,
+                old_padding,
+                new_padding,
+                added: lines
+                    .iter()
+                    .filter(|line| matches!(line, DiffLine::Added(_)))
+                    .count(),
+                removed: lines
+                    .iter()
+                    .filter(|line| matches!(line, DiffLine::Removed(_)))
+                    .count(),
+                lines,
+            });
+        }
+        diff
+    }
+}
+
+impl FunctionDiff {
+    pub fn size_delta(&self) -> i64 {
+        self.new_size as i64 - self.old_size as i64
+    }
+
+    /// Writes the changed instructions as unified-diff hunks with `context`
+    /// unchanged instructions around each change. Hunk headers count
+    /// instructions, not text lines.
+    pub fn write_unified<W: Write>(&self, out: &mut W, context: usize) -> io::Result<()> {
+        let changed: Vec<usize> = (0..self.lines.len())
+            .filter(|&index| !matches!(self.lines[index], DiffLine::Same(_)))
+            .collect();
+        let mut index = 0;
+        while index < changed.len() {
+            let start = changed[index].saturating_sub(context);
+            let mut stop = changed[index];
+            while index < changed.len() && changed[index] <= stop + 2 * context + 1 {
+                stop = changed[index];
+                index += 1;
+            }
+            let stop = (stop + context + 1).min(self.lines.len());
+
+            let (mut old_start, mut new_start) = (1, 1);
+            for line in &self.lines[..start] {
+                match line {
+                    DiffLine::Same(_) => (old_start, new_start) = (old_start + 1, new_start + 1),
+                    DiffLine::Removed(_) => old_start += 1,
+                    DiffLine::Added(_) => new_start += 1,
+                }
+            }
+            let hunk = &self.lines[start..stop];
+            let old_count = hunk
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Added(_)))
+                .count();
+            let new_count = hunk
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Removed(_)))
+                .count();
+            writeln!(
+                out,
+                "@@ -{},{} +{},{} @@ {}",
+                old_start, old_count, new_start, new_count, self.name
+            )?;
+            for line in hunk {
+                match line {
+                    DiffLine::Same(text) => writeln!(out, " {}", text)?,
+                    DiffLine::Removed(text) => writeln!(out, "-{}", text)?,
+                    DiffLine::Added(text) => writeln!(out, "+{}", text)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn edit_script_is_minimal() {
+        let script = edit_script(&lines("a b c d"), &lines("a c d e"));
+        assert_eq!(
+            script,
+            [
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Same("d".to_string()),
+                DiffLine::Added("e".to_string()),
+            ]
+        );
+        assert_eq!(edit_script(&[], &[]), []);
+        assert_eq!(
+            edit_script(&[], &lines("a")),
+            [DiffLine::Added("a".to_string())]
+        );
+        assert_eq!(
+            edit_script(&lines("a b"), &[]),
+            [
+                DiffLine::Removed("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn edit_script_replaces_everything_past_the_limit() {
+        let old: Vec<String> = (0..MAX_EDITS).map(|line| format!("old {}", line)).collect();
+        let new: Vec<String> = (0..MAX_EDITS).map(|line| format!("new {}", line)).collect();
+        let script = edit_script(&old, &new);
+        assert_eq!(script.len(), 2 * MAX_EDITS as usize);
+        assert!(script[..MAX_EDITS as usize]
+            .iter()
+            .all(|line| matches!(line, DiffLine::Removed(_))));
+    }
+
+    /// `f` returns 1 unless `edi` is zero; `g` only calls `f`.
+    const OLD: &str = "
+0000000000401000 <f>:
+  401000:\t85 ff                \ttest   %edi,%edi
+  401002:\t74 05                \tje     401009 <f+0x9>
+  401004:\tb8 01 00 00 00       \tmov    $0x1,%eax
+  401009:\tc3                   \tret
+
+000000000040100a <g>:
+  40100a:\te8 f1 ff ff ff       \tcall   401000 <f>
+  40100f:\tc3                   \tret
+";
+
+    /// The same code, moved.
+    const MOVED: &str = "
+0000000000402000 <f>:
+  402000:\t85 ff                \ttest   %edi,%edi
+  402002:\t74 05                \tje     402009 <f+0x9>
+  402004:\tb8 01 00 00 00       \tmov    $0x1,%eax
+  402009:\tc3                   \tret
+
+000000000040200a <g>:
+  40200a:\te8 f1 ff ff ff       \tcall   402000 <f>
+  40200f:\tc3                   \tret
+";
+
+    /// `je` retargeted to the `mov`, and `g` replaced by `h`.
+    const RETARGETED: &str = "
+0000000000401000 <f>:
+  401000:\t85 ff                \ttest   %edi,%edi
+  401002:\t74 00                \tje     401004 <f+0x4>
+  401004:\tb8 01 00 00 00       \tmov    $0x1,%eax
+  401009:\tc3                   \tret
+
+000000000040100a <h>:
+  40100a:\tc3                   \tret
+";
+
+    #[test]
+    fn moved_code_is_unchanged() {
+        let old = ObjDumpListing::parse(OLD.as_bytes()).unwrap();
+        let new = ObjDumpListing::parse(MOVED.as_bytes()).unwrap();
+        let diff = ListingDiff::build(&old, &new);
+        assert!(diff.functions.is_empty());
+        assert_eq!(diff.unchanged, 2);
+        let f = &old.functions[0];
+        assert_eq!(normalize(&f.instructions[1], &Code::new(f)), "je     <.L3>");
+    }
+
+    /// The same code with its branch target and functions aligned.
+    const ALIGNED: &str = "
+0000000000401000 <f>:
+  401000:\t85 ff                \ttest   %edi,%edi
+  401002:\t74 08                \tje     40100c <f+0xc>
+  401004:\tb8 01 00 00 00       \tmov    $0x1,%eax
+  401009:\t0f 1f 00             \tnopl   (%rax)
+  40100c:\tc3                   \tret
+  40100d:\t0f 1f 00             \tnopl   (%rax)
+  401010:\t66 0f 1f 44 00 00    \tnopw   0x0(%rax,%rax,1)
+  401016:\t0f 1f 40 00          \tnopl   0x0(%rax)
+  40101a:\t66 0f 1f 44 00 00    \tnopw   0x0(%rax,%rax,1)
+
+0000000000401020 <g>:
+  401020:\te8 db ff ff ff       \tcall   401000 <f>
+  401025:\tc3                   \tret
+  401026:\tcc                   \tint3
+  401027:\t66 90                \txchg   %ax,%ax
+";
+
+    #[test]
+    fn alignment_padding_is_unchanged() {
+        let old = ObjDumpListing::parse(OLD.as_bytes()).unwrap();
+        let new = ObjDumpListing::parse(ALIGNED.as_bytes()).unwrap();
+        let diff = ListingDiff::build(&old, &new);
+        assert!(diff.functions.is_empty());
+        assert_eq!(diff.unchanged, 2);
+        assert_eq!(sizes(&new.functions[0]), (10, 22));
+        assert_eq!(sizes(&new.functions[1]), (6, 3));
+
+        let f = &new.functions[0];
+        assert_eq!(normalize(&f.instructions[1], &Code::new(f)), "je     <.L3>");
+    }
+
+    #[test]
+    fn retargeted_branches_are_changes() {
+        let old = ObjDumpListing::parse(OLD.as_bytes()).unwrap();
+        let new = ObjDumpListing::parse(RETARGETED.as_bytes()).unwrap();
+        let diff = ListingDiff::build(&old, &new);
+        let changes: Vec<(&str, FunctionChange)> = diff
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("f", FunctionChange::Modified),
+                ("g", FunctionChange::Removed),
+                ("h", FunctionChange::Added),
+            ]
+        );
+        let f = &diff.functions[0];
+        assert_eq!((f.removed, f.added), (1, 1));
+        assert!(f
+            .lines
+            .contains(&DiffLine::Removed("je     <.L3>".to_string())));
+        assert!(f
+            .lines
+            .contains(&DiffLine::Added("je     <.L2>".to_string())));
+
+        let mut out = Vec::new();
+        f.write_unified(&mut out, 1).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "@@ -1,3 +1,3 @@ f\n",
+                " test   %edi,%edi\n",
+                "-je     <.L3>\n",
+                "+je     <.L2>\n",
+                " mov    $0x1,%eax\n",
+            )
+        );
+    }
+}
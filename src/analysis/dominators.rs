@@ -0,0 +1,119 @@
+use crate::analysis::cfg::ControlFlowGraph;
+
+/// Immediate dominators of the blocks of a [`ControlFlowGraph`], computed with
+/// the iterative algorithm of Cooper, Harvey and Kennedy. Blocks unreachable
+/// from the entry have no dominator.
+#[derive(Debug)]
+pub struct DominatorTree {
+    idom: Vec<Option<usize>>,
+}
+
+impl DominatorTree {
+    pub fn build(cfg: &ControlFlowGraph) -> Self {
+        let order = cfg.reverse_postorder();
+        let mut rank = vec![usize::MAX; cfg.blocks.len()];
+        for (position, &block) in order.iter().enumerate() {
+            rank[block] = position;
+        }
+
+        let mut idom = vec![None; cfg.blocks.len()];
+        if let Some(&entry) = order.first() {
+            idom[entry] = Some(entry);
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in order.iter().skip(1) {
+                let mut new_idom = None;
+                for &predecessor in &cfg.blocks[block].predecessors {
+                    if idom[predecessor].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => predecessor,
+                        Some(current) => intersect(&idom, &rank, predecessor, current),
+                    });
+                }
+                if new_idom.is_some() && idom[block] != new_idom {
+                    idom[block] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        DominatorTree { idom }
+    }
+
+    pub fn immediate_dominator(&self, block: usize) -> Option<usize> {
+        match self.idom[block] {
+            Some(idom) if idom != block => Some(idom),
+            _ => None,
+        }
+    }
+
+    pub fn dominates(&self, dominator: usize, mut block: usize) -> bool {
+        if self.idom[block].is_none() {
+            return false;
+        }
+        loop {
+            if block == dominator {
+                return true;
+            }
+            match self.immediate_dominator(block) {
+                Some(idom) => block = idom,
+                None => return false,
+            }
+        }
+    }
+}
+
+fn intersect(idom: &[Option<usize>], rank: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while rank[a] > rank[b] {
+            a = idom[a].unwrap();
+        }
+        while rank[b] > rank[a] {
+            b = idom[b].unwrap();
+        }
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objdump::listing::ObjDumpListing;
+
+    /// `if (eax == 0) ecx = 1; else ecx = 2; return;`, followed by a block
+    /// nothing branches to.
+    const DIAMOND: &str = "
+0000000000401000 <f>:
+  401000:\t85 c0                \ttest   %eax,%eax
+  401002:\t74 07                \tje     40100b <f+0xb>
+  401004:\tb9 02 00 00 00       \tmov    $0x2,%ecx
+  401009:\teb 05                \tjmp    401010 <f+0x10>
+  40100b:\tb9 01 00 00 00       \tmov    $0x1,%ecx
+  401010:\tc3                   \tret
+  401011:\t90                   \tnop
+";
+
+    #[test]
+    fn diamond() {
+        let listing = ObjDumpListing::parse(DIAMOND.as_bytes()).unwrap();
+        let cfg = ControlFlowGraph::build(&listing.functions[0]);
+        assert_eq!(cfg.blocks.len(), 5);
+        let dominators = DominatorTree::build(&cfg);
+        assert_eq!(dominators.immediate_dominator(0), None);
+        assert_eq!(dominators.immediate_dominator(1), Some(0));
+        assert_eq!(dominators.immediate_dominator(2), Some(0));
+        // The join is dominated by the entry, not by either arm.
+        assert_eq!(dominators.immediate_dominator(3), Some(0));
+        assert!(!dominators.dominates(1, 3));
+        assert!(dominators.dominates(0, 3));
+        assert!(dominators.dominates(3, 3));
+        // The trailing `nop` is unreachable.
+        assert_eq!(dominators.immediate_dominator(4), None);
+        assert!(!dominators.dominates(0, 4));
+    }
+}
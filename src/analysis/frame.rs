@@ -0,0 +1,500 @@
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::x8664_att::{
+    X8664ATTAddress, X8664ATTFlow, X8664ATTInstruction, X8664ATTOperand, X8664ATTRegister,
+    X8664ATTValue,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const CALLEE_SAVED: [&str; 6] = ["%rbx", "%rbp", "%r12", "%r13", "%r14", "%r15"];
+
+/// A stack location the function accesses, relative to the canonical frame
+/// address (the value of `%rsp` before the call): the return address is at
+/// -8 and incoming stack arguments start at 0.
+#[derive(Debug, Serialize)]
+pub struct StackSlot {
+    pub offset: i64,
+    /// Access widths in bytes, smallest first. Empty when only the address
+    /// of the slot is used.
+    pub widths: Vec<u32>,
+    pub reads: usize,
+    pub writes: usize,
+    /// Whether the slot's address is taken with `lea`.
+    pub address_taken: bool,
+    /// Whether the slot is the base of an indexed access, i.e. an array.
+    pub indexed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StackFrame {
+    pub name: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    /// Stack consumed per call in bytes, return address included, as
+    /// `gcc -fstack-usage` counts it: `red_zone` and dynamic allocations come
+    /// on top.
+    pub size: u64,
+    /// Bytes reserved by `sub $n,%rsp` before the first call.
+    pub allocation: u64,
+    /// Callee-saved registers pushed by the prologue, in push order.
+    pub saved_registers: Vec<String>,
+    /// Whether `%rbp` is set up as frame pointer.
+    pub frame_pointer: bool,
+    /// Whether `%rsp` is also moved by a non-constant amount (`alloca`,
+    /// variable-length arrays, stack realignment).
+    pub dynamic: bool,
+    /// Bytes accessed below `%rsp` without allocating them.
+    pub red_zone: u64,
+    pub slots: Vec<StackSlot>,
+}
+
+impl StackFrame {
+    /// Slot offset as `CFA-0x18`.
+    pub fn slot_name(slot: &StackSlot) -> String {
+        match slot.offset {
+            offset if offset < 0 => format!("CFA-{:#x}", offset.unsigned_abs()),
+            offset => format!("CFA+{:#x}", offset),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FrameReport {
+    pub frames: Vec<StackFrame>,
+}
+
+impl FrameReport {
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        FrameReport {
+            frames: listing
+                .functions
+                .iter()
+                .filter(|function| !function.instructions.is_empty())
+                .map(StackFrame::build)
+                .collect(),
+        }
+    }
+}
+
+fn register(operand: Option<&X8664ATTOperand>) -> Option<&str> {
+    match operand {
+        Some(X8664ATTOperand::Register(register)) => Some(register.name()),
+        _ => None,
+    }
+}
+
+fn immediate(operand: Option<&X8664ATTOperand>) -> Option<i64> {
+    match operand {
+        Some(X8664ATTOperand::Immediate(immediate)) => Some(immediate.0 as i64),
+        _ => None,
+    }
+}
+
+fn based_on(address: &X8664ATTAddress, name: &str) -> bool {
+    address.segment.is_none()
+        && matches!(&address.base, Some(X8664ATTValue::Register(register)) if register.name() == name)
+}
+
+/// Size in bytes of a general purpose or vector register.
+fn register_width(register: &X8664ATTRegister) -> Option<u32> {
+    if let Some(width) = register.vector_width() {
+        return Some(width / 8);
+    }
+    if register.is_mmx() {
+        return Some(8);
+    }
+    let name = register.name().trim_start_matches('%');
+    if let Some(number) = name.strip_prefix('r') {
+        if number.parse::<u8>().is_ok() {
+            return Some(8);
+        }
+        return match number.chars().last() {
+            Some('d') => Some(4),
+            Some('w') => Some(2),
+            Some('b') => Some(1),
+            _ => Some(8),
+        };
+    }
+    match name {
+        "eax" | "ebx" | "ecx" | "edx" | "esi" | "edi" | "ebp" | "esp" => Some(4),
+        "ax" | "bx" | "cx" | "dx" | "si" | "di" | "bp" | "sp" => Some(2),
+        "al" | "ah" | "bl" | "bh" | "cl" | "ch" | "dl" | "dh" | "sil" | "dil" | "bpl" | "spl" => {
+            Some(1)
+        }
+        _ => None,
+    }
+}
+
+fn suffix_width(suffix: char) -> Option<u32> {
+    match suffix {
+        'b' => Some(1),
+        'w' => Some(2),
+        'l' => Some(4),
+        'q' => Some(8),
+        _ => None,
+    }
+}
+
+/// Bytes of memory the instruction's memory operand covers, from the
+/// operand-size suffix, the scalar SSE form or the register operand.
+pub(crate) fn access_width(instruction: &X8664ATTInstruction) -> Option<u32> {
+    let mnemonic = instruction.opcode.mnemonic();
+    let operation = instruction.opcode.operation();
+    match operation {
+        // `movzbl`: the memory source is the first size.
+        "movzx" | "movsx" => return mnemonic[4..].chars().next().and_then(suffix_width),
+        "movsxd" => return Some(4),
+        _ => {}
+    }
+    if mnemonic != operation {
+        return mnemonic.chars().last().and_then(suffix_width);
+    }
+    let scalar = operation.strip_prefix('v').unwrap_or(operation);
+    match scalar {
+        "movd" => return Some(4),
+        "movq" | "movlps" | "movhps" | "movlpd" | "movhpd" => return Some(8),
+        _ if !scalar.starts_with('p') && scalar.ends_with("ss") => return Some(4),
+        _ if !scalar.starts_with('p') && scalar.ends_with("sd") => return Some(8),
+        _ => {}
+    }
+    instruction
+        .operands
+        .iter()
+        .find_map(|operand| match operand {
+            X8664ATTOperand::Register(register) => register_width(register),
+            X8664ATTOperand::Decorated(operand, _) => match &**operand {
+                X8664ATTOperand::Register(register) => register_width(register),
+                _ => None,
+            },
+            _ => None,
+        })
+        .or_else(|| instruction.vector_width().map(|width| width / 8))
+}
+
+/// Stack pointer tracking while walking a function in address order.
+struct Walk {
+    /// Bytes between the CFA and `%rsp`.
+    depth: i64,
+    /// Deepest constant `depth` reached so far.
+    deepest: i64,
+    /// `depth` at which `%rbp` was set to `%rsp`.
+    rbp: Option<i64>,
+    /// Cleared while `%rsp` is off by a dynamic amount.
+    known: bool,
+    called: bool,
+}
+
+impl Walk {
+    fn lose(&mut self, frame: &mut StackFrame) {
+        frame.dynamic = true;
+        self.known = false;
+    }
+
+    fn restore(&mut self, depth: i64) {
+        self.depth = depth;
+        self.known = true;
+    }
+}
+
+impl StackFrame {
+    /// Recovers the frame by walking the function in address order, tracking
+    /// `%rsp` through pushes, pops, `sub`/`add`, `leave` and `%rbp` restores.
+    /// After a `ret` or tail jump, the walk resumes at the deepest frame seen,
+    /// which is where code following an epilogue normally runs.
+    pub fn build(function: &ObjDumpFunction) -> Self {
+        let mut walk = Walk {
+            depth: 8,
+            deepest: 8,
+            rbp: None,
+            known: true,
+            called: false,
+        };
+        let mut frame = StackFrame {
+            name: function.name.clone(),
+            address: function.address,
+            size: 0,
+            allocation: 0,
+            saved_registers: Vec::new(),
+            frame_pointer: false,
+            dynamic: false,
+            red_zone: 0,
+            slots: Vec::new(),
+        };
+        let mut slots: BTreeMap<i64, StackSlot> = BTreeMap::new();
+        // Lowest CFA offset accessed, to find red zone use.
+        let mut lowest = 0;
+
+        for line in &function.instructions {
+            let instruction = &line.instruction;
+            let operands = &instruction.operands;
+            let operation = instruction.opcode.operation();
+            let destination = register(operands.last());
+
+            for (index, operand) in operands.iter().enumerate() {
+                let address = match operand {
+                    X8664ATTOperand::Address(address) => address,
+                    X8664ATTOperand::Decorated(operand, _) => match &**operand {
+                        X8664ATTOperand::Address(address) => address,
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                let base = match walk.rbp {
+                    _ if based_on(address, "%rsp") && walk.known => walk.depth,
+                    _ if based_on(address, "%rsp") => continue,
+                    Some(rbp) if based_on(address, "%rbp") => rbp,
+                    _ => continue,
+                };
+                let offset = address.displacement.as_ref().map_or(0, |value| value.0) - base;
+                let lea = operation == "lea";
+                let slot = slots.entry(offset).or_insert(StackSlot {
+                    offset,
+                    widths: Vec::new(),
+                    reads: 0,
+                    writes: 0,
+                    address_taken: false,
+                    indexed: false,
+                });
+                slot.indexed |= address.offset.is_some();
+                if !slot.indexed {
+                    lowest = lowest.min(offset);
+                }
+                if lea {
+                    slot.address_taken = true;
+                    continue;
+                }
+                let width = access_width(instruction);
+                if let Some(width) = width {
+                    if !slot.widths.contains(&width) {
+                        slot.widths.push(width);
+                        slot.widths.sort();
+                    }
+                }
+                let is_destination = index + 1 == operands.len() && operands.len() > 1;
+                let write_only = operation.starts_with("mov")
+                    || operation.starts_with("vmov")
+                    || operation.starts_with("set");
+                match is_destination {
+                    true if write_only => slot.writes += 1,
+                    true if matches!(operation, "cmp" | "test") => slot.reads += 1,
+                    true => {
+                        slot.reads += 1;
+                        slot.writes += 1;
+                    }
+                    false => slot.reads += 1,
+                }
+            }
+
+            match operation {
+                "push" => {
+                    let saving = !walk.called && walk.depth == walk.deepest;
+                    walk.depth += 8;
+                    if let Some(name) = register(operands.first()) {
+                        if saving && CALLEE_SAVED.contains(&name) {
+                            frame.saved_registers.push(name.to_string());
+                        }
+                    }
+                }
+                "pop" => walk.depth -= 8,
+                "leave" => {
+                    if let Some(rbp) = walk.rbp {
+                        walk.restore(rbp - 8);
+                    }
+                }
+                "sub" | "add" if destination == Some("%rsp") => match immediate(operands.first()) {
+                    Some(amount) => {
+                        // Compilers also allocate with `add $-0x80,%rsp`,
+                        // whose immediate fits in a byte where 0x80 doesn't.
+                        let amount = match operation {
+                            "sub" => amount,
+                            _ => amount.wrapping_neg(),
+                        };
+                        walk.depth += amount;
+                        if !walk.called && amount > 0 {
+                            frame.allocation += amount as u64;
+                        }
+                    }
+                    None => walk.lose(&mut frame),
+                },
+                "and" if destination == Some("%rsp") => walk.lose(&mut frame),
+                "mov"
+                    if destination == Some("%rbp")
+                        && register(operands.first()) == Some("%rsp") =>
+                {
+                    walk.rbp = Some(walk.depth);
+                    frame.frame_pointer = true;
+                }
+                "mov" if destination == Some("%rsp") => {
+                    match (register(operands.first()), walk.rbp) {
+                        (Some("%rbp"), Some(rbp)) => walk.restore(rbp),
+                        _ => walk.lose(&mut frame),
+                    }
+                }
+                "lea" if destination == Some("%rsp") => match operands.first() {
+                    Some(X8664ATTOperand::Address(address)) if based_on(address, "%rbp") => {
+                        match walk.rbp {
+                            Some(rbp) => walk.restore(
+                                rbp - address.displacement.as_ref().map_or(0, |value| value.0),
+                            ),
+                            None => walk.lose(&mut frame),
+                        }
+                    }
+                    Some(X8664ATTOperand::Address(address)) if based_on(address, "%rsp") => {
+                        walk.depth -= address.displacement.as_ref().map_or(0, |value| value.0)
+                    }
+                    _ => walk.lose(&mut frame),
+                },
+                _ => {}
+            }
+            // Anything else that writes `%rsp` moves it by an unknown amount.
+            if destination == Some("%rsp")
+                && !matches!(
+                    operation,
+                    "sub" | "add" | "and" | "mov" | "lea" | "push" | "pop"
+                )
+                && operands.len() > 1
+            {
+                walk.lose(&mut frame);
+            }
+            if walk.known {
+                walk.deepest = walk.deepest.max(walk.depth);
+            }
+
+            match instruction.opcode.flow() {
+                X8664ATTFlow::Call => walk.called = true,
+                X8664ATTFlow::Return | X8664ATTFlow::Jump | X8664ATTFlow::Halt => {
+                    walk.restore(walk.deepest)
+                }
+                _ => {}
+            }
+        }
+
+        frame.red_zone = (-lowest - walk.deepest).max(0) as u64;
+        frame.size = walk.deepest as u64;
+        frame.slots = slots.into_values().collect();
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(dump: &str) -> StackFrame {
+        let listing = ObjDumpListing::parse(dump.as_bytes()).unwrap();
+        StackFrame::build(&listing.functions[0])
+    }
+
+    #[test]
+    fn rbp_frame() {
+        let frame = frame(
+            "
+0000000000401000 <f>:
+  401000:\t55                   \tpush   %rbp
+  401001:\t48 89 e5             \tmov    %rsp,%rbp
+  401004:\t48 83 ec 20          \tsub    $0x20,%rsp
+  401008:\t89 7d ec             \tmov    %edi,-0x14(%rbp)
+  40100b:\t8b 45 ec             \tmov    -0x14(%rbp),%eax
+  40100e:\tc9                   \tleave
+  40100f:\tc3                   \tret
+",
+        );
+        assert!(frame.frame_pointer);
+        assert!(!frame.dynamic);
+        assert_eq!(frame.saved_registers, ["%rbp"]);
+        assert_eq!(frame.allocation, 0x20);
+        assert_eq!(frame.size, 0x30);
+        assert_eq!(frame.red_zone, 0);
+        assert_eq!(frame.slots.len(), 1);
+        let slot = &frame.slots[0];
+        assert_eq!(StackFrame::slot_name(slot), "CFA-0x24");
+        assert_eq!(slot.widths, [4]);
+        assert_eq!((slot.reads, slot.writes), (1, 1));
+        assert!(!slot.address_taken);
+    }
+
+    #[test]
+    fn rsp_only_frame() {
+        let frame = frame(
+            "
+0000000000401000 <f>:
+  401000:\t53                   \tpush   %rbx
+  401001:\t48 83 ec 10          \tsub    $0x10,%rsp
+  401005:\t48 89 7c 24 08       \tmov    %rdi,0x8(%rsp)
+  40100a:\te8 f1 00 00 00       \tcall   401100 <g>
+  40100f:\t48 8b 44 24 08       \tmov    0x8(%rsp),%rax
+  401014:\t48 83 c4 10          \tadd    $0x10,%rsp
+  401018:\t5b                   \tpop    %rbx
+  401019:\tc3                   \tret
+",
+        );
+        assert!(!frame.frame_pointer);
+        assert!(!frame.dynamic);
+        assert_eq!(frame.saved_registers, ["%rbx"]);
+        assert_eq!(frame.allocation, 0x10);
+        assert_eq!(frame.size, 0x20);
+        assert_eq!(frame.slots.len(), 1);
+        let slot = &frame.slots[0];
+        assert_eq!(StackFrame::slot_name(slot), "CFA-0x18");
+        assert_eq!(slot.widths, [8]);
+        assert_eq!((slot.reads, slot.writes), (1, 1));
+    }
+
+    #[test]
+    fn leaf_functions_use_the_red_zone() {
+        let frame = frame(
+            "
+0000000000401000 <f>:
+  401000:\t89 7c 24 fc          \tmov    %edi,-0x4(%rsp)
+  401004:\t8b 44 24 fc          \tmov    -0x4(%rsp),%eax
+  401008:\tc3                   \tret
+",
+        );
+        assert_eq!(frame.size, 8);
+        assert_eq!(frame.red_zone, 4);
+        assert_eq!(StackFrame::slot_name(&frame.slots[0]), "CFA-0xc");
+    }
+
+    #[test]
+    fn lea_takes_the_address_of_a_slot() {
+        for mnemonic in ["lea", "leaq"] {
+            let frame = frame(&format!(
+                "
+0000000000401000 <f>:
+  401000:\t55                   \tpush   %rbp
+  401001:\t48 89 e5             \tmov    %rsp,%rbp
+  401004:\t48 83 ec 10          \tsub    $0x10,%rsp
+  401008:\t48 8d 7d f0          \t{:<6} -0x10(%rbp),%rdi
+  40100c:\te8 ef 00 00 00       \tcall   401100 <g>
+  401011:\tc9                   \tleave
+  401012:\tc3                   \tret
+",
+                mnemonic
+            ));
+            assert_eq!(frame.slots.len(), 1, "{}", mnemonic);
+            let slot = &frame.slots[0];
+            assert_eq!(StackFrame::slot_name(slot), "CFA-0x20", "{}", mnemonic);
+            assert!(slot.address_taken, "{}", mnemonic);
+            assert!(slot.widths.is_empty(), "{}", mnemonic);
+            assert_eq!((slot.reads, slot.writes), (0, 0), "{}", mnemonic);
+        }
+    }
+
+    #[test]
+    fn negative_adds_allocate() {
+        let frame = frame(
+            "
+0000000000401000 <f>:
+  401000:\t48 83 c4 80          \tadd    $0xffffffffffffff80,%rsp
+  401004:\t48 89 3c 24          \tmov    %rdi,(%rsp)
+  401008:\te8 f3 00 00 00       \tcall   401100 <g>
+  40100d:\t48 83 ec 80          \tsub    $0xffffffffffffff80,%rsp
+  401011:\tc3                   \tret
+",
+        );
+        assert!(!frame.dynamic);
+        assert_eq!(frame.allocation, 0x80);
+        assert_eq!(frame.size, 0x88);
+        assert_eq!(StackFrame::slot_name(&frame.slots[0]), "CFA-0x88");
+    }
+}
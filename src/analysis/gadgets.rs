@@ -0,0 +1,386 @@
+use crate::objdump::decode::X8664Decoder;
+use crate::objdump::listing::ObjDumpListing;
+use crate::objdump::x8664_att::{X8664ATTFlow, X8664ATTInstruction, X8664ATTOperand};
+use crate::ObjumpError;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// Bytes searched backwards from a gadget's final instruction.
+const WINDOW: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GadgetKind {
+    /// Ends in `ret` (ROP).
+    Return,
+    /// Ends in `jmp *%reg` or `call *%reg` (JOP/COP).
+    Indirect,
+    /// Ends in `syscall`.
+    Syscall,
+}
+
+impl GadgetKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GadgetKind::Return => "rop",
+            GadgetKind::Indirect => "jop",
+            GadgetKind::Syscall => "sys",
+        }
+    }
+
+    fn of(instruction: &X8664ATTInstruction) -> Option<GadgetKind> {
+        let operation = instruction.opcode.operation();
+        match instruction.opcode.flow() {
+            X8664ATTFlow::Return if matches!(operation, "ret" | "retw") => Some(GadgetKind::Return),
+            X8664ATTFlow::Jump | X8664ATTFlow::Call => match instruction.operands.first() {
+                Some(X8664ATTOperand::Indirect(operand))
+                    if matches!(**operand, X8664ATTOperand::Register(_)) =>
+                {
+                    Some(GadgetKind::Indirect)
+                }
+                _ => None,
+            },
+            _ if operation == "syscall" => Some(GadgetKind::Syscall),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Gadget {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub kind: GadgetKind,
+    /// Whether the gadget starts inside an instruction of the listing.
+    pub unaligned: bool,
+    pub instructions: Vec<String>,
+    /// Other addresses with the same instruction sequence.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "crate::objdump::hex::addresses"
+    )]
+    pub duplicates: Vec<u64>,
+}
+
+impl Gadget {
+    /// Instructions joined with ` ; ` as gadget tools print them.
+    pub fn text(&self) -> String {
+        self.instructions.join(" ; ")
+    }
+}
+
+/// Single-spaced text of an instruction, the form filters match against.
+fn render(instruction: &X8664ATTInstruction) -> String {
+    instruction
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Contiguous code of the listing with the addresses instructions start at.
+struct Region {
+    address: u64,
+    bytes: Vec<u8>,
+}
+
+fn regions(listing: &ObjDumpListing) -> (Vec<Region>, HashSet<u64>) {
+    let mut regions: Vec<Region> = Vec::new();
+    let mut starts = HashSet::new();
+    let mut lines: Vec<_> = listing
+        .functions
+        .iter()
+        .flat_map(|function| &function.instructions)
+        .filter(|line| !line.bytes.is_empty())
+        .collect();
+    lines.sort_by_key(|line| line.address);
+    for line in lines {
+        starts.insert(line.address);
+        match regions.last_mut() {
+            Some(region) if region.address + region.bytes.len() as u64 == line.address => {
+                region.bytes.extend(&line.bytes)
+            }
+            _ => regions.push(Region {
+                address: line.address,
+                bytes: line.bytes.clone(),
+            }),
+        }
+    }
+    (regions, starts)
+}
+
+/// Whether the bytes at the start of `bytes` can encode a gadget's final
+/// instruction: `ret`, `ret $imm`, `ff /2` or `ff /4` on a register
+/// (optionally after REX or `notrack`/`bnd`/`repz`) or `syscall`.
+fn may_end_gadget(bytes: &[u8]) -> bool {
+    let mut index = 0;
+    while index < bytes.len().min(3) && matches!(bytes[index], 0x3e | 0xf2 | 0xf3 | 0x40..=0x4f) {
+        index += 1;
+    }
+    match bytes.get(index..) {
+        Some([0xc3 | 0xc2, ..]) => true,
+        Some([0xff, modrm, ..]) => modrm >> 6 == 3 && matches!((modrm >> 3) & 7, 2 | 4),
+        Some([0x0f, 0x05, ..]) => true,
+        _ => false,
+    }
+}
+
+pub struct GadgetFinder {
+    /// Maximum number of instructions, the final one included.
+    pub depth: usize,
+    pub unaligned: bool,
+}
+
+impl Default for GadgetFinder {
+    fn default() -> Self {
+        GadgetFinder {
+            depth: 6,
+            unaligned: true,
+        }
+    }
+}
+
+impl GadgetFinder {
+    /// Finds every instruction sequence that falls through into a `ret`,
+    /// register-indirect `jmp`/`call` or `syscall`, starting at any byte of
+    /// the listing's raw bytes (only at instruction starts when `unaligned`
+    /// is off). Identical sequences are reported once, at their lowest
+    /// address, with the others listed as duplicates.
+    pub fn find(&self, listing: &ObjDumpListing) -> Vec<Gadget> {
+        let (regions, starts) = regions(listing);
+        let mut decoder = X8664Decoder::new();
+        let mut unique: BTreeMap<Vec<String>, Gadget> = BTreeMap::new();
+        for region in &regions {
+            for end in 0..region.bytes.len() {
+                if !may_end_gadget(&region.bytes[end..]) {
+                    continue;
+                }
+                let (last, _) = decoder.decode(&region.bytes[end..], region.address + end as u64);
+                let Some(kind) = GadgetKind::of(&last) else {
+                    continue;
+                };
+                let last = render(&last);
+                for start in (end.saturating_sub(WINDOW)..=end).rev() {
+                    let address = region.address + start as u64;
+                    let unaligned = !starts.contains(&address);
+                    if unaligned && !self.unaligned {
+                        continue;
+                    }
+                    let Some(mut instructions) =
+                        self.body(&mut decoder, &region.bytes[start..end], address)
+                    else {
+                        continue;
+                    };
+                    instructions.push(last.clone());
+                    match unique.get_mut(&instructions) {
+                        Some(gadget) => gadget.duplicates.push(address),
+                        None => {
+                            unique.insert(
+                                instructions.clone(),
+                                Gadget {
+                                    address,
+                                    kind,
+                                    unaligned,
+                                    instructions,
+                                    duplicates: Vec::new(),
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        let mut gadgets: Vec<Gadget> = unique.into_values().collect();
+        for gadget in gadgets.iter_mut() {
+            gadget.duplicates.push(gadget.address);
+            gadget.duplicates.sort();
+            gadget.address = gadget.duplicates.remove(0);
+            gadget.unaligned = !starts.contains(&gadget.address);
+        }
+        gadgets.sort_by_key(|gadget| gadget.address);
+        gadgets
+    }
+
+    /// Decodes `bytes` as straight-line code that ends exactly where the
+    /// final instruction begins, or `None` if it does not.
+    fn body(&self, decoder: &mut X8664Decoder, bytes: &[u8], address: u64) -> Option<Vec<String>> {
+        let mut instructions = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            if instructions.len() + 1 >= self.depth {
+                return None;
+            }
+            let (instruction, length) = decoder.decode(&bytes[offset..], address + offset as u64);
+            if instruction.opcode.flow() != X8664ATTFlow::Sequential
+                || instruction.is_bad()
+                || instruction.opcode.is_prefix()
+                || GadgetKind::of(&instruction).is_some()
+            {
+                return None;
+            }
+            instructions.push(render(&instruction));
+            offset += length;
+        }
+        Some(instructions)
+    }
+}
+
+/// One `;`-separated step of a [`GadgetFilter`].
+#[derive(Debug)]
+struct Clause {
+    glob: String,
+}
+
+impl Clause {
+    fn matches(&self, text: &str) -> bool {
+        glob(self.glob.as_bytes(), text.as_bytes())
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of characters
+/// and `?` any single one.
+pub(crate) fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob(&pattern[1..], text) || (!text.is_empty() && glob(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob(&pattern[1..], &text[1..]),
+        (Some(expected), Some(actual)) if expected == actual => glob(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+/// Filter on the instruction sequence of a gadget.
+///
+/// The filter is a list of instruction globs separated by `;`, matched
+/// against consecutive instructions of the gadget: `pop %rdi ; ret`. In a
+/// glob `*` matches any text and `?` any single character, and whitespace
+/// is insignificant beyond separating words. A leading `^` anchors the
+/// first clause to the gadget's first instruction and a trailing `$` the
+/// last clause to its final one. `...` as a clause skips any number of
+/// instructions: `^ pop * ; ... ; ret $`.
+#[derive(Debug)]
+pub struct GadgetFilter {
+    clauses: Vec<Option<Clause>>,
+    start: bool,
+    end: bool,
+}
+
+impl GadgetFilter {
+    pub fn parse(filter: &str) -> Result<Self, ObjumpError> {
+        let mut filter = filter.trim();
+        let start = filter.starts_with('^');
+        filter = filter.trim_start_matches('^');
+        let end = filter.ends_with('$');
+        filter = filter.trim_end_matches('$');
+        let clauses: Vec<Option<Clause>> = filter
+            .split(';')
+            .map(|clause| clause.split_whitespace().collect::<Vec<_>>().join(" "))
+            .map(|clause| match clause.as_str() {
+                "..." => None,
+                _ => Some(Clause { glob: clause }),
+            })
+            .collect();
+        if clauses
+            .iter()
+            .any(|clause| clause.as_ref().is_some_and(|clause| clause.glob.is_empty()))
+        {
+            return Err(ObjumpError::InvalidArgument(filter.to_string()));
+        }
+        Ok(GadgetFilter {
+            clauses,
+            start,
+            end,
+        })
+    }
+
+    pub fn matches(&self, gadget: &Gadget) -> bool {
+        let instructions = &gadget.instructions;
+        let last = match self.start {
+            true => 0,
+            false => instructions.len(),
+        };
+        (0..=last).any(|from| self.matches_at(&self.clauses, &instructions[from..]))
+    }
+
+    fn matches_at(&self, clauses: &[Option<Clause>], instructions: &[String]) -> bool {
+        match clauses.first() {
+            None => !self.end || instructions.is_empty(),
+            Some(None) => (0..=instructions.len())
+                .any(|skip| self.matches_at(&clauses[1..], &instructions[skip..])),
+            Some(Some(clause)) => {
+                instructions
+                    .first()
+                    .is_some_and(|instruction| clause.matches(instruction))
+                    && self.matches_at(&clauses[1..], &instructions[1..])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `pop %rdi ; repz ret` after a `mov` whose immediate hides `pop %rax ; ret`.
+    const LISTING: &str = "\
+0000000000401000 <f>:
+  401000:\tb8 58 c3 00 00       \tmov    $0xc358,%eax
+  401005:\t5f                   \tpop    %rdi
+  401006:\tf3 c3                \trepz ret
+";
+
+    fn gadgets(finder: &GadgetFinder) -> Vec<(u64, bool, String)> {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        finder
+            .find(&listing)
+            .iter()
+            .map(|gadget| (gadget.address, gadget.unaligned, gadget.text()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_gadgets_ending_in_repz_ret() {
+        let found = gadgets(&GadgetFinder {
+            unaligned: false,
+            ..Default::default()
+        });
+        assert!(found.contains(&(0x401005, false, "pop %rdi ; repz ret".to_string())));
+        assert!(found.contains(&(0x401006, false, "repz ret".to_string())));
+        assert!(found.iter().all(|(_, unaligned, _)| !unaligned));
+    }
+
+    #[test]
+    fn finds_unaligned_gadgets() {
+        let found = gadgets(&GadgetFinder::default());
+        assert!(found.contains(&(0x401001, true, "pop %rax ; ret".to_string())));
+        assert!(found.contains(&(0x401002, true, "ret".to_string())));
+    }
+
+    #[test]
+    fn filters_instruction_sequences() {
+        let gadget = |text: &str| Gadget {
+            address: 0,
+            kind: GadgetKind::Return,
+            unaligned: false,
+            instructions: text.split(" ; ").map(str::to_string).collect(),
+            duplicates: Vec::new(),
+        };
+        let matches =
+            |filter: &str, text: &str| GadgetFilter::parse(filter).unwrap().matches(&gadget(text));
+        assert!(matches("pop %rdi ; ret", "pop %rsi ; pop %rdi ; ret"));
+        assert!(matches("pop  %r?i", "pop %rdi ; ret"));
+        assert!(!matches("^ pop %rdi", "pop %rsi ; pop %rdi ; ret"));
+        assert!(matches(
+            "^ pop * ; ... ; ret $",
+            "pop %rsi ; xor %eax,%eax ; ret"
+        ));
+        assert!(!matches("pop %rdi $", "pop %rdi ; ret"));
+        assert!(matches(
+            "mov *,%rax ; ... ; jmp *",
+            "mov (%rdi),%rax ; jmp *%rax"
+        ));
+        assert!(GadgetFilter::parse("pop %rdi ; ; ret").is_err());
+    }
+}
@@ -0,0 +1,460 @@
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::x8664_att::{
+    X8664ATTFlow, X8664ATTInstruction, X8664ATTOpcode, X8664ATTOperand, X8664ATTValue,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Nothing in the function the check applies to.
+    NotApplicable,
+}
+
+impl CheckStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::NotApplicable => "-",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HardeningCheck {
+    /// `endbr64` as the first instruction.
+    Endbr,
+    /// `endbr64` at code addresses taken with a RIP-relative `lea`.
+    Targets,
+    /// A `%fs:0x28` canary load is matched by a `__stack_chk_fail` call.
+    Canary,
+    /// Indirect branches go through `__x86_indirect_thunk_*`.
+    Retpoline,
+    /// No indirect branch opts out of IBT with `notrack`.
+    Notrack,
+}
+
+impl HardeningCheck {
+    pub const ALL: [HardeningCheck; 5] = [
+        HardeningCheck::Endbr,
+        HardeningCheck::Targets,
+        HardeningCheck::Canary,
+        HardeningCheck::Retpoline,
+        HardeningCheck::Notrack,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HardeningCheck::Endbr => "endbr",
+            HardeningCheck::Targets => "targets",
+            HardeningCheck::Canary => "canary",
+            HardeningCheck::Retpoline => "retpoline",
+            HardeningCheck::Notrack => "notrack",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        HardeningCheck::ALL
+            .into_iter()
+            .find(|check| check.name() == name)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionHardening {
+    pub name: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub endbr: CheckStatus,
+    pub targets: CheckStatus,
+    pub canary: CheckStatus,
+    pub retpoline: CheckStatus,
+    pub notrack: CheckStatus,
+    /// Addresses in the function that are taken but lack `endbr64`.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "crate::objdump::hex::addresses"
+    )]
+    pub unmarked_targets: Vec<u64>,
+    /// Raw `jmp *`/`call *` instructions.
+    pub indirect_branches: usize,
+    pub thunk_calls: usize,
+    pub notrack_branches: usize,
+}
+
+impl FunctionHardening {
+    pub fn status(&self, check: HardeningCheck) -> CheckStatus {
+        match check {
+            HardeningCheck::Endbr => self.endbr,
+            HardeningCheck::Targets => self.targets,
+            HardeningCheck::Canary => self.canary,
+            HardeningCheck::Retpoline => self.retpoline,
+            HardeningCheck::Notrack => self.notrack,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct HardeningReport {
+    pub functions: Vec<FunctionHardening>,
+}
+
+impl HardeningReport {
+    pub fn failures(&self, check: HardeningCheck) -> usize {
+        self.functions
+            .iter()
+            .filter(|function| function.status(check) == CheckStatus::Fail)
+            .count()
+    }
+}
+
+/// Symbol name of a `<name+0x10>` annotation without offset or version.
+fn target_symbol(instruction: &X8664ATTInstruction) -> &str {
+    let name = instruction
+        .data
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    let name = name.split('+').next().unwrap_or(name);
+    name.split('@').next().unwrap_or(name)
+}
+
+fn is_stack_chk_fail(instruction: &X8664ATTInstruction) -> bool {
+    matches!(
+        instruction.opcode.flow(),
+        X8664ATTFlow::Call | X8664ATTFlow::Jump | X8664ATTFlow::ConditionalJump
+    ) && matches!(
+        target_symbol(instruction),
+        "__stack_chk_fail" | "__stack_chk_fail_local"
+    )
+}
+
+fn is_thunk(name: &str) -> bool {
+    name.starts_with("__x86_indirect_thunk") || name.starts_with("__x86_return_thunk")
+}
+
+/// `%fs:0x28`, where glibc keeps the stack protector canary.
+fn reads_canary(instruction: &X8664ATTInstruction) -> bool {
+    instruction.operands.iter().any(|operand| {
+        matches!(operand, X8664ATTOperand::Address(address)
+            if address.segment.as_ref().is_some_and(|segment| segment.name() == "%fs")
+                && address.displacement.as_ref().is_some_and(|displacement| displacement.0 == 0x28)
+                && address.base.is_none())
+    })
+}
+
+fn is_indirect_branch(instruction: &X8664ATTInstruction) -> bool {
+    matches!(
+        instruction.opcode.flow(),
+        X8664ATTFlow::Jump | X8664ATTFlow::Call
+    ) && matches!(
+        instruction.operands.first(),
+        Some(X8664ATTOperand::Indirect(_))
+    )
+}
+
+/// Code address a `lea disp(%rip),%reg` takes.
+fn taken_address(line: &ObjDumpInstructionLine) -> Option<u64> {
+    if line.instruction.opcode.operation() != "lea" || line.bytes.is_empty() {
+        return None;
+    }
+    match line.instruction.operands.first() {
+        Some(X8664ATTOperand::Address(address))
+            if matches!(&address.base, Some(X8664ATTValue::Register(register))
+                if register.name() == "%rip") =>
+        {
+            let displacement = address.displacement.as_ref().map_or(0, |value| value.0);
+            let next = line.address + line.bytes.len() as u64;
+            Some(next.wrapping_add(displacement as u64))
+        }
+        _ => None,
+    }
+}
+
+fn has_endbr(line: Option<&ObjDumpInstructionLine>) -> bool {
+    line.is_some_and(|line| line.instruction.opcode == X8664ATTOpcode::Endbr64)
+}
+
+impl HardeningReport {
+    /// Audits every function of the listing for CET (`endbr64` at entry and
+    /// at taken addresses, `notrack`), stack protector and retpoline code
+    /// generation.
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let code: HashMap<u64, &ObjDumpInstructionLine> = listing
+            .functions
+            .iter()
+            .flat_map(|function| &function.instructions)
+            .map(|line| (line.address, line))
+            .collect();
+        // Addresses taken in each function, and whether they are marked.
+        let mut taken: HashMap<u64, Vec<(u64, bool)>> = HashMap::new();
+        let mut owners: Vec<(u64, u64)> = listing
+            .functions
+            .iter()
+            .filter_map(|function| Some((function.address, function.instructions.last()?.address)))
+            .collect();
+        owners.sort();
+        for line in listing
+            .functions
+            .iter()
+            .flat_map(|function| &function.instructions)
+        {
+            let Some(target) = taken_address(line) else {
+                continue;
+            };
+            if !code.contains_key(&target) {
+                continue;
+            }
+            let index = owners.partition_point(|owner| owner.0 <= target);
+            if index > 0 && target <= owners[index - 1].1 {
+                taken
+                    .entry(owners[index - 1].0)
+                    .or_default()
+                    .push((target, has_endbr(code.get(&target).copied())));
+            }
+        }
+        let checking: HashSet<u64> = listing
+            .functions
+            .iter()
+            .filter(|function| {
+                function
+                    .instructions
+                    .iter()
+                    .any(|line| is_stack_chk_fail(&line.instruction))
+            })
+            .map(|function| function.address)
+            .collect();
+
+        let mut report = HardeningReport::default();
+        for function in &listing.functions {
+            if function.instructions.is_empty() {
+                continue;
+            }
+            let taken = taken.remove(&function.address).unwrap_or_default();
+            report.functions.push(audit(function, &checking, &taken));
+        }
+        report
+    }
+}
+
+fn audit(
+    function: &ObjDumpFunction,
+    checking: &HashSet<u64>,
+    taken: &[(u64, bool)],
+) -> FunctionHardening {
+    let instructions = &function.instructions;
+    let endbr = match has_endbr(instructions.first()) {
+        true => CheckStatus::Pass,
+        false => CheckStatus::Fail,
+    };
+    let mut unmarked_targets: Vec<u64> = taken
+        .iter()
+        .filter(|(_, marked)| !marked)
+        .map(|(target, _)| *target)
+        .collect();
+    unmarked_targets.sort();
+    unmarked_targets.dedup();
+    let targets = match (taken.is_empty(), unmarked_targets.is_empty()) {
+        (true, _) => CheckStatus::NotApplicable,
+        (false, true) => CheckStatus::Pass,
+        (false, false) => CheckStatus::Fail,
+    };
+
+    // The `__stack_chk_fail` call may sit in a `.cold` part the function
+    // branches to.
+    let loads_canary = instructions
+        .iter()
+        .any(|line| reads_canary(&line.instruction));
+    let fails_check = checking.contains(&function.address)
+        || instructions.iter().any(|line| {
+            line.instruction.opcode.flow() != X8664ATTFlow::Call
+                && line
+                    .instruction
+                    .branch_target()
+                    .is_some_and(|target| checking.contains(&target))
+        });
+    let canary = match (loads_canary, fails_check) {
+        (true, true) => CheckStatus::Pass,
+        (true, false) => CheckStatus::Fail,
+        // Includes `.cold` parts that only hold the failure path.
+        (false, _) => CheckStatus::NotApplicable,
+    };
+
+    let indirect_branches = instructions
+        .iter()
+        .filter(|line| is_indirect_branch(&line.instruction))
+        .count();
+    let thunk_calls = instructions
+        .iter()
+        .filter(|line| {
+            line.instruction.branch_target().is_some() && is_thunk(target_symbol(&line.instruction))
+        })
+        .count();
+    let notrack_branches = instructions
+        .iter()
+        .filter(|line| line.instruction.prefixes.contains(&X8664ATTOpcode::Notrack))
+        .count();
+    let retpoline = match (indirect_branches, thunk_calls) {
+        _ if is_thunk(&function.name) => CheckStatus::NotApplicable,
+        (0, 0) => CheckStatus::NotApplicable,
+        (0, _) => CheckStatus::Pass,
+        _ => CheckStatus::Fail,
+    };
+    let notrack = match (indirect_branches, notrack_branches) {
+        (0, _) => CheckStatus::NotApplicable,
+        (_, 0) => CheckStatus::Pass,
+        _ => CheckStatus::Fail,
+    };
+
+    FunctionHardening {
+        name: function.name.clone(),
+        address: function.address,
+        endbr,
+        targets,
+        canary,
+        retpoline,
+        notrack,
+        unmarked_targets,
+        indirect_branches,
+        thunk_calls,
+        notrack_branches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `f` takes the address of `g`, which has no `endbr64`, with `mnemonic`.
+    fn taking(mnemonic: &str) -> HardeningReport {
+        let dump = format!(
+            "
+0000000000401000 <f>:
+  401000:\tf3 0f 1e fa          \tendbr64
+  401004:\t48 8d 3d 05 00 00 00 \t{:<6} 0x5(%rip),%rdi        # 401010 <g>
+  40100b:\tc3                   \tret
+
+0000000000401010 <g>:
+  401010:\t31 c0                \txor    %eax,%eax
+  401012:\tc3                   \tret
+",
+            mnemonic
+        );
+        let listing = ObjDumpListing::parse(dump.as_bytes()).unwrap();
+        HardeningReport::build(&listing)
+    }
+
+    #[test]
+    fn taken_addresses_need_endbr() {
+        for mnemonic in ["lea", "leaq"] {
+            let report = taking(mnemonic);
+            let g = &report.functions[1];
+            assert_eq!(g.endbr, CheckStatus::Fail, "{}", mnemonic);
+            assert_eq!(g.targets, CheckStatus::Fail, "{}", mnemonic);
+            assert_eq!(g.unmarked_targets, vec![0x401010], "{}", mnemonic);
+            let f = &report.functions[0];
+            assert_eq!(f.endbr, CheckStatus::Pass, "{}", mnemonic);
+            assert_eq!(f.targets, CheckStatus::NotApplicable, "{}", mnemonic);
+            assert_eq!(report.failures(HardeningCheck::Targets), 1, "{}", mnemonic);
+        }
+    }
+
+    fn statuses(report: &HardeningReport, check: HardeningCheck) -> Vec<(&str, CheckStatus)> {
+        report
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.status(check)))
+            .collect()
+    }
+
+    /// Canary loads with and without a failure path, and one whose failure
+    /// path was split out into a `.cold` part.
+    const CANARIES: &str = "
+0000000000401000 <protected>:
+  401000:\t64 48 8b 04 25 28 00 \tmov    %fs:0x28,%rax
+  401007:\t00 00 
+  401009:\t48 2b 44 24 08       \tsub    0x8(%rsp),%rax
+  40100e:\t75 01                \tjne    401011 <protected+0x11>
+  401010:\tc3                   \tret
+  401011:\te8 ea 0f 00 00       \tcall   402000 <__stack_chk_fail@plt>
+
+0000000000401020 <unchecked>:
+  401020:\t64 48 8b 04 25 28 00 \tmov    %fs:0x28,%rax
+  401027:\t00 00 
+  401029:\t48 89 44 24 08       \tmov    %rax,0x8(%rsp)
+  40102e:\tc3                   \tret
+
+0000000000401030 <split>:
+  401030:\t64 48 8b 04 25 28 00 \tmov    %fs:0x28,%rax
+  401037:\t00 00 
+  401039:\t48 2b 44 24 08       \tsub    0x8(%rsp),%rax
+  40103e:\t0f 85 bc 00 00 00    \tjne    401100 <split.cold>
+  401044:\tc3                   \tret
+
+0000000000401100 <split.cold>:
+  401100:\te8 fb 0e 00 00       \tcall   402000 <__stack_chk_fail@plt>
+";
+
+    #[test]
+    fn canary_loads_need_a_failure_path() {
+        let listing = ObjDumpListing::parse(CANARIES.as_bytes()).unwrap();
+        let report = HardeningReport::build(&listing);
+        assert_eq!(
+            statuses(&report, HardeningCheck::Canary),
+            [
+                ("protected", CheckStatus::Pass),
+                ("unchecked", CheckStatus::Fail),
+                ("split", CheckStatus::Pass),
+                ("split.cold", CheckStatus::NotApplicable),
+            ]
+        );
+        assert_eq!(report.failures(HardeningCheck::Canary), 1);
+    }
+
+    /// Indirect branches through a retpoline thunk, raw, and with `notrack`.
+    const BRANCHES: &str = "
+0000000000401000 <thunked>:
+  401000:\te8 fb 00 00 00       \tcall   401100 <__x86_indirect_thunk_rax>
+  401005:\tc3                   \tret
+
+0000000000401010 <raw>:
+  401010:\tff e0                \tjmp    *%rax
+
+0000000000401020 <untracked>:
+  401020:\t3e ff e0             \tnotrack jmp *%rax
+
+0000000000401100 <__x86_indirect_thunk_rax>:
+  401100:\tff e0                \tjmp    *%rax
+";
+
+    #[test]
+    fn indirect_branches_need_thunks_and_tracking() {
+        let listing = ObjDumpListing::parse(BRANCHES.as_bytes()).unwrap();
+        let report = HardeningReport::build(&listing);
+        assert_eq!(
+            statuses(&report, HardeningCheck::Retpoline),
+            [
+                ("thunked", CheckStatus::Pass),
+                ("raw", CheckStatus::Fail),
+                ("untracked", CheckStatus::Fail),
+                ("__x86_indirect_thunk_rax", CheckStatus::NotApplicable),
+            ]
+        );
+        assert_eq!(
+            statuses(&report, HardeningCheck::Notrack),
+            [
+                ("thunked", CheckStatus::NotApplicable),
+                ("raw", CheckStatus::Pass),
+                ("untracked", CheckStatus::Fail),
+                ("__x86_indirect_thunk_rax", CheckStatus::Pass),
+            ]
+        );
+        assert_eq!(report.functions[0].thunk_calls, 1);
+        assert_eq!(report.functions[2].notrack_branches, 1);
+    }
+}
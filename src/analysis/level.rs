@@ -0,0 +1,206 @@
+use crate::objdump::listing::ObjDumpListing;
+use crate::objdump::x8664_att::X8664ATTExtension;
+use serde::Serialize;
+
+/// x86-64 psABI microarchitecture levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum MicroarchLevel {
+    #[serde(rename = "x86-64-v1")]
+    V1,
+    #[serde(rename = "x86-64-v2")]
+    V2,
+    #[serde(rename = "x86-64-v3")]
+    V3,
+    #[serde(rename = "x86-64-v4")]
+    V4,
+}
+
+impl MicroarchLevel {
+    /// Level that first guarantees `extension`. Extensions outside the psABI
+    /// levels (AES, SHA, ...) map to v1 and are reported separately.
+    pub fn of(extension: X8664ATTExtension) -> Self {
+        match extension {
+            X8664ATTExtension::Cmpxchg16b
+            | X8664ATTExtension::LahfSahf
+            | X8664ATTExtension::Popcnt
+            | X8664ATTExtension::Sse3
+            | X8664ATTExtension::Ssse3
+            | X8664ATTExtension::Sse41
+            | X8664ATTExtension::Sse42 => MicroarchLevel::V2,
+            X8664ATTExtension::Avx
+            | X8664ATTExtension::Avx2
+            | X8664ATTExtension::Bmi1
+            | X8664ATTExtension::Bmi2
+            | X8664ATTExtension::F16c
+            | X8664ATTExtension::Fma
+            | X8664ATTExtension::Lzcnt
+            | X8664ATTExtension::Movbe => MicroarchLevel::V3,
+            X8664ATTExtension::Avx512 => MicroarchLevel::V4,
+            _ => MicroarchLevel::V1,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MicroarchLevel::V1 => "x86-64-v1",
+            MicroarchLevel::V2 => "x86-64-v2",
+            MicroarchLevel::V3 => "x86-64-v3",
+            MicroarchLevel::V4 => "x86-64-v4",
+        }
+    }
+
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.trim_start_matches("x86-64-") {
+            "v1" => Some(MicroarchLevel::V1),
+            "v2" => Some(MicroarchLevel::V2),
+            "v3" => Some(MicroarchLevel::V3),
+            "v4" => Some(MicroarchLevel::V4),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelOffender {
+    pub level: MicroarchLevel,
+    pub extension: &'static str,
+    pub address: String,
+    pub function: String,
+    pub mnemonic: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelReport {
+    pub required: MicroarchLevel,
+    /// First instruction found for each level above v1.
+    pub offenders: Vec<LevelOffender>,
+    /// First instruction found for each extension that no level covers.
+    pub outside_levels: Vec<LevelOffender>,
+    pub ignored_functions: usize,
+}
+
+impl LevelReport {
+    /// Computes the level required by every function whose name does not
+    /// contain one of the `allow` patterns, so that functions only reached
+    /// behind a CPUID dispatch can be excluded from the gate.
+    pub fn build(listing: &ObjDumpListing, allow: &[String]) -> Self {
+        let mut report = LevelReport {
+            required: MicroarchLevel::V1,
+            offenders: Vec::new(),
+            outside_levels: Vec::new(),
+            ignored_functions: 0,
+        };
+        for function in &listing.functions {
+            if allow
+                .iter()
+                .any(|pattern| function.name.contains(pattern.as_str()))
+            {
+                report.ignored_functions += 1;
+                continue;
+            }
+            for line in &function.instructions {
+                let extension = line.instruction.extension();
+                let level = MicroarchLevel::of(extension);
+                let offender = || LevelOffender {
+                    level,
+                    extension: extension.name(),
+                    address: format!("{:#x}", line.address),
+                    function: function.name.clone(),
+                    mnemonic: line.instruction.opcode.mnemonic().to_string(),
+                };
+                if level > MicroarchLevel::V1 {
+                    report.required = report.required.max(level);
+                    if !report.offenders.iter().any(|known| known.level == level) {
+                        report.offenders.push(offender());
+                    }
+                } else if matches!(
+                    extension,
+                    X8664ATTExtension::Aes
+                        | X8664ATTExtension::Pclmul
+                        | X8664ATTExtension::Sha
+                        | X8664ATTExtension::Rdrand
+                        | X8664ATTExtension::Adx
+                ) && !report
+                    .outside_levels
+                    .iter()
+                    .any(|known| known.extension == extension.name())
+                {
+                    report.outside_levels.push(offender());
+                }
+            }
+        }
+        report.offenders.sort_by_key(|offender| offender.level);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+0000000000401000 <f>:
+  401000:\tf3 48 0f b8 c7       \tpopcnt %rdi,%rax
+  401005:\tc5 ec 58 d9          \tvaddps %ymm1,%ymm2,%ymm3
+  401009:\tc3                   \tret
+
+000000000040100a <g>:
+  40100a:\t66 0f 38 dc c1       \taesenc %xmm1,%xmm0
+  40100f:\tc4 e2 60 f2 c8       \tandn   %eax,%ebx,%ecx
+  401014:\tc3                   \tret
+
+0000000000401015 <f_avx512>:
+  401015:\t62 f1 6d 48 fe d9    \tvpaddd %zmm1,%zmm2,%zmm3
+  40101b:\tc3                   \tret
+";
+
+    fn offenders(offenders: &[LevelOffender]) -> Vec<(&str, &str, &str)> {
+        offenders
+            .iter()
+            .map(|offender| {
+                (
+                    offender.extension,
+                    offender.address.as_str(),
+                    offender.function.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lists_the_first_offender_of_each_level() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = LevelReport::build(&listing, &[]);
+        assert_eq!(report.required, MicroarchLevel::V4);
+        assert_eq!(
+            offenders(&report.offenders),
+            [
+                ("popcnt", "0x401000", "f"),
+                ("avx", "0x401005", "f"),
+                ("avx-512", "0x401015", "f_avx512"),
+            ]
+        );
+        assert_eq!(
+            offenders(&report.outside_levels),
+            [("aes", "0x40100a", "g")]
+        );
+        assert_eq!(report.ignored_functions, 0);
+    }
+
+    #[test]
+    fn allowed_functions_do_not_raise_the_level() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = LevelReport::build(&listing, &["avx512".to_string(), "g".to_string()]);
+        assert_eq!(report.required, MicroarchLevel::V3);
+        assert_eq!(report.offenders.len(), 2);
+        assert!(report.outside_levels.is_empty());
+        assert_eq!(report.ignored_functions, 2);
+    }
+
+    #[test]
+    fn parses_level_names() {
+        assert_eq!(MicroarchLevel::parse("x86-64-v3"), Some(MicroarchLevel::V3));
+        assert_eq!(MicroarchLevel::parse("v2"), Some(MicroarchLevel::V2));
+        assert_eq!(MicroarchLevel::parse("v5"), None);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::analysis::cfg::ControlFlowGraph;
+use crate::analysis::dominators::DominatorTree;
+use crate::analysis::switches::JumpTable;
+use crate::objdump::listing::ObjDumpFunction;
+
+/// Natural loop: the blocks that reach a back edge into `header` without
+/// passing through it. Back edges sharing a header are merged into one loop.
+#[derive(Debug)]
+pub struct NaturalLoop {
+    pub header: usize,
+    pub blocks: Vec<usize>,
+    pub parent: Option<usize>,
+    pub depth: u32,
+}
+
+#[derive(Debug)]
+pub struct LoopForest {
+    pub loops: Vec<NaturalLoop>,
+    pub block_depth: Vec<u32>,
+}
+
+impl LoopForest {
+    pub fn build(cfg: &ControlFlowGraph, dominators: &DominatorTree) -> Self {
+        let mut loops: Vec<NaturalLoop> = Vec::new();
+        for (block, node) in cfg.blocks.iter().enumerate() {
+            for &header in &node.successors {
+                if !dominators.dominates(header, block) {
+                    continue;
+                }
+                let mut body = vec![false; cfg.blocks.len()];
+                body[header] = true;
+                let mut stack = vec![block];
+                while let Some(member) = stack.pop() {
+                    if body[member] || !dominators.dominates(header, member) {
+                        continue;
+                    }
+                    body[member] = true;
+                    stack.extend(cfg.blocks[member].predecessors.iter().copied());
+                }
+                let blocks: Vec<usize> = (0..body.len()).filter(|&b| body[b]).collect();
+                match loops.iter_mut().find(|natural| natural.header == header) {
+                    Some(natural) => {
+                        natural.blocks.extend(blocks);
+                        natural.blocks.sort_unstable();
+                        natural.blocks.dedup();
+                    }
+                    None => loops.push(NaturalLoop {
+                        header,
+                        blocks,
+                        parent: None,
+                        depth: 0,
+                    }),
+                }
+            }
+        }
+
+        let mut block_depth = vec![0; cfg.blocks.len()];
+        for natural in &loops {
+            for &block in &natural.blocks {
+                block_depth[block] += 1;
+            }
+        }
+
+        for index in 0..loops.len() {
+            let header = loops[index].header;
+            loops[index].depth = block_depth[header];
+            loops[index].parent = (0..loops.len())
+                .filter(|&other| {
+                    other != index && loops[other].blocks.binary_search(&header).is_ok()
+                })
+                .min_by_key(|&other| loops[other].blocks.len());
+        }
+
+        LoopForest { loops, block_depth }
+    }
+}
+
+/// Builds the loop forest of `function`, with the recovered jump `tables`
+/// it dispatches through, and records each instruction's loop nesting depth
+/// in [`ObjDumpInstructionLine::loop_depth`].
+///
+/// [`ObjDumpInstructionLine::loop_depth`]: crate::objdump::line::ObjDumpInstructionLine::loop_depth
+pub fn annotate_loop_depths(
+    function: &mut ObjDumpFunction,
+    tables: &[&JumpTable],
+) -> (ControlFlowGraph, LoopForest) {
+    let cfg = ControlFlowGraph::build_with_tables(function, tables);
+    let dominators = DominatorTree::build(&cfg);
+    let forest = LoopForest::build(&cfg, &dominators);
+    for (block, node) in cfg.blocks.iter().enumerate() {
+        for line in &mut function.instructions[node.start..node.end] {
+            line.loop_depth = forest.block_depth[block];
+        }
+    }
+    (cfg, forest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::switches::SwitchReport;
+    use crate::objdump::listing::ObjDumpListing;
+
+    /// Two nested counted loops: blocks 1..=3 form the outer loop, block 2
+    /// the inner one.
+    const NESTED: &str = "
+0000000000401000 <f>:
+  401000:\t31 c0                \txor    %eax,%eax
+  401002:\t31 c9                \txor    %ecx,%ecx
+  401004:\tff c0                \tinc    %eax
+  401006:\t83 f8 0a             \tcmp    $0xa,%eax
+  401009:\t7c f9                \tjl     401004 <f+0x4>
+  40100b:\tff c1                \tinc    %ecx
+  40100d:\t83 f9 0a             \tcmp    $0xa,%ecx
+  401010:\t7c f0                \tjl     401002 <f+0x2>
+  401012:\tc3                   \tret
+";
+
+    #[test]
+    fn nested_loops() {
+        let mut listing = ObjDumpListing::parse(NESTED.as_bytes()).unwrap();
+        let (cfg, forest) = annotate_loop_depths(&mut listing.functions[0], &[]);
+        assert_eq!(cfg.blocks.len(), 5);
+        assert_eq!(forest.loops.len(), 2);
+        let outer = forest.loops.iter().position(|l| l.header == 1).unwrap();
+        let inner = forest.loops.iter().position(|l| l.header == 2).unwrap();
+        assert_eq!(forest.loops[outer].blocks, vec![1, 2, 3]);
+        assert_eq!(forest.loops[outer].depth, 1);
+        assert_eq!(forest.loops[outer].parent, None);
+        assert_eq!(forest.loops[inner].blocks, vec![2]);
+        assert_eq!(forest.loops[inner].depth, 2);
+        assert_eq!(forest.loops[inner].parent, Some(outer));
+        assert_eq!(forest.block_depth, vec![0, 1, 2, 1, 0]);
+        let depths: Vec<u32> = listing.functions[0]
+            .instructions
+            .iter()
+            .map(|line| line.loop_depth)
+            .collect();
+        assert_eq!(depths, vec![0, 1, 2, 2, 2, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn back_edges_sharing_a_header_merge() {
+        // Two `continue` paths back to the header at 0x401000.
+        let listing = ObjDumpListing::parse(
+            "
+0000000000401000 <g>:
+  401000:\tff c0                \tinc    %eax
+  401002:\t85 c9                \ttest   %ecx,%ecx
+  401004:\t74 fa                \tje     401000 <g>
+  401006:\t83 f8 0a             \tcmp    $0xa,%eax
+  401009:\t7c f5                \tjl     401000 <g>
+  40100b:\tc3                   \tret
+"
+            .as_bytes(),
+        )
+        .unwrap();
+        let cfg = ControlFlowGraph::build(&listing.functions[0]);
+        let dominators = DominatorTree::build(&cfg);
+        let forest = LoopForest::build(&cfg, &dominators);
+        assert_eq!(forest.loops.len(), 1);
+        assert_eq!(forest.loops[0].header, 0);
+        assert_eq!(forest.loops[0].blocks, vec![0, 1]);
+    }
+
+    /// A six-way `switch` compiled with `-O2 -fPIC` whose first two cases
+    /// are loops, with its jump table from `objdump -s -j .rodata`.
+    const SWITCH: &str = "\
+Contents of section .rodata:
+ 2000 48f0ffff 68f0ffff 90f0ffff 98f0ffff  H...h...........
+ 2010 30f0ffff 40f0ffff                    0...@...
+
+Disassembly of section .text:
+
+0000000000001010 <f>:
+    1010:\t83 ff 05             \tcmp    $0x5,%edi
+    1013:\t0f 87 e7 ff ff ff    \tja     1000 <f.cold>
+    1019:\t48 8d 0d e0 0f 00 00 \tlea    0xfe0(%rip),%rcx        # 2000 <f+0xff0>
+    1020:\t89 f8                \tmov    %edi,%eax
+    1022:\t48 63 04 81          \tmovslq (%rcx,%rax,4),%rax
+    1026:\t48 01 c8             \tadd    %rcx,%rax
+    1029:\tff e0                \tjmp    *%rax
+    102b:\t0f 1f 44 00 00       \tnopl   0x0(%rax,%rax,1)
+    1030:\t8d 04 95 00 00 00 00 \tlea    0x0(,%rdx,4),%eax
+    1037:\tc3                   \tret
+    1038:\t0f 1f 84 00 00 00 00 \tnopl   0x0(%rax,%rax,1)
+    103f:\t00
+    1040:\t8d 04 d2             \tlea    (%rdx,%rdx,8),%eax
+    1043:\tc3                   \tret
+    1044:\t0f 1f 40 00          \tnopl   0x0(%rax)
+    1048:\t85 d2                \ttest   %edx,%edx
+    104a:\t7e 54                \tjle    10a0 <f+0x90>
+    104c:\t48 63 c2             \tmovslq %edx,%rax
+    104f:\t48 8d 14 86          \tlea    (%rsi,%rax,4),%rdx
+    1053:\t89 f8                \tmov    %edi,%eax
+    1055:\t0f 1f 00             \tnopl   (%rax)
+    1058:\t03 06                \tadd    (%rsi),%eax
+    105a:\t48 83 c6 04          \tadd    $0x4,%rsi
+    105e:\t48 39 f2             \tcmp    %rsi,%rdx
+    1061:\t75 f5                \tjne    1058 <f+0x48>
+    1063:\tc3                   \tret
+    1064:\t0f 1f 40 00          \tnopl   0x0(%rax)
+    1068:\t85 d2                \ttest   %edx,%edx
+    106a:\t7e 3c                \tjle    10a8 <f+0x98>
+    106c:\t48 63 c2             \tmovslq %edx,%rax
+    106f:\t48 8d 0c 86          \tlea    (%rsi,%rax,4),%rcx
+    1073:\t31 c0                \txor    %eax,%eax
+    1075:\t0f 1f 00             \tnopl   (%rax)
+    1078:\t8b 16                \tmov    (%rsi),%edx
+    107a:\t48 83 c6 04          \tadd    $0x4,%rsi
+    107e:\t8d 14 52             \tlea    (%rdx,%rdx,2),%edx
+    1081:\t31 d0                \txor    %edx,%eax
+    1083:\t48 39 ce             \tcmp    %rcx,%rsi
+    1086:\t75 f0                \tjne    1078 <f+0x68>
+    1088:\tc3                   \tret
+    1089:\t0f 1f 80 00 00 00 00 \tnopl   0x0(%rax)
+    1090:\t8d 42 f5             \tlea    -0xb(%rdx),%eax
+    1093:\tc3                   \tret
+    1094:\t0f 1f 40 00          \tnopl   0x0(%rax)
+    1098:\t89 d0                \tmov    %edx,%eax
+    109a:\t83 f0 05             \txor    $0x5,%eax
+    109d:\tc3                   \tret
+    109e:\t66 90                \txchg   %ax,%ax
+    10a0:\t89 f8                \tmov    %edi,%eax
+    10a2:\tc3                   \tret
+    10a3:\t0f 1f 44 00 00       \tnopl   0x0(%rax,%rax,1)
+    10a8:\t31 c0                \txor    %eax,%eax
+    10aa:\tc3                   \tret
+";
+
+    #[test]
+    fn loops_inside_switch_cases() {
+        let mut listing = ObjDumpListing::parse(SWITCH.as_bytes()).unwrap();
+        let switches = SwitchReport::build(&listing);
+        let tables = switches.tables_of("f");
+        assert_eq!(tables.len(), 1);
+
+        let function = &mut listing.functions[0];
+        let (_, forest) = annotate_loop_depths(function, &[]);
+        assert!(forest.loops.is_empty());
+
+        let (cfg, forest) = annotate_loop_depths(function, &tables);
+        let headers: Vec<u64> = forest
+            .loops
+            .iter()
+            .map(|natural| function.instructions[cfg.blocks[natural.header].start].address)
+            .collect();
+        assert_eq!(headers, [0x1058, 0x1078]);
+        let depth = |address| {
+            let line = function
+                .instructions
+                .iter()
+                .find(|line| line.address == address);
+            line.unwrap().loop_depth
+        };
+        assert_eq!((depth(0x105a), depth(0x1063), depth(0x1081)), (1, 0, 1));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::ObjDumpListing;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Instruction counts keyed by mnemonic, suffix-free operation, category and
+/// ISA extension.
+#[derive(Debug, Default, Serialize)]
+pub struct InstructionMix {
+    pub total: usize,
+    pub opcodes: BTreeMap<String, usize>,
+    pub operations: BTreeMap<String, usize>,
+    pub categories: BTreeMap<String, usize>,
+    pub extensions: BTreeMap<String, usize>,
+}
+
+impl InstructionMix {
+    pub fn add(&mut self, line: &ObjDumpInstructionLine) {
+        let opcode = &line.instruction.opcode;
+        self.total += 1;
+        *self
+            .opcodes
+            .entry(opcode.mnemonic().to_string())
+            .or_default() += 1;
+        *self
+            .operations
+            .entry(opcode.operation().to_string())
+            .or_default() += 1;
+        *self
+            .categories
+            .entry(opcode.category().name().to_string())
+            .or_default() += 1;
+        *self
+            .extensions
+            .entry(line.instruction.extension().name().to_string())
+            .or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: &InstructionMix) {
+        self.total += other.total;
+        for (mine, theirs) in [
+            (&mut self.opcodes, &other.opcodes),
+            (&mut self.operations, &other.operations),
+            (&mut self.categories, &other.categories),
+            (&mut self.extensions, &other.extensions),
+        ] {
+            for (key, count) in theirs {
+                *mine.entry(key.clone()).or_default() += count;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionMix {
+    pub name: String,
+    pub address: String,
+    pub mix: InstructionMix,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MixReport {
+    pub overall: InstructionMix,
+    pub functions: Vec<FunctionMix>,
+}
+
+impl MixReport {
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let mut report = MixReport::default();
+        for function in &listing.functions {
+            let mut mix = InstructionMix::default();
+            for line in &function.instructions {
+                mix.add(line);
+            }
+            report.overall.merge(&mix);
+            report.functions.push(FunctionMix {
+                name: function.name.clone(),
+                address: format!("{:#x}", function.address),
+                mix,
+            });
+        }
+        report
+    }
+}
+
+fn write_histogram(
+    out: &mut impl std::io::Write,
+    title: &str,
+    counts: &BTreeMap<String, usize>,
+    total: usize,
+) -> std::io::Result<()> {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let width = counts
+        .iter()
+        .map(|(key, _)| key.len())
+        .max()
+        .unwrap_or(0)
+        .max(title.len());
+    writeln!(out, "  {:<width$}  {:>10}  {:>7}", title, "count", "share")?;
+    for (key, count) in counts {
+        let share = 100.0 * *count as f64 / total.max(1) as f64;
+        writeln!(out, "  {:<width$}  {:>10}  {:>6.2}%", key, count, share)?;
+    }
+    Ok(())
+}
+
+pub fn write_table(
+    out: &mut impl std::io::Write,
+    mix: &InstructionMix,
+    opcodes: bool,
+) -> std::io::Result<()> {
+    writeln!(out, "  total {}", mix.total)?;
+    write_histogram(out, "extension", &mix.extensions, mix.total)?;
+    write_histogram(out, "category", &mix.categories, mix.total)?;
+    write_histogram(out, "operation", &mix.operations, mix.total)?;
+    if opcodes {
+        write_histogram(out, "opcode", &mix.opcodes, mix.total)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+0000000000401000 <f>:
+  401000:\t89 f8                \tmov    %edi,%eax
+  401002:\tc7 07 01 00 00 00    \tmovl   $0x1,(%rdi)
+  401008:\t01 f8                \tadd    %edi,%eax
+  40100a:\tc3                   \tret
+
+000000000040100b <g>:
+  40100b:\t0f 58 c1             \taddps  %xmm1,%xmm0
+  40100e:\tc3                   \tret
+";
+
+    fn counts(counts: &BTreeMap<String, usize>) -> Vec<(&str, usize)> {
+        counts
+            .iter()
+            .map(|(key, count)| (key.as_str(), *count))
+            .collect()
+    }
+
+    #[test]
+    fn counts_instructions_per_function_and_overall() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = MixReport::build(&listing);
+        assert_eq!(report.functions.len(), 2);
+        assert_eq!(report.functions[0].address, "0x401000");
+        assert_eq!(report.functions[0].mix.total, 4);
+        assert_eq!(report.functions[1].mix.total, 2);
+
+        let overall = &report.overall;
+        assert_eq!(overall.total, 6);
+        assert_eq!(
+            counts(&overall.opcodes),
+            [
+                ("add", 1),
+                ("addps", 1),
+                ("mov", 1),
+                ("movl", 1),
+                ("ret", 2)
+            ]
+        );
+        assert_eq!(
+            counts(&overall.operations),
+            [("add", 1), ("addps", 1), ("mov", 2), ("ret", 2)]
+        );
+        assert_eq!(
+            counts(&overall.categories),
+            [
+                ("arithmetic", 1),
+                ("branch", 2),
+                ("data-transfer", 2),
+                ("simd", 1)
+            ]
+        );
+        assert_eq!(counts(&overall.extensions), [("base", 5), ("sse", 1)]);
+    }
+
+    #[test]
+    fn sorts_histograms_by_count() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = MixReport::build(&listing);
+        let mut out = Vec::new();
+        write_table(&mut out, &report.overall, false).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        let extensions: Vec<&str> = table.lines().skip(1).take(3).collect();
+        assert_eq!(
+            extensions,
+            [
+                "  extension       count    share",
+                "  base                5   83.33%",
+                "  sse                 1   16.67%",
+            ]
+        );
+        assert!(!table.contains("opcode"));
+    }
+}
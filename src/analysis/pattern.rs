@@ -0,0 +1,616 @@
+use crate::analysis::gadgets::glob;
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::ObjDumpListing;
+use crate::objdump::x8664_att::{
+    X8664ATTAddress, X8664ATTInstruction, X8664ATTInteger, X8664ATTOperand, X8664ATTRegister,
+    X8664ATTValue,
+};
+use crate::ObjumpError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+type Captures = BTreeMap<String, String>;
+
+/// Binds capture `name` to `value`, or checks it against the earlier binding.
+fn bind(captures: &mut Captures, name: &str, value: String) -> bool {
+    match captures.get(name) {
+        Some(bound) => *bound == value,
+        None => {
+            captures.insert(name.to_string(), value);
+            true
+        }
+    }
+}
+
+/// Text a register is captured and compared as.
+fn register_value(register: &X8664ATTRegister) -> String {
+    register.family().to_string()
+}
+
+fn operand_value(operand: &X8664ATTOperand) -> String {
+    match operand {
+        X8664ATTOperand::Register(register) => register_value(register),
+        operand => operand.to_string(),
+    }
+}
+
+/// One part of a memory operand pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Any,
+    Absent,
+    AnyRegister,
+    Capture(String),
+    Literal(String),
+}
+
+impl Part {
+    fn parse(text: &str, absent: Part) -> Part {
+        match text {
+            "" => absent,
+            "*" => Part::Any,
+            "%*" => Part::AnyRegister,
+            _ => match text.strip_prefix("%?").or_else(|| text.strip_prefix('?')) {
+                Some(name) => Part::Capture(name.to_string()),
+                None => Part::Literal(text.to_string()),
+            },
+        }
+    }
+
+    fn matches(&self, value: Option<String>, captures: &mut Captures) -> bool {
+        match (self, value) {
+            (Part::Any, _) => true,
+            (Part::Absent, value) => value.is_none(),
+            (Part::AnyRegister, value) => value.is_some_and(|value| value.starts_with('%')),
+            (Part::Capture(name), Some(value)) => bind(captures, name, value),
+            (Part::Literal(literal), Some(value)) => *literal == value,
+            (_, None) => false,
+        }
+    }
+}
+
+fn value_text(value: &Option<X8664ATTValue>) -> Option<String> {
+    value.as_ref().map(|value| match value {
+        X8664ATTValue::Register(register) => register_value(register),
+        X8664ATTValue::Immediate(immediate) => immediate.to_string(),
+    })
+}
+
+fn integer_text(value: &Option<X8664ATTInteger>) -> Option<String> {
+    value.as_ref().map(|value| value.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MemoryPattern {
+    segment: Part,
+    displacement: Part,
+    base: Part,
+    index: Part,
+    scale: Part,
+}
+
+impl MemoryPattern {
+    fn parse(text: &str) -> Result<Self, ObjumpError> {
+        let invalid = || ObjumpError::InvalidArgument(text.to_string());
+        let (segment, memory) = match text.split_once(':') {
+            Some((segment, memory)) => (Part::parse(segment, Part::Absent), memory),
+            None => (Part::Absent, text),
+        };
+        let (displacement, inner) = memory.split_once('(').ok_or_else(invalid)?;
+        let inner = inner.strip_suffix(')').ok_or_else(invalid)?;
+        let mut parts = inner.split(',');
+        let mut part = || parts.next().unwrap_or_default();
+        let (base, index, scale) = (part(), part(), part());
+        // Literals are normalised the way the listing parser prints them.
+        let literal = |part: Part, normalise: &dyn Fn(&str) -> Option<String>| match part {
+            Part::Literal(literal) => normalise(&literal).map(Part::Literal).ok_or_else(invalid),
+            part => Ok(part),
+        };
+        let integer = |text: &str| {
+            X8664ATTInteger::try_from(text)
+                .ok()
+                .map(|value| value.to_string())
+        };
+        let value = |text: &str| value_text(&X8664ATTValue::try_from(text).ok());
+        let scaler = |text: &str| text.parse::<u8>().ok().map(|scale| format!("{:#x}", scale));
+        Ok(MemoryPattern {
+            segment: literal(segment, &|text| Some(register_value(&text.into())))?,
+            displacement: literal(Part::parse(displacement, Part::Any), &integer)?,
+            base: literal(Part::parse(base, Part::Absent), &value)?,
+            index: literal(Part::parse(index, Part::Absent), &value)?,
+            scale: literal(Part::parse(scale, Part::Absent), &scaler)?,
+        })
+    }
+
+    fn matches(&self, address: &X8664ATTAddress, captures: &mut Captures) -> bool {
+        self.segment
+            .matches(address.segment.as_ref().map(register_value), captures)
+            && self
+                .displacement
+                .matches(integer_text(&address.displacement), captures)
+            && self.base.matches(value_text(&address.base), captures)
+            && self.index.matches(value_text(&address.offset), captures)
+            && self.scale.matches(
+                address
+                    .scaler
+                    .as_ref()
+                    .map(|scale| format!("{:#x}", scale.0)),
+                captures,
+            )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OperandPattern {
+    Any,
+    AnyRegister,
+    AnyImmediate,
+    AnyTarget,
+    Capture(String),
+    Register(String),
+    Immediate(String),
+    Target(String),
+    /// Glob over the `<symbol+offset>` annotation of a branch.
+    Symbol(String),
+    Memory(MemoryPattern),
+    Indirect(Box<OperandPattern>),
+    Not(Box<OperandPattern>),
+    Literal(X8664ATTOperand),
+}
+
+impl OperandPattern {
+    fn parse(text: &str) -> Result<Self, ObjumpError> {
+        Ok(match text {
+            "*" => OperandPattern::Any,
+            "%*" => OperandPattern::AnyRegister,
+            "$*" => OperandPattern::AnyImmediate,
+            "<*>" => OperandPattern::AnyTarget,
+            _ if text.starts_with('!') => {
+                OperandPattern::Not(Box::new(OperandPattern::parse(&text[1..])?))
+            }
+            _ if text.starts_with('*') => {
+                OperandPattern::Indirect(Box::new(OperandPattern::parse(&text[1..])?))
+            }
+            _ if text.contains('(') => OperandPattern::Memory(MemoryPattern::parse(text)?),
+            _ if text.starts_with('?') => OperandPattern::Capture(text[1..].to_string()),
+            _ if text.starts_with("%?") => OperandPattern::Register(text[2..].to_string()),
+            _ if text.starts_with("$?") => OperandPattern::Immediate(text[2..].to_string()),
+            _ if text.starts_with("<?") && text.ends_with('>') => {
+                OperandPattern::Target(text[2..text.len() - 1].to_string())
+            }
+            _ if text.starts_with('<') && text.ends_with('>') => {
+                OperandPattern::Symbol(text[1..text.len() - 1].to_string())
+            }
+            _ => OperandPattern::Literal(
+                X8664ATTOperand::try_from(text)
+                    .map_err(|_| ObjumpError::InvalidArgument(text.to_string()))?,
+            ),
+        })
+    }
+
+    fn matches(
+        &self,
+        operand: &X8664ATTOperand,
+        instruction: &X8664ATTInstruction,
+        captures: &mut Captures,
+    ) -> bool {
+        let operand = match operand {
+            X8664ATTOperand::Decorated(operand, _) => operand,
+            operand => operand,
+        };
+        match (self, operand) {
+            (OperandPattern::Any, _) => true,
+            (OperandPattern::AnyRegister, X8664ATTOperand::Register(_)) => true,
+            (OperandPattern::AnyImmediate, X8664ATTOperand::Immediate(_)) => true,
+            (OperandPattern::AnyTarget, X8664ATTOperand::Target(_)) => true,
+            (OperandPattern::Capture(name), operand) => {
+                bind(captures, name, operand_value(operand))
+            }
+            (OperandPattern::Register(name), X8664ATTOperand::Register(register)) => {
+                bind(captures, name, register_value(register))
+            }
+            (OperandPattern::Immediate(name), X8664ATTOperand::Immediate(immediate)) => {
+                bind(captures, name, immediate.to_string())
+            }
+            (OperandPattern::Target(name), X8664ATTOperand::Target(target)) => {
+                bind(captures, name, format!("{:x}", target))
+            }
+            (OperandPattern::Symbol(pattern), X8664ATTOperand::Target(_)) => glob(
+                pattern.as_bytes(),
+                instruction
+                    .data
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .as_bytes(),
+            ),
+            (OperandPattern::Memory(pattern), X8664ATTOperand::Address(address)) => {
+                pattern.matches(address, captures)
+            }
+            (OperandPattern::Indirect(pattern), X8664ATTOperand::Indirect(operand)) => {
+                pattern.matches(operand, instruction, captures)
+            }
+            (OperandPattern::Not(pattern), operand) => {
+                !pattern.matches(operand, instruction, &mut captures.clone())
+            }
+            (OperandPattern::Literal(literal), operand) => literal == operand,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InstructionPattern {
+    /// Globs, one of which must match.
+    opcodes: Vec<String>,
+    /// `None` when operands were left out.
+    operands: Option<Vec<OperandPattern>>,
+    /// The operand list ended in `...`.
+    rest: bool,
+}
+
+/// Splits operands at commas outside parentheses.
+fn split_operands(text: &str) -> Vec<&str> {
+    let mut operands = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                operands.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    operands.push(text[start..].trim());
+    operands
+}
+
+impl InstructionPattern {
+    fn parse(text: &str) -> Result<Self, ObjumpError> {
+        let (opcode, operands) = match text.split_once(char::is_whitespace) {
+            Some((opcode, operands)) => (opcode, operands.trim()),
+            None => (text, ""),
+        };
+        let mut pattern = InstructionPattern {
+            opcodes: opcode.split('|').map(str::to_string).collect(),
+            operands: None,
+            rest: false,
+        };
+        if operands.is_empty() {
+            return Ok(pattern);
+        }
+        let mut operands = split_operands(operands);
+        if operands.last() == Some(&"...") {
+            operands.pop();
+            pattern.rest = true;
+        }
+        pattern.operands = Some(
+            operands
+                .into_iter()
+                .map(OperandPattern::parse)
+                .collect::<Result<_, _>>()?,
+        );
+        Ok(pattern)
+    }
+
+    fn matches(&self, instruction: &X8664ATTInstruction, captures: &mut Captures) -> bool {
+        let mnemonic = instruction.opcode.mnemonic();
+        let operation = instruction.opcode.operation();
+        if !self.opcodes.iter().any(|opcode| {
+            glob(opcode.as_bytes(), mnemonic.as_bytes())
+                || glob(opcode.as_bytes(), operation.as_bytes())
+        }) {
+            return false;
+        }
+        let Some(patterns) = &self.operands else {
+            return true;
+        };
+        let operands = &instruction.operands;
+        if operands.len() < patterns.len() || operands.len() > patterns.len() && !self.rest {
+            return false;
+        }
+        patterns
+            .iter()
+            .zip(operands)
+            .all(|(pattern, operand)| pattern.matches(operand, instruction, captures))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Element {
+    Instruction(InstructionPattern),
+    Not(InstructionPattern),
+    Gap(usize),
+}
+
+/// A compiled instruction sequence pattern, for searching listings for idioms.
+///
+/// A pattern is a `;`-separated sequence of elements, each matching one
+/// instruction, or a gap of several:
+///
+/// ```text
+/// cmp $?n,%?r ; ja <*> ; jmp *(,%?r,8)
+/// mov $*,%?d ; ...{4} ; !mov *,%?d ; idiv %?d
+/// ```
+///
+/// * `op a,b` matches an instruction whose mnemonic or suffix-free operation
+///   (`mov` for `movl`) matches the glob `op`, with alternatives separated by
+///   `|` (`ja|jae`, `cmov*`). Operands are in AT&T order. Without operands
+///   any operands match; a trailing `...` operand matches any remaining ones.
+/// * `!op a,b` matches one instruction that does not match `op a,b`.
+/// * `...` skips any number of instructions, `...{N}` at most `N`.
+///
+/// Operands:
+///
+/// * `*` any operand, `%*` any register, `$*` any immediate, `<*>` any
+///   branch target and `<glob>` a target whose symbol matches `glob`.
+/// * `?x`, `%?x`, `$?x` and `<?x>` capture the operand as `x`; later uses of
+///   `x` must match the same value. Registers are captured by their 64-bit
+///   register, so `%?r` binds `%eax` and `%rax` alike.
+/// * `disp(base,index,scale)` memory, each part a literal, `*`, `%*` or a
+///   capture. An empty displacement matches any, an empty base, index or
+///   scale only a missing one: `*(,%?r,8)` is a jump through a table.
+/// * `*operand` an indirect branch operand, `!operand` anything else.
+/// * Any other operand is parsed as in a listing and must match exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    elements: Vec<Element>,
+}
+
+impl TryFrom<&str> for Pattern {
+    type Error = ObjumpError;
+
+    fn try_from(text: &str) -> Result<Self, ObjumpError> {
+        let mut elements = Vec::new();
+        for element in text.split(';').map(str::trim) {
+            let invalid = || ObjumpError::InvalidArgument(element.to_string());
+            elements.push(match element {
+                "" => return Err(invalid()),
+                "..." => Element::Gap(usize::MAX),
+                _ if element.starts_with("...{") && element.ends_with('}') => Element::Gap(
+                    element[4..element.len() - 1]
+                        .parse()
+                        .map_err(|_| invalid())?,
+                ),
+                _ if element.starts_with('!') => {
+                    Element::Not(InstructionPattern::parse(element[1..].trim())?)
+                }
+                _ => Element::Instruction(InstructionPattern::parse(element)?),
+            });
+        }
+        Ok(Pattern { elements })
+    }
+}
+
+impl Pattern {
+    /// Matches the pattern against `lines` starting at the first one, and
+    /// returns how many lines the shortest match covers with its captures.
+    pub fn match_at(&self, lines: &[ObjDumpInstructionLine]) -> Option<(usize, Captures)> {
+        self.match_from(0, lines, 0, Captures::new())
+    }
+
+    fn match_from(
+        &self,
+        element: usize,
+        lines: &[ObjDumpInstructionLine],
+        position: usize,
+        mut captures: Captures,
+    ) -> Option<(usize, Captures)> {
+        let Some(current) = self.elements.get(element) else {
+            return Some((position, captures));
+        };
+        match current {
+            Element::Instruction(pattern) => {
+                let line = lines.get(position)?;
+                if !pattern.matches(&line.instruction, &mut captures) {
+                    return None;
+                }
+                self.match_from(element + 1, lines, position + 1, captures)
+            }
+            Element::Not(pattern) => {
+                let line = lines.get(position)?;
+                if pattern.matches(&line.instruction, &mut captures.clone()) {
+                    return None;
+                }
+                self.match_from(element + 1, lines, position + 1, captures)
+            }
+            Element::Gap(limit) => {
+                let remaining = lines.len() - position;
+                (0..=remaining.min(*limit)).find_map(|skipped| {
+                    self.match_from(element + 1, lines, position + skipped, captures.clone())
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchedLine {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PatternMatch {
+    pub function: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub lines: Vec<MatchedLine>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub captures: Captures,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SearchReport {
+    pub matches: Vec<PatternMatch>,
+}
+
+impl SearchReport {
+    /// Non-overlapping matches of `pattern` within each function, leftmost
+    /// and shortest first.
+    pub fn build(listing: &ObjDumpListing, pattern: &Pattern) -> Self {
+        let mut report = SearchReport::default();
+        for function in &listing.functions {
+            let lines = &function.instructions;
+            let mut start = 0;
+            while start < lines.len() {
+                let Some((end, captures)) = pattern.match_at(&lines[start..]) else {
+                    start += 1;
+                    continue;
+                };
+                let end = start + end.max(1);
+                report.matches.push(PatternMatch {
+                    function: function.name.clone(),
+                    address: lines[start].address,
+                    lines: lines[start..end]
+                        .iter()
+                        .map(|line| MatchedLine {
+                            address: line.address,
+                            text: line.instruction.to_string(),
+                        })
+                        .collect(),
+                    captures,
+                });
+                start = end;
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "
+0000000000401000 <f>:
+  401000:\t48 89 f8             \tmov    %rdi,%rax
+  401003:\t48 83 c0 08          \tadd    $0x8,%rax
+  401007:\t8b 4f 08             \tmov    0x8(%rdi),%ecx
+  40100a:\t31 d2                \txor    %edx,%edx
+  40100c:\tf7 f1                \tdiv    %ecx
+  40100e:\tff 24 c5 00 20 40 00 \tjmp    *0x402000(,%rax,8)
+  401015:\te8 e6 00 00 00       \tcall   401100 <g>
+  40101a:\tc3                   \tret
+";
+
+    /// Address, length and captures of each match of `pattern` in [`LISTING`].
+    fn search(pattern: &str) -> Vec<(u64, usize, Captures)> {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let pattern = Pattern::try_from(pattern).unwrap();
+        SearchReport::build(&listing, &pattern)
+            .matches
+            .into_iter()
+            .map(|found| (found.address, found.lines.len(), found.captures))
+            .collect()
+    }
+
+    fn captures(pairs: &[(&str, &str)]) -> Captures {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn captures_bind_and_must_agree() {
+        assert_eq!(
+            search("mov %?src,%?dst ; add $?n,%?dst"),
+            [(
+                0x401000,
+                2,
+                captures(&[("dst", "%rax"), ("n", "$0x8"), ("src", "%rdi")])
+            )]
+        );
+        // `%ecx` is captured as its 64-bit register.
+        assert_eq!(
+            search("mov ?a,%?b"),
+            [
+                (0x401000, 1, captures(&[("a", "%rdi"), ("b", "%rax")])),
+                (0x401007, 1, captures(&[("a", "0x8(%rdi)"), ("b", "%rcx")])),
+            ]
+        );
+        assert_eq!(
+            search("call <?t>"),
+            [(0x401015, 1, captures(&[("t", "401100")]))]
+        );
+        assert_eq!(search("call <g*>").len(), 1);
+        assert!(search("call <h*>").is_empty());
+        assert!(search("mov %?r,%?r").is_empty());
+        assert!(search("mov %?r,* ; add *,%?r ; mov *,%?r").is_empty());
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(
+            search("xor ; !mov ; jmp *"),
+            [(0x40100a, 3, Captures::new())]
+        );
+        assert!(search("xor ; !div").is_empty());
+        assert_eq!(
+            search("mov !%rdi,%*")
+                .iter()
+                .map(|found| found.0)
+                .collect::<Vec<_>>(),
+            [0x401007]
+        );
+    }
+
+    #[test]
+    fn gaps() {
+        assert_eq!(search("mov ; ... ; ret"), [(0x401000, 8, Captures::new())]);
+        assert_eq!(
+            search("mov ; ...{2} ; xor"),
+            [(0x401000, 4, Captures::new())]
+        );
+        assert!(search("add ; ...{1} ; div").is_empty());
+        assert_eq!(
+            search("div ; ...{0} ; jmp"),
+            [(0x40100c, 2, Captures::new())]
+        );
+    }
+
+    #[test]
+    fn memory_operands() {
+        assert_eq!(
+            search("jmp *(,%?r,8)"),
+            [(0x40100e, 1, captures(&[("r", "%rax")]))]
+        );
+        assert_eq!(search("mov 0x8(%rdi),%*").len(), 1);
+        assert_eq!(search("mov (%rdi),*").len(), 1);
+        assert!(search("mov 0x10(%rdi),*").is_empty());
+        assert!(search("mov *(%rdi,%*),*").is_empty());
+        assert!(search("jmp *0x402000(,%rax,4)").is_empty());
+        assert_eq!(search("jmp *0x402000(,%rax,8)").len(), 1);
+    }
+
+    #[test]
+    fn operand_counts() {
+        assert_eq!(search("div %*").len(), 1);
+        assert!(search("xor %*").is_empty());
+        assert_eq!(search("xor %*,...").len(), 1);
+        assert_eq!(search("xor|div").len(), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for text in [
+            "",
+            "mov ; ",
+            "; ret",
+            "...{x}",
+            "...{}",
+            "mov 0x8(%rdi,*",
+            "mov $$$",
+        ] {
+            match Pattern::try_from(text) {
+                Err(ObjumpError::InvalidArgument(_)) => {}
+                other => panic!("{:?} parsed as {:?}", text, other),
+            }
+        }
+    }
+}
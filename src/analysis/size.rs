@@ -0,0 +1,237 @@
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::symbol::demangle;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+#[derive(Debug, Serialize)]
+pub struct SymbolSize {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub demangled: Option<String>,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub size: u64,
+    pub instructions: usize,
+}
+
+/// Symbols that demangle to the same path once generic arguments are
+/// removed, i.e. the instantiations of one generic function.
+#[derive(Debug, Serialize)]
+pub struct GenericGroup {
+    pub path: String,
+    pub size: u64,
+    pub instantiations: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SizeReport {
+    pub total: u64,
+    /// Largest first.
+    pub symbols: Vec<SymbolSize>,
+    /// Largest first.
+    pub groups: Vec<GenericGroup>,
+}
+
+/// Bytes covered by the instructions of `function`. Lines without raw bytes
+/// (`--no-show-raw-insn`) extend to the next instruction.
+fn function_size(function: &ObjDumpFunction) -> u64 {
+    let instructions = &function.instructions;
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, line)| match line.bytes.len() {
+            0 => instructions
+                .get(index + 1)
+                .map_or(0, |next| next.address.saturating_sub(line.address)),
+            length => length as u64,
+        })
+        .sum()
+}
+
+/// Removes the generic arguments of every path segment:
+/// `core::ptr::drop_in_place<alloc::vec::Vec<u8>>` becomes
+/// `core::ptr::drop_in_place` and `<alloc::vec::Vec<T> as core::ops::Drop>::drop`
+/// becomes `<alloc::vec::Vec as core::ops::Drop>::drop`. A `<` that does not
+/// follow a name opens a qualified path and is kept, as is the `<` of a C++
+/// `operator<`.
+pub fn strip_generics(path: &str) -> String {
+    let mut stripped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        let follows_name = stripped
+            .chars()
+            .last()
+            .is_some_and(|last| last.is_alphanumeric() || matches!(last, '_' | '}' | ':'));
+        if c != '<' || !follows_name || stripped.ends_with("operator") {
+            stripped.push(c);
+            continue;
+        }
+        let mut depth = 1;
+        for c in chars.by_ref() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+        // Turbofish: `size_of::<T>` becomes `size_of`.
+        if stripped.ends_with("::") {
+            stripped.truncate(stripped.len() - 2);
+        }
+    }
+    stripped
+}
+
+impl SizeReport {
+    /// Measures every symbol of the listing and groups generic
+    /// instantiations by their demangled, argument-free path.
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let mut report = SizeReport::default();
+        let mut groups: HashMap<String, GenericGroup> = HashMap::new();
+        for function in &listing.functions {
+            let size = function_size(function);
+            let demangled = demangle(&function.name, true);
+            let path = strip_generics(demangled.as_deref().unwrap_or(&function.name));
+            let group = groups.entry(path.clone()).or_insert(GenericGroup {
+                path,
+                size: 0,
+                instantiations: 0,
+            });
+            group.size += size;
+            group.instantiations += 1;
+            report.total += size;
+            report.symbols.push(SymbolSize {
+                name: function.name.clone(),
+                demangled,
+                address: function.address,
+                size,
+                instructions: function.instructions.len(),
+            });
+        }
+        report
+            .symbols
+            .sort_by(|a, b| b.size.cmp(&a.size).then(a.address.cmp(&b.address)));
+        report.groups = groups.into_values().collect();
+        report
+            .groups
+            .sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        report
+    }
+
+    /// Writes the `top` largest groups (all when `None`) as a table.
+    pub fn write_groups<W: Write>(&self, out: &mut W, top: Option<usize>) -> io::Result<()> {
+        writeln!(out, "{:>10} {:>6} {:>6}  path", "bytes", "%", "count")?;
+        for group in self.groups.iter().take(top.unwrap_or(usize::MAX)) {
+            writeln!(
+                out,
+                "{:>10} {:>6.2} {:>6}  {}",
+                group.size,
+                percent(group.size, self.total),
+                group.instantiations,
+                group.path
+            )?;
+        }
+        writeln!(
+            out,
+            "{:>10} {:>6.2} {:>6}  total",
+            self.total,
+            100.0,
+            self.symbols.len()
+        )
+    }
+
+    /// Writes the `top` largest symbols (all when `None`) as a table.
+    pub fn write_symbols<W: Write>(&self, out: &mut W, top: Option<usize>) -> io::Result<()> {
+        writeln!(out, "{:>10} {:>6} {:>18}  symbol", "bytes", "%", "address")?;
+        for symbol in self.symbols.iter().take(top.unwrap_or(usize::MAX)) {
+            writeln!(
+                out,
+                "{:>10} {:>6.2} {:>#18x}  {}",
+                symbol.size,
+                percent(symbol.size, self.total),
+                symbol.address,
+                symbol.demangled.as_ref().unwrap_or(&symbol.name)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn percent(size: u64, total: u64) -> f64 {
+    match total {
+        0 => 0.0,
+        total => size as f64 * 100.0 / total as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+0000000000401000 <_RINvCs3nilrLLJwz5_1g2idhEB2_>:
+  401000:\t89 f8                \tmov    %edi,%eax
+  401002:\tc3                   \tret
+
+0000000000401010 <_RINvCs3nilrLLJwz5_1g2idyEB2_>:
+  401010:\t48 89 f8             \tmov    %rdi,%rax
+  401013:\tc3                   \tret
+
+0000000000401020 <_RNvCs3nilrLLJwz5_1g1a>:
+  401020:\t48 83 ec 08          \tsub    $0x8,%rsp
+  401024:\t48 83 c4 08          \tadd    $0x8,%rsp
+  401028:\tc3                   \tret
+";
+
+    #[test]
+    fn strips_generic_arguments() {
+        for (path, stripped) in [
+            (
+                "core::ptr::drop_in_place<alloc::vec::Vec<u8>>",
+                "core::ptr::drop_in_place",
+            ),
+            (
+                "<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop",
+                "<alloc::vec::Vec as core::ops::drop::Drop>::drop",
+            ),
+            ("core::mem::size_of::<u64>", "core::mem::size_of"),
+            (
+                "std::vector<int>::push_back(int&&)",
+                "std::vector::push_back(int&&)",
+            ),
+            (
+                "bool operator<(A const&, A const&)",
+                "bool operator<(A const&, A const&)",
+            ),
+            ("main", "main"),
+        ] {
+            assert_eq!(strip_generics(path), stripped);
+        }
+    }
+
+    #[test]
+    fn groups_instantiations_of_generic_functions() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = SizeReport::build(&listing);
+        assert_eq!(report.total, 16);
+        let symbols: Vec<_> = report
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.demangled.as_deref().unwrap(), symbol.size))
+            .collect();
+        assert_eq!(
+            symbols,
+            [("g::a", 9), ("g::id::<u64>", 4), ("g::id::<u8>", 3)]
+        );
+        let groups: Vec<_> = report
+            .groups
+            .iter()
+            .map(|group| (group.path.as_str(), group.size, group.instantiations))
+            .collect();
+        assert_eq!(groups, [("g::a", 9, 1), ("g::id", 7, 2)]);
+    }
+}
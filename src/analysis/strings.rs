@@ -0,0 +1,255 @@
+use crate::analysis::xrefs::{memory_target, next_address};
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::ObjDumpListing;
+use crate::objdump::symbol::Mangling;
+use crate::objdump::x8664_att::{X8664ATTFlow, X8664ATTOperand};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StringKind {
+    /// NUL-terminated.
+    C,
+    /// A Rust `&str`: pointer and length, without a terminator.
+    Rust,
+}
+
+impl StringKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StringKind::C => "c",
+            StringKind::Rust => "rust",
+        }
+    }
+}
+
+/// An instruction that takes the address of a string literal.
+#[derive(Debug, Serialize)]
+pub struct StringReference {
+    pub function: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub target: u64,
+    pub kind: StringKind,
+    pub text: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct StringReport {
+    /// Sorted by instruction address.
+    pub references: Vec<StringReference>,
+}
+
+/// Longest string read, in bytes.
+const MAX_LENGTH: usize = 4096;
+
+/// Instructions on either side of a `lea` searched for the length of a Rust
+/// string slice.
+const LENGTH_WINDOW: usize = 4;
+
+/// `bytes` as text, if it is UTF-8 without control characters other than
+/// whitespace and escape.
+fn text(bytes: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    text.chars()
+        .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r' | '\x1b'))
+        .then_some(text)
+}
+
+/// Integer argument registers of the System V ABI, in order.
+const ARGUMENTS: [&str; 6] = ["%rdi", "%rsi", "%rdx", "%rcx", "%r8", "%r9"];
+
+/// Address of the data an instruction points a register at, and the
+/// register: `lea` with a RIP-relative or absolute operand, or a `mov` of an
+/// absolute address in non-PIE code.
+fn pointer(instructions: &[ObjDumpInstructionLine], index: usize) -> Option<(u64, &str)> {
+    let instruction = &instructions[index].instruction;
+    match (
+        instruction.opcode.operation(),
+        instruction.operands.as_slice(),
+    ) {
+        ("lea", [X8664ATTOperand::Address(address), X8664ATTOperand::Register(register)]) => {
+            let target = memory_target(address, next_address(instructions, index))?;
+            Some((target, register.family()))
+        }
+        (
+            "mov" | "movabs",
+            [X8664ATTOperand::Immediate(immediate), X8664ATTOperand::Register(register)],
+        ) => Some((immediate.0, register.family())),
+        _ => None,
+    }
+}
+
+/// Length of the Rust string slice instruction `index` points `register`
+/// at: a constant moved into the next argument register in the same
+/// straight-line run, the way a `&str` argument is passed as pointer and
+/// length. The nearest such `mov` wins.
+fn slice_length(
+    instructions: &[ObjDumpInstructionLine],
+    index: usize,
+    register: &str,
+) -> Option<usize> {
+    let position = ARGUMENTS
+        .iter()
+        .position(|&argument| argument == register)?;
+    let length = *ARGUMENTS.get(position + 1)?;
+    let sequential =
+        |other: &usize| instructions[*other].instruction.opcode.flow() == X8664ATTFlow::Sequential;
+    let before = (index.saturating_sub(LENGTH_WINDOW)..index)
+        .rev()
+        .take_while(sequential);
+    let after =
+        (index + 1..(index + LENGTH_WINDOW + 1).min(instructions.len())).take_while(sequential);
+    let mut nearby: Vec<usize> = before.chain(after).collect();
+    nearby.sort_by_key(|&other| other.abs_diff(index));
+    nearby.into_iter().find_map(|other| {
+        let instruction = &instructions[other].instruction;
+        match (
+            instruction.opcode.operation(),
+            instruction.operands.as_slice(),
+        ) {
+            (
+                "mov",
+                [X8664ATTOperand::Immediate(immediate), X8664ATTOperand::Register(destination)],
+            ) if destination.family() == length => Some(immediate.0 as usize),
+            _ => None,
+        }
+    })
+}
+
+/// The string at `target`: NUL-terminated, or `length` bytes of a Rust
+/// literal, which are packed without terminators.
+fn string_at(
+    listing: &ObjDumpListing,
+    target: u64,
+    length: Option<usize>,
+) -> Option<(StringKind, String)> {
+    let bytes = listing.bytes_at(target)?;
+    let (kind, text) = match length {
+        Some(length) if length <= MAX_LENGTH => (StringKind::Rust, text(bytes.get(..length)?)?),
+        Some(_) => return None,
+        None => {
+            let end = bytes.iter().take(MAX_LENGTH).position(|&byte| byte == 0)?;
+            (StringKind::C, text(&bytes[..end])?)
+        }
+    };
+    (!text.is_empty()).then(|| (kind, text.to_string()))
+}
+
+impl StringReport {
+    /// Finds every `lea`/`mov` that points into a `.rodata` section and
+    /// reads the C string or Rust string slice there.
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let mut report = StringReport::default();
+        for function in &listing.functions {
+            let instructions = &function.instructions;
+            let rust = matches!(
+                Mangling::of(&function.name),
+                Some(Mangling::RustLegacy | Mangling::RustV0)
+            );
+            for index in 0..instructions.len() {
+                let Some((target, register)) = pointer(instructions, index) else {
+                    continue;
+                };
+                if !listing
+                    .section_at(target)
+                    .is_some_and(|section| section.name.starts_with(".rodata"))
+                {
+                    continue;
+                }
+                // Rust code has a length for every literal, and passes C
+                // strings so rarely that text up to a NUL is likely several
+                // literals run together.
+                let length = match rust {
+                    true => match slice_length(instructions, index, register) {
+                        Some(length) => Some(length),
+                        None => continue,
+                    },
+                    false => None,
+                };
+                let Some((kind, text)) = string_at(listing, target, length) else {
+                    continue;
+                };
+                report.references.push(StringReference {
+                    function: function.name.clone(),
+                    address: instructions[index].address,
+                    target,
+                    kind,
+                    text,
+                });
+            }
+        }
+        report.references.sort_by_key(|reference| reference.address);
+        report
+    }
+
+    /// The string the instruction at `address` points to.
+    pub fn at(&self, address: u64) -> Option<&StringReference> {
+        let index = self
+            .references
+            .binary_search_by_key(&address, |reference| reference.address)
+            .ok()?;
+        Some(&self.references[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+Contents of section .rodata:
+ 402000 68656c6c 6f20776f 726c640a 00        hello world..
+Contents of section .data:
+ 404000 01000000                             ....
+
+Disassembly of section .text:
+
+0000000000401000 <main>:
+  401000:\t48 8d 3d f9 0f 00 00 \tlea    0xff9(%rip),%rdi        # 402000 <_IO_stdin_used>
+  401007:\t48 8d 35 f2 2f 00 00 \tlea    0x2ff2(%rip),%rsi        # 404000 <counter>
+  40100e:\tc3                   \tret
+
+0000000000401010 <_RNvCs3nilrLLJwz5_1g1a>:
+  401010:\t48 8d 3d e9 0f 00 00 \tlea    0xfe9(%rip),%rdi        # 402000 <_IO_stdin_used>
+  401017:\tbe 05 00 00 00       \tmov    $0x5,%esi
+  40101c:\t48 8d 05 dd 0f 00 00 \tlea    0xfdd(%rip),%rax        # 402000 <_IO_stdin_used>
+  401023:\tc3                   \tret
+";
+
+    #[test]
+    fn annotates_c_strings_and_rust_slices() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = StringReport::build(&listing);
+        let references: Vec<_> = report
+            .references
+            .iter()
+            .map(|reference| {
+                let text = reference.text.as_str();
+                (reference.address, reference.target, reference.kind, text)
+            })
+            .collect();
+        assert_eq!(
+            references,
+            [
+                (0x401000, 0x402000, StringKind::C, "hello world\n"),
+                (0x401010, 0x402000, StringKind::Rust, "hello"),
+            ]
+        );
+        assert_eq!(
+            report.at(0x401010).unwrap().function,
+            "_RNvCs3nilrLLJwz5_1g1a"
+        );
+        // Data outside `.rodata`, and a Rust pointer without a length.
+        assert!(report.at(0x401007).is_none());
+        assert!(report.at(0x40101c).is_none());
+    }
+
+    #[test]
+    fn rejects_binary_data() {
+        assert_eq!(text(b"tab\tand newline\n"), Some("tab\tand newline\n"));
+        assert_eq!(text(b"\x01\x02"), None);
+        assert_eq!(text(b"\xff"), None);
+    }
+}
@@ -0,0 +1,381 @@
+use crate::analysis::xrefs::{memory_target, next_address};
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::x8664_att::{
+    X8664ATTAddress, X8664ATTFlow, X8664ATTInstruction, X8664ATTOperand, X8664ATTValue,
+};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JumpTableKind {
+    /// 8-byte code addresses: `jmp *0x402040(,%rdi,8)`.
+    Absolute,
+    /// 4-byte offsets from the table, added to its `lea` address in
+    /// position-independent code.
+    Relative,
+}
+
+impl JumpTableKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JumpTableKind::Absolute => "absolute",
+            JumpTableKind::Relative => "relative",
+        }
+    }
+
+    fn entry_size(&self) -> usize {
+        match self {
+            JumpTableKind::Absolute => 8,
+            JumpTableKind::Relative => 4,
+        }
+    }
+}
+
+/// A `switch` dispatched through a table of code addresses.
+#[derive(Debug, Serialize)]
+pub struct JumpTable {
+    pub function: String,
+    /// The indirect `jmp`.
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub jump: u64,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub table: u64,
+    pub kind: JumpTableKind,
+    /// Whether a `cmp`/`ja` range check gave the number of entries. Without
+    /// one, entries are read while they point into the function.
+    pub bounded: bool,
+    /// Where the range check sends out-of-range indices.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::objdump::hex::optional_address"
+    )]
+    pub default: Option<u64>,
+    /// Target of each entry, by index.
+    #[serde(serialize_with = "crate::objdump::hex::addresses")]
+    pub targets: Vec<u64>,
+}
+
+impl JumpTable {
+    /// Distinct targets of the dispatch, in address order.
+    pub fn successors(&self) -> Vec<u64> {
+        let mut successors = self.targets.clone();
+        successors.sort();
+        successors.dedup();
+        successors
+    }
+}
+
+/// An indirect jump through a register or an indexed table that is not a
+/// recognised table dispatch.
+#[derive(Debug, Serialize)]
+pub struct IndirectJump {
+    pub function: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SwitchReport {
+    pub tables: Vec<JumpTable>,
+    pub unresolved: Vec<IndirectJump>,
+}
+
+/// Instructions before an indirect jump searched for the table load and the
+/// range check.
+const WINDOW: usize = 16;
+
+/// Largest table accepted, bounded or not.
+const MAX_ENTRIES: u64 = 4096;
+
+fn family(value: &Option<X8664ATTValue>) -> Option<&str> {
+    match value {
+        Some(X8664ATTValue::Register(register)) => Some(register.family()),
+        _ => None,
+    }
+}
+
+/// `table(,%index,8)`, an absolute table of code addresses.
+fn absolute_table(address: &X8664ATTAddress) -> Option<u64> {
+    match (&address.base, &address.offset, &address.scaler) {
+        (None, Some(_), Some(scale)) if scale.0 == 8 => memory_target(address, 0),
+        _ => None,
+    }
+}
+
+/// Index of the `lea table(%rip),%base` last setting register `base` before
+/// instruction `load`. The base is often loaded once, outside the loop or
+/// `switch` it serves, so the search runs back to the function entry
+/// unless something else writes the register first.
+fn table_base(instructions: &[ObjDumpInstructionLine], load: usize, base: &str) -> Option<usize> {
+    for index in (0..load).rev() {
+        let instruction = &instructions[index].instruction;
+        let operation = instruction.opcode.operation();
+        match instruction.operands.as_slice() {
+            [X8664ATTOperand::Address(_), X8664ATTOperand::Register(register)]
+                if operation == "lea" && register.family() == base =>
+            {
+                return Some(index);
+            }
+            [.., X8664ATTOperand::Register(register)]
+                if register.family() == base
+                    && !matches!(operation, "cmp" | "test" | "bt" | "push") =>
+            {
+                return None;
+            }
+            _ if instruction.opcode.flow() == X8664ATTFlow::Call => {
+                let preserved = ["%rbx", "%rbp", "%r12", "%r13", "%r14", "%r15"];
+                if !preserved.contains(&base) {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Finds the table an indirect `jmp` at `jump` dispatches through, and the
+/// index of the instruction loading from it.
+fn locate(function: &ObjDumpFunction, jump: usize) -> Option<(u64, JumpTableKind, usize)> {
+    let instructions = &function.instructions;
+    let start = jump.saturating_sub(WINDOW);
+    let register = match instructions[jump].instruction.operands.first()? {
+        X8664ATTOperand::Indirect(operand) => match operand.as_ref() {
+            X8664ATTOperand::Address(address) => {
+                return Some((absolute_table(address)?, JumpTableKind::Absolute, jump));
+            }
+            X8664ATTOperand::Register(register) => register.family(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    for load in (start..jump).rev() {
+        let instruction = &instructions[load].instruction;
+        let [X8664ATTOperand::Address(address), X8664ATTOperand::Register(destination)] =
+            instruction.operands.as_slice()
+        else {
+            continue;
+        };
+        match instruction.opcode.operation() {
+            // `mov table(,%rax,8),%rax ; jmp *%rax`
+            "mov" if destination.family() == register => {
+                return Some((absolute_table(address)?, JumpTableKind::Absolute, load));
+            }
+            // `lea table(%rip),%rdx ; movslq (%rdx,%rax,4),%rax ;
+            // add %rdx,%rax ; jmp *%rax`, with the `add` either way round.
+            "movsxd" if address.scaler.as_ref().is_some_and(|scale| scale.0 == 4) => {
+                let base = family(&address.base)?;
+                if destination.family() != register && base != register {
+                    return None;
+                }
+                let lea = table_base(instructions, load, base)?;
+                let X8664ATTOperand::Address(address) = &instructions[lea].instruction.operands[0]
+                else {
+                    return None;
+                };
+                let table = memory_target(address, next_address(instructions, lea))?;
+                return Some((table, JumpTableKind::Relative, load));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Number of entries and default target from the `cmp $N,%reg ; ja default`
+/// range check before the table load.
+fn range_check(function: &ObjDumpFunction, load: usize) -> Option<(u64, u64)> {
+    let instructions = &function.instructions;
+    let start = load.saturating_sub(WINDOW);
+    (start + 1..load).rev().find_map(|branch| {
+        let instruction = &instructions[branch].instruction;
+        if instruction.opcode.flow() != X8664ATTFlow::ConditionalJump {
+            return None;
+        }
+        let compare = &instructions[branch - 1].instruction;
+        let limit = match (compare.opcode.operation(), compare.operands.first()) {
+            ("cmp", Some(X8664ATTOperand::Immediate(limit))) => limit.0,
+            _ => return None,
+        };
+        let target = instruction.branch_target()?;
+        let fallthrough = next_address(instructions, branch);
+        match instruction.opcode.operation() {
+            "ja" => Some((limit.saturating_add(1), target)),
+            "jae" => Some((limit, target)),
+            "jbe" => Some((limit.saturating_add(1), fallthrough)),
+            "jb" => Some((limit, fallthrough)),
+            _ => None,
+        }
+    })
+}
+
+fn entry(listing: &ObjDumpListing, table: u64, kind: JumpTableKind, index: u64) -> Option<u64> {
+    let size = kind.entry_size();
+    let value = listing.read_integer(table + index * size as u64, size)?;
+    Some(match kind {
+        JumpTableKind::Absolute => value,
+        JumpTableKind::Relative => table.wrapping_add_signed(value as u32 as i32 as i64),
+    })
+}
+
+impl JumpTable {
+    /// Recovers the table the indirect `jmp` at instruction `jump` of
+    /// `function` dispatches through, reading its entries from the data
+    /// sections of the listing.
+    pub fn recover(
+        listing: &ObjDumpListing,
+        function: &ObjDumpFunction,
+        jump: usize,
+    ) -> Option<Self> {
+        let (table, kind, load) = locate(function, jump)?;
+        let check = range_check(function, load).filter(|&(entries, _)| entries <= MAX_ENTRIES);
+        let targets: Vec<u64> = match check {
+            Some((entries, _)) => (0..entries)
+                .map(|index| entry(listing, table, kind, index))
+                .collect::<Option<_>>()?,
+            None => {
+                let last = function.instructions.last()?;
+                let end = last.address + last.bytes.len() as u64;
+                (0..MAX_ENTRIES)
+                    .map_while(|index| entry(listing, table, kind, index))
+                    .take_while(|&target| target >= function.address && target < end)
+                    .collect()
+            }
+        };
+        if targets.is_empty() {
+            return None;
+        }
+        Some(JumpTable {
+            function: function.name.clone(),
+            jump: function.instructions[jump].address,
+            table,
+            kind,
+            bounded: check.is_some(),
+            default: check.map(|(_, default)| default),
+            targets,
+        })
+    }
+}
+
+/// Whether an instruction is an indirect jump through a register or an
+/// indexed table. Jumps through a single slot, like PLT stubs through the
+/// GOT, are left out.
+fn dispatches(instruction: &X8664ATTInstruction) -> bool {
+    match instruction.operands.first() {
+        Some(X8664ATTOperand::Indirect(operand)) => match operand.as_ref() {
+            X8664ATTOperand::Register(_) => true,
+            X8664ATTOperand::Address(address) => address.offset.is_some(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+impl SwitchReport {
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let mut report = SwitchReport::default();
+        for function in &listing.functions {
+            for (index, line) in function.instructions.iter().enumerate() {
+                let instruction = &line.instruction;
+                if instruction.opcode.flow() != X8664ATTFlow::Jump || !dispatches(instruction) {
+                    continue;
+                }
+                match JumpTable::recover(listing, function, index) {
+                    Some(table) => report.tables.push(table),
+                    None => report.unresolved.push(IndirectJump {
+                        function: function.name.clone(),
+                        address: line.address,
+                    }),
+                }
+            }
+        }
+        report
+    }
+
+    /// The tables dispatching from `function`, for
+    /// [`ControlFlowGraph::build_with_tables`](crate::analysis::cfg::ControlFlowGraph::build_with_tables).
+    pub fn tables_of(&self, function: &str) -> Vec<&JumpTable> {
+        self.tables
+            .iter()
+            .filter(|table| table.function == function)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A five-case `switch` compiled with `-O2 -fPIC`, with the table it
+    /// dispatches through from `objdump -s -j .rodata`.
+    const LISTING: &str = "\
+Contents of section .rodata:
+ 2000 50f0ffff 58f0ffff 68f0ffff 30f0ffff  P...X...h...0...
+ 2010 40f0ffff                             @...
+
+Disassembly of section .text:
+
+0000000000001010 <f>:
+    1010:\t83 ff 04             \tcmp    $0x4,%edi
+    1013:\t0f 87 e7 ff ff ff    \tja     1000 <f.cold>
+    1019:\t48 8d 15 e0 0f 00 00 \tlea    0xfe0(%rip),%rdx        # 2000 <f+0xff0>
+    1020:\t89 ff                \tmov    %edi,%edi
+    1022:\t48 63 04 ba          \tmovslq (%rdx,%rdi,4),%rax
+    1026:\t48 01 d0             \tadd    %rdx,%rax
+    1029:\tff e0                \tjmp    *%rax
+    102b:\t0f 1f 44 00 00       \tnopl   0x0(%rax,%rax,1)
+    1030:\t89 f0                \tmov    %esi,%eax
+    1032:\t83 f0 05             \txor    $0x5,%eax
+    1035:\tc3                   \tret
+    1036:\t66 2e 0f 1f 84 00 00 \tcs nopw 0x0(%rax,%rax,1)
+    103d:\t00 00 00
+    1040:\t8d 04 b5 00 00 00 00 \tlea    0x0(,%rsi,4),%eax
+    1047:\tc3                   \tret
+    1048:\t0f 1f 84 00 00 00 00 \tnopl   0x0(%rax,%rax,1)
+    104f:\t00
+    1050:\t8d 46 03             \tlea    0x3(%rsi),%eax
+    1053:\tc3                   \tret
+    1054:\t0f 1f 40 00          \tnopl   0x0(%rax)
+    1058:\t8d 04 f5 00 00 00 00 \tlea    0x0(,%rsi,8),%eax
+    105f:\t29 f0                \tsub    %esi,%eax
+    1061:\tc3                   \tret
+    1062:\t66 0f 1f 44 00 00    \tnopw   0x0(%rax,%rax,1)
+    1068:\t8d 46 f5             \tlea    -0xb(%rsi),%eax
+    106b:\tc3                   \tret
+
+0000000000001070 <g>:
+    1070:\tff e7                \tjmp    *%rdi
+";
+
+    #[test]
+    fn recovers_relative_jump_tables() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = SwitchReport::build(&listing);
+        let [table] = report.tables.as_slice() else {
+            panic!("expected one table, found {:?}", report.tables);
+        };
+        assert_eq!(table.function, "f");
+        assert_eq!(table.jump, 0x1029);
+        assert_eq!(table.table, 0x2000);
+        assert_eq!(table.kind, JumpTableKind::Relative);
+        assert!(table.bounded);
+        assert_eq!(table.default, Some(0x1000));
+        assert_eq!(table.targets, [0x1050, 0x1058, 0x1068, 0x1030, 0x1040]);
+        assert_eq!(table.successors(), [0x1030, 0x1040, 0x1050, 0x1058, 0x1068]);
+        assert_eq!(report.tables_of("f").len(), 1);
+        assert_eq!(report.tables_of("g").len(), 0);
+    }
+
+    #[test]
+    fn reports_register_jumps_without_a_table() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = SwitchReport::build(&listing);
+        let unresolved: Vec<_> = report
+            .unresolved
+            .iter()
+            .map(|jump| (jump.function.as_str(), jump.address))
+            .collect();
+        assert_eq!(unresolved, [("g", 0x1070)]);
+    }
+}
@@ -0,0 +1,457 @@
+use crate::analysis::cfg::ControlFlowGraph;
+use crate::analysis::switches::{JumpTable, SwitchReport};
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::x8664_att::{
+    X8664ATTFlow, X8664ATTInstruction, X8664ATTOperand, X8664ATTRegister,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+mod table;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyscallAbi {
+    /// `syscall`.
+    X8664,
+    /// `int $0x80` and `sysenter`.
+    I386,
+}
+
+impl SyscallAbi {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyscallAbi::X8664 => "x86_64",
+            SyscallAbi::I386 => "i386",
+        }
+    }
+
+    fn of(instruction: &X8664ATTInstruction) -> Option<SyscallAbi> {
+        match instruction.opcode.operation() {
+            "syscall" => Some(SyscallAbi::X8664),
+            "sysenter" => Some(SyscallAbi::I386),
+            "int"
+                if matches!(
+                    instruction.operands.first(),
+                    Some(X8664ATTOperand::Immediate(immediate)) if immediate.0 == 0x80
+                ) =>
+            {
+                Some(SyscallAbi::I386)
+            }
+            _ => None,
+        }
+    }
+
+    /// Linux name of syscall `number` in this ABI.
+    pub fn syscall_name(&self, number: u64) -> Option<&'static str> {
+        let table = match self {
+            SyscallAbi::X8664 => table::X86_64,
+            SyscallAbi::I386 => table::I386,
+        };
+        table
+            .binary_search_by_key(&number, |&(number, _)| number)
+            .ok()
+            .map(|index| table[index].1)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyscallSite {
+    pub function: String,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub abi: SyscallAbi,
+    /// Syscall number, when the basic block (or all paths into it) loads a
+    /// constant into `%eax`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'static str>,
+    /// Argument register the number is copied from, e.g. `%rdi` in a
+    /// `syscall(2)`-style wrapper.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_register: Option<String>,
+}
+
+/// One distinct resolved syscall and where it is made.
+#[derive(Debug, Serialize)]
+pub struct SyscallUse {
+    pub abi: SyscallAbi,
+    pub number: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'static str>,
+    pub sites: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyscallReport {
+    pub sites: Vec<SyscallSite>,
+    /// Sorted by ABI and number.
+    pub syscalls: Vec<SyscallUse>,
+}
+
+impl SyscallReport {
+    pub fn build(listing: &ObjDumpListing) -> Self {
+        let mut report = SyscallReport::default();
+        let switches = SwitchReport::build(listing);
+        for function in &listing.functions {
+            if !function
+                .instructions
+                .iter()
+                .any(|line| SyscallAbi::of(&line.instruction).is_some())
+            {
+                continue;
+            }
+            report
+                .sites
+                .extend(sites(function, &switches.tables_of(&function.name)));
+        }
+        let mut syscalls: BTreeMap<(SyscallAbi, u64), SyscallUse> = BTreeMap::new();
+        for site in &report.sites {
+            let Some(number) = site.number else {
+                continue;
+            };
+            syscalls
+                .entry((site.abi, number))
+                .or_insert(SyscallUse {
+                    abi: site.abi,
+                    number,
+                    name: site.name,
+                    sites: 0,
+                })
+                .sites += 1;
+        }
+        report.syscalls = syscalls.into_values().collect();
+        report
+    }
+
+    pub fn unresolved(&self) -> impl Iterator<Item = &SyscallSite> {
+        self.sites.iter().filter(|site| site.number.is_none())
+    }
+}
+
+/// The 64-bit general purpose register an operand names, and whether the
+/// name covers at least its low 32 bits (writes to `%ax`/`%al` merge).
+fn general_register(operand: Option<&X8664ATTOperand>) -> Option<(&str, bool)> {
+    let Some(X8664ATTOperand::Register(register)) = operand else {
+        return None;
+    };
+    let family = register.family();
+    if let X8664ATTRegister::Unknown(_) = X8664ATTRegister::from(family) {
+        return None;
+    }
+    let name = register.name();
+    Some((
+        family,
+        name == family || name.starts_with("%e") || name.ends_with('d'),
+    ))
+}
+
+/// Whether the instruction writes register family `target` without naming
+/// it as destination. Syscalls return in `%rax` and clobber `%rcx` and
+/// `%r11`.
+fn writes_implicitly(instruction: &X8664ATTInstruction, target: &str) -> bool {
+    let operation = instruction.opcode.operation();
+    let widening = matches!(operation, "mul" | "div" | "idiv")
+        || operation == "imul" && instruction.operands.len() == 1;
+    match target {
+        "%rax" => {
+            widening
+                || SyscallAbi::of(instruction).is_some()
+                || matches!(
+                    operation,
+                    "cltq"
+                        | "cwtl"
+                        | "cbtw"
+                        | "cmpxchg"
+                        | "lods"
+                        | "rdtsc"
+                        | "rdtscp"
+                        | "cpuid"
+                        | "xgetbv"
+                        | "in"
+                )
+        }
+        "%rdx" => {
+            widening
+                || matches!(
+                    operation,
+                    "cqto" | "cltd" | "cwtd" | "rdtsc" | "rdtscp" | "cpuid" | "xgetbv"
+                )
+        }
+        "%rcx" => matches!(operation, "syscall" | "rdtscp" | "cpuid"),
+        "%r11" => operation == "syscall",
+        _ => false,
+    }
+}
+
+/// What backtracking from a syscall found in `%rax`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Origin<'a> {
+    Constant(u64),
+    /// The register holding the value on entry to the function.
+    Register(&'a str),
+    Unknown,
+    /// No path from the function entry reaches the block.
+    Unreached,
+}
+
+/// Predecessor blocks searched past the syscall's own block, for numbers
+/// loaded before a loop or a join.
+const DEPTH: usize = 4;
+
+/// Walks back from instruction `end` of `block` for the last write of
+/// register `target`, following register-to-register copies. At the start
+/// of the block the search continues into the predecessors, which must all
+/// agree.
+fn origin<'a>(
+    function: &'a ObjDumpFunction,
+    cfg: &ControlFlowGraph,
+    block: usize,
+    end: usize,
+    mut target: &'a str,
+    path: &mut Vec<usize>,
+) -> Origin<'a> {
+    let instructions = &function.instructions;
+    let start = cfg.blocks[block].start;
+    for position in (start..end).rev() {
+        let instruction = &instructions[position].instruction;
+        let operands = &instruction.operands;
+        let operation = instruction.opcode.operation();
+        if instruction.opcode.flow() == X8664ATTFlow::Call || writes_implicitly(instruction, target)
+        {
+            return Origin::Unknown;
+        }
+        if operation == "xchg" {
+            match [
+                general_register(operands.first()),
+                general_register(operands.get(1)),
+            ] {
+                [Some((first, true)), Some((second, true))] if first == target => target = second,
+                [Some((first, true)), Some((second, true))] if second == target => target = first,
+                [Some((first, _)), _] | [_, Some((first, _))] if first == target => {
+                    return Origin::Unknown
+                }
+                _ => {}
+            }
+            continue;
+        }
+        let Some((destination, full)) = general_register(operands.last()) else {
+            continue;
+        };
+        if destination != target || matches!(operation, "cmp" | "test" | "bt" | "push") {
+            continue;
+        }
+        if !full {
+            return Origin::Unknown;
+        }
+        match (operation, operands.as_slice()) {
+            ("mov", [X8664ATTOperand::Immediate(immediate), _]) => {
+                return Origin::Constant(immediate.0)
+            }
+            ("xor" | "sub", [source, destination]) if source == destination => {
+                return Origin::Constant(0)
+            }
+            ("mov", [source, _]) => match general_register(Some(source)) {
+                Some((register, true)) => target = register,
+                _ => return Origin::Unknown,
+            },
+            ("lea", [X8664ATTOperand::Address(address), _])
+                if address.base.is_none() && address.offset.is_none() =>
+            {
+                let displacement = address.displacement.as_ref().map_or(0, |value| value.0);
+                return Origin::Constant(displacement as u64);
+            }
+            // `push $0x3c; pop %rax`, as size-optimised code loads numbers.
+            ("pop", [_]) if position > start => {
+                let previous = &instructions[position - 1].instruction;
+                return match (previous.opcode.operation(), previous.operands.first()) {
+                    ("push", Some(X8664ATTOperand::Immediate(immediate))) => {
+                        Origin::Constant(immediate.0)
+                    }
+                    _ => Origin::Unknown,
+                };
+            }
+            _ => return Origin::Unknown,
+        }
+    }
+    if block == 0 {
+        return match target {
+            "%rax" => Origin::Unknown,
+            register => Origin::Register(register),
+        };
+    }
+    if path.len() > DEPTH {
+        return Origin::Unknown;
+    }
+    // A loop back to a block already on the path carries whatever reached
+    // it from outside the loop, so it adds nothing.
+    path.push(block);
+    let mut found = Origin::Unreached;
+    for &predecessor in &cfg.blocks[block].predecessors {
+        if path.contains(&predecessor) {
+            continue;
+        }
+        let end = cfg.blocks[predecessor].end;
+        found = match (found, origin(function, cfg, predecessor, end, target, path)) {
+            (Origin::Unreached, origin) | (origin, Origin::Unreached) => origin,
+            (previous, origin) if previous == origin => origin,
+            _ => Origin::Unknown,
+        };
+        if found == Origin::Unknown {
+            break;
+        }
+    }
+    path.pop();
+    found
+}
+
+fn sites(function: &ObjDumpFunction, tables: &[&JumpTable]) -> Vec<SyscallSite> {
+    let cfg = ControlFlowGraph::build_with_tables(function, tables);
+    let mut sites = Vec::new();
+    for (block, range) in cfg.blocks.iter().enumerate() {
+        for index in range.start..range.end {
+            let line = &function.instructions[index];
+            let Some(abi) = SyscallAbi::of(&line.instruction) else {
+                continue;
+            };
+            let (number, from_register) =
+                match origin(function, &cfg, block, index, "%rax", &mut Vec::new()) {
+                    Origin::Constant(number) => (Some(number), None),
+                    Origin::Register(register) => (None, Some(register.to_string())),
+                    Origin::Unknown | Origin::Unreached => (None, None),
+                };
+            sites.push(SyscallSite {
+                function: function.name.clone(),
+                address: line.address,
+                abi,
+                number,
+                name: number.and_then(|number| abi.syscall_name(number)),
+                from_register,
+            });
+        }
+    }
+    sites
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "
+0000000000401000 <immediate>:
+  401000:\tb8 3c 00 00 00       \tmov    $0x3c,%eax
+  401005:\t0f 05                \tsyscall
+
+0000000000401010 <zeroed>:
+  401010:\t31 c0                \txor    %eax,%eax
+  401012:\t0f 05                \tsyscall
+
+0000000000401020 <pushed>:
+  401020:\t6a 27                \tpush   $0x27
+  401022:\t58                   \tpop    %rax
+  401023:\t0f 05                \tsyscall
+
+0000000000401030 <copied>:
+  401030:\t41 b8 e7 00 00 00    \tmov    $0xe7,%r8d
+  401036:\t44 89 c0             \tmov    %r8d,%eax
+  401039:\t0f 05                \tsyscall
+
+0000000000401040 <wrapper>:
+  401040:\t89 f8                \tmov    %edi,%eax
+  401042:\t0f 05                \tsyscall
+
+0000000000401050 <loaded>:
+  401050:\t8b 07                \tmov    (%rdi),%eax
+  401052:\t0f 05                \tsyscall
+
+0000000000401060 <partial>:
+  401060:\tb0 01                \tmov    $0x1,%al
+  401062:\t0f 05                \tsyscall
+
+0000000000401070 <called>:
+  401070:\tb8 01 00 00 00       \tmov    $0x1,%eax
+  401075:\te8 86 00 00 00       \tcall   401100 <g>
+  40107a:\t0f 05                \tsyscall
+
+0000000000401080 <joined>:
+  401080:\tb8 3c 00 00 00       \tmov    $0x3c,%eax
+  401085:\t85 ff                \ttest   %edi,%edi
+  401087:\t74 01                \tje     40108a <joined+0xa>
+  401089:\t90                   \tnop
+  40108a:\t0f 05                \tsyscall
+
+0000000000401090 <diverging>:
+  401090:\tb8 3c 00 00 00       \tmov    $0x3c,%eax
+  401095:\t85 ff                \ttest   %edi,%edi
+  401097:\t74 05                \tje     40109e <diverging+0xe>
+  401099:\tb8 e7 00 00 00       \tmov    $0xe7,%eax
+  40109e:\t0f 05                \tsyscall
+
+00000000004010a0 <legacy>:
+  4010a0:\tb8 01 00 00 00       \tmov    $0x1,%eax
+  4010a5:\tcd 80                \tint    $0x80
+";
+
+    #[test]
+    fn resolves_syscall_numbers() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let report = SyscallReport::build(&listing);
+        let sites: Vec<_> = report
+            .sites
+            .iter()
+            .map(|site| {
+                (
+                    site.function.as_str(),
+                    site.abi,
+                    site.number,
+                    site.name,
+                    site.from_register.as_deref(),
+                )
+            })
+            .collect();
+        let x8664 = SyscallAbi::X8664;
+        assert_eq!(
+            sites,
+            [
+                ("immediate", x8664, Some(60), Some("exit"), None),
+                ("zeroed", x8664, Some(0), Some("read"), None),
+                ("pushed", x8664, Some(39), Some("getpid"), None),
+                ("copied", x8664, Some(231), Some("exit_group"), None),
+                ("wrapper", x8664, None, None, Some("%rdi")),
+                ("loaded", x8664, None, None, None),
+                ("partial", x8664, None, None, None),
+                ("called", x8664, None, None, None),
+                ("joined", x8664, Some(60), Some("exit"), None),
+                ("diverging", x8664, None, None, None),
+                ("legacy", SyscallAbi::I386, Some(1), Some("exit"), None),
+            ]
+        );
+        assert_eq!(report.unresolved().count(), 5);
+        let uses: Vec<_> = report
+            .syscalls
+            .iter()
+            .map(|syscall| (syscall.abi, syscall.number, syscall.sites))
+            .collect();
+        assert_eq!(
+            uses,
+            [
+                (x8664, 0, 1),
+                (x8664, 39, 1),
+                (x8664, 60, 2),
+                (x8664, 231, 1),
+                (SyscallAbi::I386, 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_are_sorted_for_lookup() {
+        for table in [table::X86_64, table::I386] {
+            assert!(table.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        }
+        assert_eq!(SyscallAbi::X8664.syscall_name(1), Some("write"));
+        assert_eq!(SyscallAbi::I386.syscall_name(4), Some("write"));
+        assert_eq!(SyscallAbi::X8664.syscall_name(100_000), None);
+    }
+}
@@ -0,0 +1,1012 @@
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::x8664_att::{
+    X8664ATTAddress, X8664ATTCategory, X8664ATTFlow, X8664ATTInstruction, X8664ATTOperand,
+    X8664ATTRegister, X8664ATTValue,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Execution resource classes an instruction is timed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimingClass {
+    IntAlu,
+    Shift,
+    Lea,
+    Multiply,
+    WideMultiply,
+    Divide,
+    Branch,
+    Jump,
+    Call,
+    Return,
+    BitCount,
+    Conditional,
+    Nop,
+    Move,
+    VectorMove,
+    VectorAlu,
+    VectorShift,
+    Shuffle,
+    CrossLane,
+    FpAdd,
+    FpMultiply,
+    Fma,
+    VectorMultiply,
+    FpDivide,
+    FpSqrt,
+    Convert,
+    FpCompare,
+    Horizontal,
+    Stack,
+    Microcoded,
+}
+
+/// Latency and the ports each uop may issue to, as a bit mask over
+/// [`Microarchitecture::ports`].
+struct Timing {
+    latency: u32,
+    uops: &'static [u32],
+}
+
+/// Execution ports, dispatch width and per-class timings of one core,
+/// rounded from published measurements (uops.info, Agner Fog's tables).
+pub struct Microarchitecture {
+    pub name: &'static str,
+    pub ports: &'static [&'static str],
+    pub dispatch_width: u32,
+    pub load_latency: u32,
+    pub vector_load_latency: u32,
+    /// Ports of a load uop.
+    load: u32,
+    /// Ports of the store-address and store-data uops.
+    store: &'static [u32],
+    timing: fn(TimingClass) -> Timing,
+}
+
+const fn timing(latency: u32, uops: &'static [u32]) -> Timing {
+    Timing { latency, uops }
+}
+
+// Skylake ports.
+const P0: u32 = 1;
+const P1: u32 = 1 << 1;
+const P2: u32 = 1 << 2;
+const P3: u32 = 1 << 3;
+const P4: u32 = 1 << 4;
+const P5: u32 = 1 << 5;
+const P6: u32 = 1 << 6;
+const P7: u32 = 1 << 7;
+const P0156: u32 = P0 | P1 | P5 | P6;
+const P015: u32 = P0 | P1 | P5;
+const P01: u32 = P0 | P1;
+const P06: u32 = P0 | P6;
+const P15: u32 = P1 | P5;
+const P237: u32 = P2 | P3 | P7;
+
+fn skylake(class: TimingClass) -> Timing {
+    match class {
+        TimingClass::IntAlu | TimingClass::Move => timing(1, &[P0156]),
+        TimingClass::Shift | TimingClass::Conditional | TimingClass::Branch => timing(1, &[P06]),
+        TimingClass::Lea => timing(1, &[P15]),
+        TimingClass::Multiply | TimingClass::BitCount => timing(3, &[P1]),
+        TimingClass::WideMultiply => timing(4, &[P1, P5]),
+        TimingClass::Divide => timing(26, &[P0, P0, P0, P0, P0, P0, P1, P5, P6, P6]),
+        TimingClass::Jump => timing(1, &[P6]),
+        TimingClass::Call => timing(1, &[P6, P237, P4]),
+        TimingClass::Return => timing(1, &[P6, P237]),
+        TimingClass::Nop | TimingClass::Stack => timing(1, &[]),
+        TimingClass::VectorMove | TimingClass::VectorAlu => timing(1, &[P015]),
+        TimingClass::VectorShift => timing(1, &[P01]),
+        TimingClass::Shuffle => timing(1, &[P5]),
+        TimingClass::CrossLane => timing(3, &[P5]),
+        TimingClass::FpAdd | TimingClass::FpMultiply | TimingClass::Fma => timing(4, &[P01]),
+        TimingClass::VectorMultiply => timing(5, &[P01]),
+        TimingClass::FpDivide => timing(11, &[P0, P0, P0, P0]),
+        TimingClass::FpSqrt => timing(12, &[P0, P0, P0, P0, P0, P0]),
+        TimingClass::Convert => timing(5, &[P01, P5]),
+        TimingClass::FpCompare => timing(3, &[P0]),
+        TimingClass::Horizontal => timing(6, &[P01, P5, P5]),
+        TimingClass::Microcoded => timing(3, &[P0156, P0156, P0156]),
+    }
+}
+
+// Zen 3 pipes.
+const ALU0: u32 = 1;
+const ALU1: u32 = 1 << 1;
+const ALU2: u32 = 1 << 2;
+const ALU3: u32 = 1 << 3;
+const AGU0: u32 = 1 << 4;
+const AGU1: u32 = 1 << 5;
+const AGU2: u32 = 1 << 6;
+const FP0: u32 = 1 << 7;
+const FP1: u32 = 1 << 8;
+const FP2: u32 = 1 << 9;
+const FP3: u32 = 1 << 10;
+const ALU: u32 = ALU0 | ALU1 | ALU2 | ALU3;
+const BRANCH: u32 = ALU0 | ALU3;
+const FP: u32 = FP0 | FP1 | FP2 | FP3;
+const FP01: u32 = FP0 | FP1;
+const FP12: u32 = FP1 | FP2;
+const FP23: u32 = FP2 | FP3;
+
+fn zen3(class: TimingClass) -> Timing {
+    match class {
+        TimingClass::IntAlu
+        | TimingClass::Move
+        | TimingClass::Lea
+        | TimingClass::BitCount
+        | TimingClass::Conditional => timing(1, &[ALU]),
+        TimingClass::Shift => timing(1, &[ALU1 | ALU2]),
+        TimingClass::Multiply => timing(3, &[ALU1]),
+        TimingClass::WideMultiply => timing(3, &[ALU1, ALU1]),
+        TimingClass::Divide => timing(14, &[ALU2, ALU2, ALU2, ALU2, ALU2, ALU2, ALU2]),
+        TimingClass::Branch | TimingClass::Jump => timing(1, &[BRANCH]),
+        TimingClass::Call => timing(1, &[BRANCH, AGU0 | AGU1]),
+        TimingClass::Return => timing(1, &[BRANCH, AGU0 | AGU1 | AGU2]),
+        TimingClass::Nop | TimingClass::Stack => timing(1, &[]),
+        TimingClass::VectorMove | TimingClass::VectorAlu => timing(1, &[FP]),
+        TimingClass::VectorShift | TimingClass::Shuffle => timing(1, &[FP12]),
+        TimingClass::CrossLane => timing(3, &[FP12]),
+        TimingClass::FpAdd => timing(3, &[FP23]),
+        TimingClass::FpMultiply => timing(3, &[FP01]),
+        TimingClass::Fma => timing(4, &[FP01]),
+        TimingClass::VectorMultiply => timing(3, &[FP0]),
+        TimingClass::FpDivide => timing(11, &[FP1, FP1, FP1, FP1]),
+        TimingClass::FpSqrt => timing(14, &[FP1, FP1, FP1, FP1, FP1]),
+        TimingClass::Convert => timing(4, &[FP23]),
+        TimingClass::FpCompare => timing(3, &[FP23]),
+        TimingClass::Horizontal => timing(6, &[FP12, FP12, FP23]),
+        TimingClass::Microcoded => timing(3, &[ALU, ALU, ALU]),
+    }
+}
+
+pub const MICROARCHITECTURES: [Microarchitecture; 2] = [
+    Microarchitecture {
+        name: "skylake",
+        ports: &["p0", "p1", "p2", "p3", "p4", "p5", "p6", "p7"],
+        dispatch_width: 4,
+        load_latency: 5,
+        vector_load_latency: 6,
+        load: P2 | P3,
+        store: &[P237, P4],
+        timing: skylake,
+    },
+    Microarchitecture {
+        name: "zen3",
+        ports: &[
+            "alu0", "alu1", "alu2", "alu3", "agu0", "agu1", "agu2", "fp0", "fp1", "fp2", "fp3",
+        ],
+        dispatch_width: 6,
+        load_latency: 4,
+        vector_load_latency: 7,
+        load: AGU0 | AGU1 | AGU2,
+        store: &[AGU0 | AGU1],
+        timing: zen3,
+    },
+];
+
+impl Microarchitecture {
+    pub fn find(name: &str) -> Option<&'static Microarchitecture> {
+        MICROARCHITECTURES.iter().find(|uarch| uarch.name == name)
+    }
+}
+
+/// Timing class of an operation, keyed by the suffix-free mnemonic.
+fn class(instruction: &X8664ATTInstruction) -> TimingClass {
+    let operation = instruction.opcode.operation();
+    let vector = operation.strip_prefix('v').unwrap_or(operation);
+    match instruction.opcode.flow() {
+        X8664ATTFlow::ConditionalJump => return TimingClass::Branch,
+        X8664ATTFlow::Jump => return TimingClass::Jump,
+        X8664ATTFlow::Call => return TimingClass::Call,
+        X8664ATTFlow::Return => return TimingClass::Return,
+        _ => {}
+    }
+    match operation {
+        "lea" => return TimingClass::Lea,
+        "imul" if instruction.operands.len() == 1 => return TimingClass::WideMultiply,
+        "mul" | "mulx" => return TimingClass::WideMultiply,
+        "imul" => return TimingClass::Multiply,
+        "div" | "idiv" => return TimingClass::Divide,
+        "popcnt" | "lzcnt" | "tzcnt" | "bsf" | "bsr" | "pdep" | "pext" => {
+            return TimingClass::BitCount
+        }
+        "push" | "pop" => return TimingClass::Stack,
+        "xchg" | "cmpxchg" | "xadd" | "cpuid" | "rdtsc" | "leave" | "enter" => {
+            return TimingClass::Microcoded
+        }
+        _ => {}
+    }
+    if operation.starts_with("cmov") || operation.starts_with("set") {
+        return TimingClass::Conditional;
+    }
+    match instruction.opcode.category() {
+        X8664ATTCategory::Nop | X8664ATTCategory::Prefix => return TimingClass::Nop,
+        X8664ATTCategory::DataTransfer | X8664ATTCategory::Conversion => return TimingClass::Move,
+        X8664ATTCategory::Shift => return TimingClass::Shift,
+        X8664ATTCategory::Arithmetic
+        | X8664ATTCategory::Logic
+        | X8664ATTCategory::Compare
+        | X8664ATTCategory::Bit
+        | X8664ATTCategory::Flags => return TimingClass::IntAlu,
+        X8664ATTCategory::Simd => {}
+        _ => return TimingClass::Microcoded,
+    }
+    if vector.starts_with("fmadd")
+        || vector.starts_with("fmsub")
+        || vector.starts_with("fnmadd")
+        || vector.starts_with("fnmsub")
+    {
+        return TimingClass::Fma;
+    }
+    if vector.starts_with("sqrt") || vector.starts_with("rsqrt") {
+        return TimingClass::FpSqrt;
+    }
+    if vector.starts_with("div") {
+        return TimingClass::FpDivide;
+    }
+    if vector.starts_with("cvt") || vector.starts_with("pmovzx") || vector.starts_with("pmovsx") {
+        return TimingClass::Convert;
+    }
+    if vector.starts_with("hadd") || vector.starts_with("hsub") || vector.starts_with("phadd") {
+        return TimingClass::Horizontal;
+    }
+    if vector.starts_with("comis") || vector.starts_with("ucomis") {
+        return TimingClass::FpCompare;
+    }
+    if vector.starts_with("perm")
+        || vector.starts_with("insert")
+        || vector.starts_with("extract")
+        || vector.starts_with("broadcast")
+        || vector.starts_with("pbroadcast")
+        || vector.starts_with("gather")
+        || vector.starts_with("pgather")
+    {
+        return TimingClass::CrossLane;
+    }
+    if vector.starts_with("pshuf")
+        || vector.starts_with("shuf")
+        || vector.starts_with("unpck")
+        || vector.starts_with("punpck")
+        || vector.starts_with("pack")
+        || vector.starts_with("palignr")
+        || vector.starts_with("pinsr")
+        || vector.starts_with("pextr")
+        || vector.starts_with("pslldq")
+        || vector.starts_with("psrldq")
+        || vector.starts_with("movhlps")
+        || vector.starts_with("movlhps")
+    {
+        return TimingClass::Shuffle;
+    }
+    if vector.starts_with("psll") || vector.starts_with("psrl") || vector.starts_with("psra") {
+        return TimingClass::VectorShift;
+    }
+    if vector.starts_with("pmul") || vector.starts_with("pmadd") || vector.starts_with("psadbw") {
+        return TimingClass::VectorMultiply;
+    }
+    if vector.starts_with("mul") {
+        return TimingClass::FpMultiply;
+    }
+    if vector.starts_with("add")
+        || vector.starts_with("sub")
+        || vector.starts_with("min")
+        || vector.starts_with("max")
+        || vector.starts_with("cmp") && vector.ends_with(['s', 'd']) && vector.len() > 4
+        || vector.starts_with("round")
+    {
+        return TimingClass::FpAdd;
+    }
+    match vector.starts_with("mov") {
+        true => TimingClass::VectorMove,
+        false => TimingClass::VectorAlu,
+    }
+}
+
+/// Dependency a register carries: its family, so that `%eax` and `%al`
+/// carry the dependency of `%rax`, with `%xmmN`/`%ymmN` folded into `%zmmN`
+/// and the x87 stack taken as one register. `None` for registers that never
+/// form dependencies here (`%rip`, segments).
+fn dependency(register: &X8664ATTRegister) -> Option<String> {
+    let family = register.family();
+    if matches!(
+        family,
+        "%rip" | "%eip" | "%cs" | "%ds" | "%es" | "%ss" | "%fs" | "%gs"
+    ) {
+        return None;
+    }
+    for prefix in ["%xmm", "%ymm"] {
+        if let Some(number) = family.strip_prefix(prefix) {
+            return Some(format!("%zmm{}", number));
+        }
+    }
+    if family.starts_with("%st") {
+        return Some("%st".to_string());
+    }
+    Some(family.to_string())
+}
+
+fn address_registers(address: &X8664ATTAddress, registers: &mut Vec<String>) {
+    for value in [&address.base, &address.offset].into_iter().flatten() {
+        if let X8664ATTValue::Register(register) = value {
+            registers.extend(dependency(register));
+        }
+    }
+}
+
+fn operand_register(operand: &X8664ATTOperand) -> Option<String> {
+    match operand {
+        X8664ATTOperand::Register(register) => dependency(register),
+        X8664ATTOperand::Decorated(operand, _) => operand_register(operand),
+        _ => None,
+    }
+}
+
+fn operand_address(operand: &X8664ATTOperand) -> Option<&X8664ATTAddress> {
+    match operand {
+        X8664ATTOperand::Address(address) => Some(address),
+        X8664ATTOperand::Indirect(operand) | X8664ATTOperand::Decorated(operand, _) => {
+            operand_address(operand)
+        }
+        _ => None,
+    }
+}
+
+const FLAGS: &str = "flags";
+
+/// Registers an instruction reads and writes, memory addresses and the
+/// flags it depends on.
+#[derive(Debug, Default)]
+struct Effects {
+    reads: Vec<String>,
+    /// Registers the address of a memory operand is computed from.
+    address_reads: Vec<String>,
+    writes: Vec<String>,
+    load: bool,
+    store: bool,
+}
+
+fn effects(instruction: &X8664ATTInstruction) -> Effects {
+    let mut effects = Effects::default();
+    let operation = instruction.opcode.operation();
+    let operands = &instruction.operands;
+    let flow = instruction.opcode.flow();
+    let category = instruction.opcode.category();
+
+    for operand in operands {
+        if let Some(address) = operand_address(operand) {
+            address_registers(address, &mut effects.address_reads);
+        }
+    }
+
+    let no_destination = matches!(
+        operation,
+        "cmp"
+            | "test"
+            | "bt"
+            | "push"
+            | "comiss"
+            | "comisd"
+            | "ucomiss"
+            | "ucomisd"
+            | "vcomiss"
+            | "vcomisd"
+            | "vucomiss"
+            | "vucomisd"
+            | "ptest"
+            | "vptest"
+    ) || operation.starts_with("prefetch")
+        || flow != X8664ATTFlow::Sequential
+        || category == X8664ATTCategory::Nop;
+    // Destinations that are written without being read first.
+    let write_only = matches!(
+        operation,
+        "mov"
+            | "movabs"
+            | "movzx"
+            | "movsx"
+            | "movsxd"
+            | "lea"
+            | "pop"
+            | "popcnt"
+            | "lzcnt"
+            | "tzcnt"
+            | "bsf"
+            | "bsr"
+            | "pshufd"
+            | "pshufhw"
+            | "pshuflw"
+            | "andn"
+            | "bextr"
+            | "shlx"
+            | "shrx"
+            | "sarx"
+            | "rorx"
+            | "pdep"
+            | "pext"
+            | "mulx"
+    ) || operation.starts_with("set")
+        || operation.starts_with("cvt")
+        || operation.starts_with("vcvt")
+        || operation.starts_with("sqrt")
+        || operation.starts_with("pmovzx")
+        || operation.starts_with("pmovsx")
+        || operation.starts_with("vmov")
+        || operation.starts_with("vbroadcast")
+        || operation.starts_with("mov") && category == X8664ATTCategory::Simd
+        || operation.starts_with('v') && operands.len() >= 3
+        || operation == "imul" && operands.len() == 3;
+
+    let (destination, sources) = match operands.split_last() {
+        Some((last, rest)) if !no_destination => (Some(last), rest),
+        _ => (None, &operands[..]),
+    };
+    for source in sources {
+        effects.reads.extend(operand_register(source));
+        effects.load |= operand_address(source).is_some()
+            && match flow {
+                X8664ATTFlow::Sequential => operation != "lea",
+                X8664ATTFlow::Jump | X8664ATTFlow::Call => true,
+                _ => false,
+            };
+    }
+    if let Some(destination) = destination {
+        match operand_register(destination) {
+            Some(register) => {
+                if !write_only {
+                    effects.reads.push(register.clone());
+                }
+                effects.writes.push(register);
+            }
+            None if operand_address(destination).is_some() => {
+                effects.store = true;
+                effects.load |= !write_only;
+            }
+            None => {}
+        }
+    }
+    if category == X8664ATTCategory::Nop || operation.starts_with("prefetch") {
+        effects.load = false;
+    }
+
+    // Zero idioms do not depend on their source.
+    let zeroing = matches!(
+        operation,
+        "xor"
+            | "sub"
+            | "pxor"
+            | "xorps"
+            | "xorpd"
+            | "vpxor"
+            | "vxorps"
+            | "vxorpd"
+            | "pcmpeqd"
+            | "psubd"
+            | "vpsubd"
+    ) && operands.len() >= 2
+        && operands.iter().all(|operand| *operand == operands[0]);
+    if zeroing {
+        effects.reads.clear();
+    }
+
+    match operation {
+        "push" => {
+            effects.reads.push("%rsp".to_string());
+            effects.writes.push("%rsp".to_string());
+            effects.store = true;
+        }
+        "pop" => {
+            effects.reads.push("%rsp".to_string());
+            effects.writes.push("%rsp".to_string());
+            effects.load = true;
+        }
+        "div" | "idiv" | "mul" => {
+            effects
+                .reads
+                .extend(["%rax".to_string(), "%rdx".to_string()]);
+            effects
+                .writes
+                .extend(["%rax".to_string(), "%rdx".to_string()]);
+        }
+        "imul" if operands.len() == 1 => {
+            effects.reads.push("%rax".to_string());
+            effects
+                .writes
+                .extend(["%rax".to_string(), "%rdx".to_string()]);
+        }
+        "cltq" | "cwtl" | "cbtw" => {
+            effects.reads.push("%rax".to_string());
+            effects.writes.push("%rax".to_string());
+        }
+        "cqto" | "cltd" | "cwtd" => {
+            effects.reads.push("%rax".to_string());
+            effects.writes.push("%rdx".to_string());
+        }
+        _ => {}
+    }
+    match flow {
+        X8664ATTFlow::Call => {
+            effects.reads.push("%rsp".to_string());
+            effects
+                .writes
+                .extend(["%rsp".to_string(), "%rax".to_string()]);
+            effects.store = true;
+        }
+        X8664ATTFlow::Return => {
+            effects.reads.push("%rsp".to_string());
+            effects.writes.push("%rsp".to_string());
+            effects.load = true;
+        }
+        _ => {}
+    }
+
+    let reads_flags = flow == X8664ATTFlow::ConditionalJump
+        || operation.starts_with("cmov")
+        || operation.starts_with("set")
+        || matches!(operation, "adc" | "sbb" | "rcl" | "rcr");
+    let writes_flags = matches!(
+        category,
+        X8664ATTCategory::Arithmetic
+            | X8664ATTCategory::Logic
+            | X8664ATTCategory::Shift
+            | X8664ATTCategory::Compare
+            | X8664ATTCategory::Bit
+    ) && !matches!(
+        operation,
+        "not" | "mulx" | "andn" | "shlx" | "shrx" | "sarx" | "rorx"
+    ) || matches!(
+        operation.strip_prefix('v').unwrap_or(operation),
+        "comiss" | "comisd" | "ucomiss" | "ucomisd" | "ptest"
+    );
+    if reads_flags {
+        effects.reads.push(FLAGS.to_string());
+    }
+    if writes_flags {
+        effects.writes.push(FLAGS.to_string());
+    }
+    effects
+}
+
+/// Cycle a value is ready at, and the (iteration, instruction) producing it.
+type Ready = (f64, Option<(usize, usize)>);
+
+#[derive(Debug, Serialize)]
+pub struct InstructionTiming {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    pub text: String,
+    pub uops: usize,
+    /// Dispatch slots taken: a load micro-fuses with the operation that
+    /// uses it, and a store's address and data uops share one slot.
+    pub fused_uops: usize,
+    pub latency: u32,
+    pub reciprocal_throughput: f64,
+    pub may_load: bool,
+    pub may_store: bool,
+    /// Cycles each port is busy per execution, in the order of `ports`.
+    pub pressure: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bottleneck {
+    Ports,
+    Dispatch,
+    Latency,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThroughputReport {
+    pub microarchitecture: &'static str,
+    pub ports: Vec<&'static str>,
+    pub dispatch_width: u32,
+    pub iterations: usize,
+    pub instructions: Vec<InstructionTiming>,
+    /// Summed port pressure of one iteration.
+    pub port_pressure: Vec<f64>,
+    pub block_reciprocal_throughput: f64,
+    /// Cycles per iteration imposed by dependencies carried between
+    /// iterations, or the length of the longest chain within one
+    /// iteration when nothing is carried.
+    pub latency_bound: f64,
+    pub loop_carried: bool,
+    pub cycles_per_iteration: f64,
+    pub total_cycles: f64,
+    pub bottleneck: Bottleneck,
+    /// Indices into `instructions` of the recurrence that sets the latency
+    /// bound, or of the longest chain when nothing is carried.
+    pub critical_path: Vec<usize>,
+}
+
+/// Distributes each uop evenly over the ports it may use, as llvm-mca
+/// does for resource groups.
+fn pressure(uarch: &Microarchitecture, uops: &[u32]) -> Vec<f64> {
+    let mut pressure = vec![0.0; uarch.ports.len()];
+    for mask in uops {
+        let ports = mask.count_ones() as f64;
+        for (port, busy) in pressure.iter_mut().enumerate() {
+            if mask & (1 << port) != 0 {
+                *busy += 1.0 / ports;
+            }
+        }
+    }
+    pressure
+}
+
+impl ThroughputReport {
+    /// Estimates the steady-state cycles per iteration of `lines` executed
+    /// back to back `iterations` times, from port pressure, dispatch width
+    /// and register dependency chains (memory dependencies are ignored).
+    pub fn build(
+        uarch: &'static Microarchitecture,
+        lines: &[&ObjDumpInstructionLine],
+        iterations: usize,
+    ) -> Self {
+        let iterations = iterations.max(2);
+        let mut instructions = Vec::new();
+        let mut dependencies = Vec::new();
+        for line in lines {
+            let instruction = &line.instruction;
+            let effects = effects(instruction);
+            // Plain loads and stores only use the load and store ports.
+            let base = match class(instruction) {
+                TimingClass::Move | TimingClass::VectorMove if effects.load || effects.store => {
+                    timing(0, &[])
+                }
+                class => (uarch.timing)(class),
+            };
+            let mut uops: Vec<u32> = base.uops.to_vec();
+            if effects.load {
+                uops.push(uarch.load);
+            }
+            if effects.store {
+                uops.extend(uarch.store);
+            }
+            let uop_count = uops.len().max(1);
+            let fused_uops = (base.uops.len()
+                + usize::from(effects.load && base.uops.is_empty())
+                + usize::from(effects.store))
+            .max(1);
+            let pressure = pressure(uarch, &uops);
+            let busiest = pressure.iter().cloned().fold(0.0, f64::max);
+            let load_latency = match effects.load {
+                true if instruction.vector_width().is_some() => uarch.vector_load_latency,
+                true => uarch.load_latency,
+                false => 0,
+            };
+            instructions.push(InstructionTiming {
+                address: line.address,
+                text: instruction
+                    .to_string()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                uops: uop_count,
+                fused_uops,
+                latency: base.latency + load_latency,
+                reciprocal_throughput: busiest.max(fused_uops as f64 / uarch.dispatch_width as f64),
+                may_load: effects.load,
+                may_store: effects.store,
+                pressure,
+            });
+            dependencies.push((effects, base.latency, load_latency));
+        }
+
+        let mut port_pressure = vec![0.0; uarch.ports.len()];
+        for instruction in &instructions {
+            for (total, busy) in port_pressure.iter_mut().zip(&instruction.pressure) {
+                *total += busy;
+            }
+        }
+        let fused_uops: usize = instructions
+            .iter()
+            .map(|instruction| instruction.fused_uops)
+            .sum();
+        let ports_bound = port_pressure.iter().cloned().fold(0.0, f64::max);
+        let dispatch_bound = fused_uops as f64 / uarch.dispatch_width as f64;
+
+        // Ready time of each register, and which instruction (iteration,
+        // index) produced it, over `iterations` unconstrained executions.
+        let mut ready: HashMap<&str, Ready> = HashMap::new();
+        let mut finished = vec![vec![(0.0, None); lines.len()]; iterations];
+        let mut iteration_end = vec![0.0; iterations];
+        for iteration in 0..iterations {
+            for (index, (effects, latency, load_latency)) in dependencies.iter().enumerate() {
+                let operand = |registers: &Vec<String>| {
+                    registers
+                        .iter()
+                        .filter_map(|register| ready.get(register.as_str()))
+                        .cloned()
+                        .fold((0.0, None), |best: Ready, candidate| {
+                            match candidate.0 > best.0 {
+                                true => candidate,
+                                false => best,
+                            }
+                        })
+                };
+                let (address_ready, address_producer) = operand(&effects.address_reads);
+                let (data_ready, data_producer) = operand(&effects.reads);
+                let address_ready = address_ready + *load_latency as f64;
+                let (start, producer) = match address_ready > data_ready {
+                    true => (address_ready, address_producer),
+                    false => (data_ready, data_producer),
+                };
+                let finish = start + *latency as f64;
+                for register in &effects.writes {
+                    ready.insert(register.as_str(), (finish, Some((iteration, index))));
+                }
+                finished[iteration][index] = (finish, producer);
+                iteration_end[iteration] = f64::max(iteration_end[iteration], finish);
+            }
+        }
+        let half = iterations / 2;
+        let slope =
+            (iteration_end[iterations - 1] - iteration_end[half - 1]) / (iterations - half) as f64;
+        let loop_carried = slope > 1e-9;
+        let latency_bound = match loop_carried {
+            true => slope,
+            false => iteration_end[0],
+        };
+
+        // Walk producers back from the instruction that finishes last. In a
+        // loop the walk ends up going round the recurrence, which is what
+        // bounds the iteration rate, so only that cycle is kept.
+        let from = match loop_carried {
+            true => iterations - 1,
+            false => 0,
+        };
+        let mut chain: Vec<usize> = Vec::new();
+        let mut cursor = finished[from]
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1 .0.total_cmp(&b.1 .0))
+            .map(|(index, _)| (from, index));
+        while let Some((iteration, index)) = cursor {
+            if let Some(position) = chain.iter().position(|&seen| seen == index) {
+                chain.drain(..position);
+                break;
+            }
+            chain.push(index);
+            cursor = finished[iteration][index].1;
+        }
+        chain.reverse();
+        if loop_carried {
+            let first = (0..chain.len()).min_by_key(|&position| chain[position]);
+            chain.rotate_left(first.unwrap_or(0));
+        }
+        let critical_path = chain;
+
+        let block = ports_bound.max(dispatch_bound);
+        let (cycles_per_iteration, bottleneck) = if loop_carried && slope > block {
+            (slope, Bottleneck::Latency)
+        } else if ports_bound >= dispatch_bound {
+            (ports_bound, Bottleneck::Ports)
+        } else {
+            (dispatch_bound, Bottleneck::Dispatch)
+        };
+        let total_cycles = iteration_end[0].max(cycles_per_iteration)
+            + cycles_per_iteration * (iterations - 1) as f64;
+
+        ThroughputReport {
+            microarchitecture: uarch.name,
+            ports: uarch.ports.to_vec(),
+            dispatch_width: uarch.dispatch_width,
+            iterations,
+            instructions,
+            port_pressure,
+            block_reciprocal_throughput: block,
+            latency_bound,
+            loop_carried,
+            cycles_per_iteration,
+            total_cycles,
+            bottleneck,
+            critical_path,
+        }
+    }
+
+    pub fn uops(&self) -> usize {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.uops)
+            .sum()
+    }
+
+    pub fn fused_uops(&self) -> usize {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.fused_uops)
+            .sum()
+    }
+
+    /// Writes the report in the layout of llvm-mca's default views:
+    /// summary, instruction info, resource pressure and critical path.
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let instructions = self.instructions.len() * self.iterations;
+        writeln!(out, "Microarchitecture: {}", self.microarchitecture)?;
+        writeln!(out, "Iterations:        {}", self.iterations)?;
+        writeln!(out, "Instructions:      {}", instructions)?;
+        writeln!(out, "Total Cycles:      {:.0}", self.total_cycles.ceil())?;
+        writeln!(out, "Total uOps:        {}", self.uops() * self.iterations)?;
+        writeln!(out)?;
+        writeln!(out, "Dispatch Width:    {}", self.dispatch_width)?;
+        writeln!(
+            out,
+            "uOps Per Cycle:    {:.2}",
+            self.fused_uops() as f64 / self.cycles_per_iteration.max(f64::EPSILON)
+        )?;
+        writeln!(
+            out,
+            "IPC:               {:.2}",
+            self.instructions.len() as f64 / self.cycles_per_iteration.max(f64::EPSILON)
+        )?;
+        writeln!(
+            out,
+            "Block RThroughput: {:.2}",
+            self.block_reciprocal_throughput
+        )?;
+        writeln!(
+            out,
+            "Latency bound:     {:.2} ({})",
+            self.latency_bound,
+            match self.loop_carried {
+                true => "loop-carried",
+                false => "single iteration",
+            }
+        )?;
+        writeln!(
+            out,
+            "Cycles/iteration:  {:.2} (bound by {})",
+            self.cycles_per_iteration,
+            match self.bottleneck {
+                Bottleneck::Ports => "port pressure",
+                Bottleneck::Dispatch => "dispatch width",
+                Bottleneck::Latency => "dependency latency",
+            }
+        )?;
+
+        writeln!(out, "\n\nInstruction Info:")?;
+        writeln!(out, "[1]: #uOps")?;
+        writeln!(out, "[2]: Latency")?;
+        writeln!(out, "[3]: RThroughput")?;
+        writeln!(out, "[4]: MayLoad")?;
+        writeln!(out, "[5]: MayStore\n")?;
+        writeln!(out, "[1]    [2]    [3]    [4]    [5]    Instructions:")?;
+        for instruction in &self.instructions {
+            writeln!(
+                out,
+                " {:<6} {:<6} {:<6.2} {:<6} {:<6} {}",
+                instruction.uops,
+                instruction.latency,
+                instruction.reciprocal_throughput,
+                if instruction.may_load { "*" } else { "" },
+                if instruction.may_store { "*" } else { "" },
+                instruction.text
+            )?;
+        }
+
+        writeln!(out, "\n\nResources:")?;
+        for (index, port) in self.ports.iter().enumerate() {
+            writeln!(out, "[{}] - {}", index, port)?;
+        }
+        writeln!(out, "\n\nResource pressure per iteration:")?;
+        for index in 0..self.ports.len() {
+            write!(out, "{:<7}", format!("[{}]", index))?;
+        }
+        writeln!(out)?;
+        for busy in &self.port_pressure {
+            write!(out, "{:<7}", pressure_cell(*busy))?;
+        }
+        writeln!(out)?;
+        writeln!(out, "\n\nResource pressure by instruction:")?;
+        for index in 0..self.ports.len() {
+            write!(out, "{:<7}", format!("[{}]", index))?;
+        }
+        writeln!(out, "Instructions:")?;
+        for instruction in &self.instructions {
+            for busy in &instruction.pressure {
+                write!(out, "{:<7}", pressure_cell(*busy))?;
+            }
+            writeln!(out, "{}", instruction.text)?;
+        }
+
+        writeln!(
+            out,
+            "\n\nCritical path ({:.2} cycles{}):",
+            self.latency_bound,
+            if self.loop_carried {
+                " per iteration"
+            } else {
+                ""
+            }
+        )?;
+        for &index in &self.critical_path {
+            let instruction = &self.instructions[index];
+            writeln!(
+                out,
+                "  {:>8x}:  +{:<4} {}",
+                instruction.address, instruction.latency, instruction.text
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn pressure_cell(busy: f64) -> String {
+    match busy {
+        busy if busy < 0.005 => "-".to_string(),
+        busy => format!("{:.2}", busy),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objdump::listing::ObjDumpListing;
+
+    fn report(dump: &str) -> ThroughputReport {
+        let listing = ObjDumpListing::parse(dump.as_bytes()).unwrap();
+        let lines: Vec<&ObjDumpInstructionLine> =
+            listing.functions[0].instructions.iter().collect();
+        ThroughputReport::build(Microarchitecture::find("skylake").unwrap(), &lines, 100)
+    }
+
+    #[test]
+    fn loop_carried_chain_bounds_the_loop() {
+        // `%rax` goes through `imul` (3 cycles) and `add` (1) every
+        // iteration; the counter chain is a single cycle.
+        let report = report(
+            "
+0000000000401000 <f>:
+  401000:\t48 0f af c1          \timul   %rcx,%rax
+  401004:\t48 83 c0 01          \tadd    $0x1,%rax
+  401008:\t48 ff cf             \tdec    %rdi
+  40100b:\t75 f3                \tjne    401000 <f>
+",
+        );
+        assert!(report.loop_carried);
+        assert_eq!(report.latency_bound, 4.0);
+        assert_eq!(report.cycles_per_iteration, 4.0);
+        assert_eq!(report.bottleneck, Bottleneck::Latency);
+        assert_eq!(report.critical_path, [0, 1]);
+    }
+
+    #[test]
+    fn sub_registers_carry_the_dependency_of_their_family() {
+        let report = report(
+            "
+0000000000401000 <f>:
+  401000:\t0f af c1             \timul   %ecx,%eax
+  401003:\t48 83 c0 01          \tadd    $0x1,%rax
+  401007:\t04 01                \tadd    $0x1,%al
+  401009:\t75 f5                \tjne    401000 <f>
+",
+        );
+        assert!(report.loop_carried);
+        assert_eq!(report.latency_bound, 5.0);
+        assert_eq!(report.critical_path, [0, 1, 2]);
+    }
+
+    #[test]
+    fn independent_block_is_port_bound() {
+        // Three multiplies on port 1 only, fed by fresh copies each time.
+        let report = report(
+            "
+0000000000401000 <f>:
+  401000:\t48 89 f0             \tmov    %rsi,%rax
+  401003:\t48 0f af c7          \timul   %rdi,%rax
+  401007:\t48 89 f2             \tmov    %rsi,%rdx
+  40100a:\t48 0f af d7          \timul   %rdi,%rdx
+  40100e:\t49 89 f0             \tmov    %rsi,%r8
+  401011:\t4c 0f af c7          \timul   %rdi,%r8
+",
+        );
+        assert!(!report.loop_carried);
+        assert_eq!(report.latency_bound, 4.0);
+        assert_eq!(report.fused_uops(), 6);
+        assert_eq!(report.port_pressure[1], 3.75);
+        assert_eq!(report.block_reciprocal_throughput, 3.75);
+        assert_eq!(report.cycles_per_iteration, 3.75);
+        assert_eq!(report.bottleneck, Bottleneck::Ports);
+    }
+}
@@ -0,0 +1,452 @@
+use crate::objdump::line::ObjDumpInstructionLine;
+use crate::objdump::listing::{ObjDumpFunction, ObjDumpListing};
+use crate::objdump::x8664_att::{
+    X8664ATTAddress, X8664ATTFlow, X8664ATTOperand, X8664ATTRegister, X8664ATTValue,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::RangeBounds;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum XrefKind {
+    Call,
+    Jump,
+    /// Conditional jump.
+    Branch,
+    Read,
+    Write,
+    /// The address is computed with `lea` or passed as an immediate.
+    Address,
+}
+
+impl XrefKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            XrefKind::Call => "call",
+            XrefKind::Jump => "jump",
+            XrefKind::Branch => "branch",
+            XrefKind::Read => "read",
+            XrefKind::Write => "write",
+            XrefKind::Address => "address",
+        }
+    }
+}
+
+/// A reference from the instruction at `from` to address `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Xref {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub from: u64,
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub to: u64,
+    pub kind: XrefKind,
+}
+
+/// An explicit register operand of the instruction at `from`, filed under
+/// the 64-bit register it is part of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RegisterReference {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub from: u64,
+    pub kind: XrefKind,
+}
+
+/// Address a memory operand refers to when it is known statically: the
+/// RIP-relative ones, relative to `next` (the address of the following
+/// instruction), and absolute ones. Indexed absolute operands such as
+/// `0x4005d0(,%rax,8)` resolve to the base of the table.
+pub fn memory_target(address: &X8664ATTAddress, next: u64) -> Option<u64> {
+    if address.segment.is_some() {
+        return None;
+    }
+    let displacement = address.displacement.as_ref()?.0;
+    match &address.base {
+        Some(X8664ATTValue::Register(register)) if register.name() == "%rip" => {
+            Some(next.wrapping_add(displacement as u64))
+        }
+        None => Some(displacement as u64),
+        _ => None,
+    }
+}
+
+/// Address of the instruction after `index`, which RIP-relative operands
+/// are relative to. Without raw bytes the following line gives it.
+pub fn next_address(instructions: &[ObjDumpInstructionLine], index: usize) -> u64 {
+    let line = &instructions[index];
+    match line.bytes.len() {
+        0 => instructions
+            .get(index + 1)
+            .map_or(line.address, |next| next.address),
+        length => line.address + length as u64,
+    }
+}
+
+/// How the instruction uses operand `index` of `count`: sources are read,
+/// the destination (last operand) written and, unless the operation only
+/// stores, read as well.
+fn access(operation: &str, index: usize, count: usize) -> &'static [XrefKind] {
+    let compares = matches!(
+        operation,
+        "cmp" | "test" | "bt" | "push" | "mul" | "div" | "idiv" | "ptest" | "vptest"
+    ) || operation.contains("comis");
+    let stores = operation.starts_with("mov")
+        || operation.starts_with("vmov")
+        || operation.starts_with("set")
+        || matches!(operation, "lea" | "pop");
+    match index + 1 == count {
+        true if compares => &[XrefKind::Read],
+        true if stores => &[XrefKind::Write],
+        true => &[XrefKind::Read, XrefKind::Write],
+        false => &[XrefKind::Read],
+    }
+}
+
+/// Cross references of a listing: which instructions branch to, call, load
+/// from, store to or take the address of each code and data address, and
+/// which use each general purpose register.
+pub struct XrefDatabase<'a> {
+    listing: &'a ObjDumpListing,
+    /// Sorted by `from`.
+    xrefs: Vec<Xref>,
+    /// Indices into `xrefs` by target address.
+    targets: BTreeMap<u64, Vec<usize>>,
+    registers: HashMap<String, Vec<RegisterReference>>,
+    /// Address, function and instruction index of every instruction,
+    /// sorted by address.
+    instructions: Vec<(u64, usize, usize)>,
+}
+
+impl<'a> XrefDatabase<'a> {
+    pub fn build(listing: &'a ObjDumpListing) -> Self {
+        let mut database = XrefDatabase {
+            listing,
+            xrefs: Vec::new(),
+            targets: BTreeMap::new(),
+            registers: HashMap::new(),
+            instructions: Vec::new(),
+        };
+        // Immediates are only references when they are a function address,
+        // as in `mov $0x401136,%edi` passing a callback.
+        let mut immediates = Vec::new();
+        for (function_index, function) in listing.functions.iter().enumerate() {
+            for (index, line) in function.instructions.iter().enumerate() {
+                database
+                    .instructions
+                    .push((line.address, function_index, index));
+                let next = next_address(&function.instructions, index);
+                database.add(line, next, &mut immediates);
+            }
+        }
+        database.instructions.sort();
+
+        let starts: HashSet<u64> = listing
+            .functions
+            .iter()
+            .map(|function| function.address)
+            .collect();
+        database.xrefs.extend(
+            immediates
+                .into_iter()
+                .filter(|(_, value)| starts.contains(value))
+                .map(|(from, to)| Xref {
+                    from,
+                    to,
+                    kind: XrefKind::Address,
+                }),
+        );
+        database.xrefs.sort_by_key(|xref| xref.from);
+        for (index, xref) in database.xrefs.iter().enumerate() {
+            database.targets.entry(xref.to).or_default().push(index);
+        }
+        database
+    }
+
+    fn add(&mut self, line: &ObjDumpInstructionLine, next: u64, immediates: &mut Vec<(u64, u64)>) {
+        let instruction = &line.instruction;
+        let operation = instruction.opcode.operation();
+        let from = line.address;
+        if let Some(to) = instruction.branch_target() {
+            let kind = match instruction.opcode.flow() {
+                X8664ATTFlow::Call => XrefKind::Call,
+                X8664ATTFlow::ConditionalJump => XrefKind::Branch,
+                _ => XrefKind::Jump,
+            };
+            self.xrefs.push(Xref { from, to, kind });
+        }
+        let count = instruction.operands.len();
+        for (index, operand) in instruction.operands.iter().enumerate() {
+            let operand = match operand {
+                X8664ATTOperand::Decorated(operand, _) => operand,
+                operand => operand,
+            };
+            let (address, kinds) = match operand {
+                X8664ATTOperand::Register(register) => {
+                    for &kind in access(operation, index, count) {
+                        self.register(from, register, kind);
+                    }
+                    continue;
+                }
+                X8664ATTOperand::Immediate(immediate) => {
+                    immediates.push((from, immediate.0));
+                    continue;
+                }
+                // `call *0x2fe2(%rip)` loads its target from memory.
+                X8664ATTOperand::Indirect(operand) => match operand.as_ref() {
+                    X8664ATTOperand::Address(address) => (address, &[XrefKind::Read][..]),
+                    X8664ATTOperand::Register(register) => {
+                        self.register(from, register, XrefKind::Read);
+                        continue;
+                    }
+                    _ => continue,
+                },
+                X8664ATTOperand::Address(address) if operation == "lea" => {
+                    (address, &[XrefKind::Address][..])
+                }
+                // Multi-byte `nopw 0x0(%rax,%rax,1)` padding touches nothing.
+                X8664ATTOperand::Address(_) if operation.starts_with("nop") => continue,
+                X8664ATTOperand::Address(address) => (address, access(operation, index, count)),
+                _ => continue,
+            };
+            for value in [&address.base, &address.offset].into_iter().flatten() {
+                if let X8664ATTValue::Register(register) = value {
+                    self.register(from, register, XrefKind::Read);
+                }
+            }
+            if let Some(to) = memory_target(address, next) {
+                for &kind in kinds {
+                    self.xrefs.push(Xref { from, to, kind });
+                }
+            }
+        }
+    }
+
+    fn register(&mut self, from: u64, register: &X8664ATTRegister, kind: XrefKind) {
+        if register.name() == "%rip" {
+            return;
+        }
+        let references = self
+            .registers
+            .entry(register.family().to_string())
+            .or_default();
+        // References are added in instruction order, so a duplicate can
+        // only come from the same instruction, at the end of the list.
+        let reference = RegisterReference { from, kind };
+        if !references
+            .iter()
+            .rev()
+            .take_while(|known| known.from == from)
+            .any(|known| *known == reference)
+        {
+            references.push(reference);
+        }
+    }
+
+    /// Every reference, sorted by referencing address.
+    pub fn xrefs(&self) -> &[Xref] {
+        &self.xrefs
+    }
+
+    /// References to `address`, in address order of the referencing
+    /// instructions.
+    pub fn xrefs_to(&self, address: u64) -> impl Iterator<Item = &Xref> {
+        self.xrefs_to_range(address..=address)
+    }
+
+    /// References into `range`, e.g. to any field of a data object.
+    pub fn xrefs_to_range(&self, range: impl RangeBounds<u64>) -> impl Iterator<Item = &Xref> {
+        let mut indices: Vec<usize> = self
+            .targets
+            .range(range)
+            .flat_map(|(_, indices)| indices.iter().copied())
+            .collect();
+        indices.sort();
+        indices.into_iter().map(|index| &self.xrefs[index])
+    }
+
+    /// References made by the instruction at `address`.
+    pub fn xrefs_from(&self, address: u64) -> &[Xref] {
+        let start = self.xrefs.partition_point(|xref| xref.from < address);
+        let end = self.xrefs.partition_point(|xref| xref.from <= address);
+        &self.xrefs[start..end]
+    }
+
+    /// Calls to the function at `address`, and tail calls from other
+    /// functions.
+    pub fn callers(&self, address: u64) -> impl Iterator<Item = &Xref> {
+        self.xrefs_to(address).filter(move |xref| match xref.kind {
+            XrefKind::Call => true,
+            XrefKind::Jump | XrefKind::Branch => self
+                .function_containing(xref.from)
+                .is_some_and(|function| function.address != address),
+            _ => false,
+        })
+    }
+
+    /// Instructions naming `register`, or any part of it: `%eax` finds uses
+    /// of `%rax`, `%ax`, `%al` and `%ah` as well.
+    pub fn register_references(&self, register: &str) -> &[RegisterReference] {
+        let register = X8664ATTRegister::from(register);
+        self.registers
+            .get(register.family())
+            .map_or(&[], |references| references.as_slice())
+    }
+
+    /// The instruction at `address`.
+    pub fn instruction(&self, address: u64) -> Option<&'a ObjDumpInstructionLine> {
+        let index = self
+            .instructions
+            .binary_search_by_key(&address, |&(address, _, _)| address)
+            .ok()?;
+        let (_, function, index) = self.instructions[index];
+        Some(&self.listing.functions[function].instructions[index])
+    }
+
+    /// The function whose instructions include `address`.
+    pub fn function_containing(&self, address: u64) -> Option<&'a ObjDumpFunction> {
+        let index = self
+            .instructions
+            .partition_point(|&(start, _, _)| start <= address);
+        let &(_, function, _) = self.instructions.get(index.checked_sub(1)?)?;
+        let function = &self.listing.functions[function];
+        let last = function.instructions.last()?;
+        (address < last.address + last.bytes.len().max(1) as u64).then_some(function)
+    }
+
+    /// The function named `name`, by symbol, demangled name or PLT stub.
+    pub fn function(&self, name: &str) -> Option<&'a ObjDumpFunction> {
+        let plt = format!("{}@plt", name);
+        self.listing.functions.iter().find(|function| {
+            function.name == name
+                || function.name == plt
+                || function.demangled().as_deref() == Some(name)
+        })
+    }
+
+    /// `<name+0x10>` form of an address, or the plain address outside any
+    /// function.
+    pub fn symbolize(&self, address: u64) -> String {
+        match self.function_containing(address) {
+            Some(function) if function.address == address => format!("<{}>", function.name),
+            Some(function) => format!("<{}+{:#x}>", function.name, address - function.address),
+            None => format!("{:#x}", address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "
+0000000000401000 <main>:
+  401000:\t8b 05 fa 0f 00 00    \tmov    0xffa(%rip),%eax        # 402000 <counter>
+  401006:\t83 c0 01             \tadd    $0x1,%eax
+  401009:\t89 05 f1 0f 00 00    \tmov    %eax,0xff1(%rip)        # 402000 <counter>
+  40100f:\t83 05 ee 0f 00 00 01 \taddl   $0x1,0xfee(%rip)        # 402004 <counter+0x4>
+  401016:\t48 8d 3d e3 0f 00 00 \tlea    0xfe3(%rip),%rdi        # 402000 <counter>
+  40101d:\tbf 30 10 40 00       \tmov    $0x401030,%edi
+  401022:\te8 09 00 00 00       \tcall   401030 <g>
+  401027:\tc3                   \tret
+
+0000000000401030 <g>:
+  401030:\te9 cb ff ff ff       \tjmp    401000 <main>
+";
+
+    fn kinds<'a>(xrefs: impl Iterator<Item = &'a Xref>) -> Vec<(u64, XrefKind)> {
+        xrefs.map(|xref| (xref.from, xref.kind)).collect()
+    }
+
+    #[test]
+    fn data_references() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let database = XrefDatabase::build(&listing);
+        assert_eq!(
+            kinds(database.xrefs_to_range(0x402000..0x402008)),
+            [
+                (0x401000, XrefKind::Read),
+                (0x401009, XrefKind::Write),
+                (0x40100f, XrefKind::Read),
+                (0x40100f, XrefKind::Write),
+                (0x401016, XrefKind::Address),
+            ]
+        );
+        assert_eq!(kinds(database.xrefs_to(0x402004)).len(), 2);
+        assert!(database.xrefs_to(u64::MAX).next().is_none());
+        assert!(database.xrefs_to_range(u64::MAX - 1..).next().is_none());
+    }
+
+    #[test]
+    fn code_references_and_callers() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let database = XrefDatabase::build(&listing);
+        // The immediate is a function address passed as a callback.
+        assert_eq!(
+            kinds(database.xrefs_to(0x401030)),
+            [(0x40101d, XrefKind::Address), (0x401022, XrefKind::Call)]
+        );
+        assert_eq!(
+            kinds(database.callers(0x401030)),
+            [(0x401022, XrefKind::Call)]
+        );
+        // `g` tail-calls `main`.
+        assert_eq!(
+            kinds(database.callers(0x401000)),
+            [(0x401030, XrefKind::Jump)]
+        );
+        assert_eq!(database.xrefs_from(0x40100f).len(), 2);
+        assert_eq!(database.function("g").unwrap().address, 0x401030);
+        assert_eq!(database.symbolize(0x401009), "<main+0x9>");
+        assert_eq!(database.symbolize(0x402000), "0x402000");
+    }
+
+    #[test]
+    fn register_references() {
+        let listing = ObjDumpListing::parse(LISTING.as_bytes()).unwrap();
+        let database = XrefDatabase::build(&listing);
+        let references = |register| -> Vec<(u64, XrefKind)> {
+            database
+                .register_references(register)
+                .iter()
+                .map(|reference| (reference.from, reference.kind))
+                .collect()
+        };
+        assert_eq!(
+            references("%rax"),
+            [
+                (0x401000, XrefKind::Write),
+                (0x401006, XrefKind::Read),
+                (0x401006, XrefKind::Write),
+                (0x401009, XrefKind::Read),
+            ]
+        );
+        assert_eq!(references("%al"), references("%rax"));
+        assert_eq!(
+            references("%edi"),
+            [(0x401016, XrefKind::Write), (0x40101d, XrefKind::Write)]
+        );
+        assert!(references("%rip").is_empty());
+    }
+
+    #[test]
+    fn builds_large_listings_in_linear_time() {
+        let mut dump = String::from("0000000000400000 <f>:\n");
+        let lines = 200_000;
+        for index in 0..lines {
+            let address = 0x400000 + 3 * index;
+            dump.push_str(&format!(
+                "  {:x}:\t48 01 c0             \tadd    %rax,%rax\n",
+                address
+            ));
+        }
+        let listing = ObjDumpListing::parse(dump.as_bytes()).unwrap();
+        let started = std::time::Instant::now();
+        let database = XrefDatabase::build(&listing);
+        let references = database.register_references("%rax");
+        assert_eq!(references.len(), 2 * lines as usize);
+        assert_eq!(references[0].kind, XrefKind::Read);
+        assert_eq!(references[1].kind, XrefKind::Write);
+        // A quadratic build takes tens of seconds here.
+        assert!(started.elapsed().as_secs() < 10);
+    }
+}
@@ -0,0 +1,518 @@
+use crate::ObjumpError;
+
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_DYNSYM: u32 = 11;
+pub const SHT_GNU_VERDEF: u32 = 0x6fff_fffd;
+pub const SHT_GNU_VERSYM: u32 = 0x6fff_ffff;
+
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_GNU_IFUNC: u8 = 10;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+
+const EM_X86_64: u16 = 62;
+
+#[derive(Debug)]
+pub struct ElfSection {
+    pub name: String,
+    pub kind: u32,
+    pub flags: u64,
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub entry_size: u64,
+    pub data: Vec<u8>,
+}
+
+impl ElfSection {
+    pub fn is_executable(&self) -> bool {
+        self.flags & SHF_EXECINSTR != 0 && self.kind != SHT_NOBITS
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        self.flags & SHF_ALLOC != 0 && address >= self.address && address < self.address + self.size
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfSymbol {
+    pub name: String,
+    pub value: u64,
+    pub size: u64,
+    pub kind: u8,
+    pub binding: u8,
+    pub section: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfRelocation {
+    pub offset: u64,
+    pub kind: u32,
+    pub symbol: u32,
+    pub addend: i64,
+}
+
+/// The parts of a little-endian ELF64 x86-64 image `objump` needs to
+/// disassemble it: section contents and the static (or, for stripped
+/// binaries, dynamic) symbol table.
+#[derive(Debug)]
+pub struct ElfFile {
+    pub entry: u64,
+    pub sections: Vec<ElfSection>,
+    pub symbols: Vec<ElfSymbol>,
+    pub dynamic_symbols: Vec<ElfSymbol>,
+}
+
+fn invalid(message: &str) -> ObjumpError {
+    ObjumpError::InvalidElf(message.to_string())
+}
+
+fn slice(data: &[u8], offset: u64, size: u64) -> Result<&[u8], ObjumpError> {
+    let start = usize::try_from(offset).map_err(|_| invalid("offset out of range"))?;
+    let end = usize::try_from(size)
+        .ok()
+        .and_then(|size| start.checked_add(size))
+        .ok_or(invalid("size out of range"))?;
+    data.get(start..end).ok_or(invalid("truncated file"))
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn string_at(table: &[u8], offset: u32) -> String {
+    let table = table.get(offset as usize..).unwrap_or_default();
+    let end = table
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(table.len());
+    String::from_utf8_lossy(&table[..end]).into_owned()
+}
+
+pub fn is_elf(data: &[u8]) -> bool {
+    data.starts_with(b"\x7fELF")
+}
+
+impl ElfFile {
+    pub fn parse(data: &[u8]) -> Result<Self, ObjumpError> {
+        if !is_elf(data) || data.len() < 64 {
+            return Err(invalid("not an ELF file"));
+        }
+        if data[4] != 2 || data[5] != 1 {
+            return Err(invalid("only little-endian ELF64 is supported"));
+        }
+        if u16_at(data, 18) != EM_X86_64 {
+            return Err(invalid("only x86-64 is supported"));
+        }
+        let entry = u64_at(data, 24);
+        let header_offset = u64_at(data, 40);
+        let header_size = u16_at(data, 58) as u64;
+        let header_count = u16_at(data, 60) as u64;
+        let names_index = u16_at(data, 62) as usize;
+        if header_count > 0 && header_size < 64 {
+            return Err(invalid("bad section header size"));
+        }
+
+        let mut sections = Vec::new();
+        let mut names = Vec::new();
+        for index in 0..header_count {
+            let header = slice(data, header_offset + index * header_size, 64)?;
+            let kind = u32_at(header, 4);
+            let offset = u64_at(header, 24);
+            let size = u64_at(header, 32);
+            names.push(u32_at(header, 0));
+            sections.push(ElfSection {
+                name: String::new(),
+                kind,
+                flags: u64_at(header, 8),
+                address: u64_at(header, 16),
+                offset,
+                size,
+                link: u32_at(header, 40),
+                info: u32_at(header, 44),
+                entry_size: u64_at(header, 56),
+                data: match kind {
+                    SHT_NOBITS => Vec::new(),
+                    _ => slice(data, offset, size)?.to_vec(),
+                },
+            });
+        }
+        if let Some(table) = sections
+            .get(names_index)
+            .map(|section| section.data.clone())
+        {
+            for (section, name) in sections.iter_mut().zip(names) {
+                section.name = string_at(&table, name);
+            }
+        }
+
+        let mut elf = ElfFile {
+            entry,
+            sections,
+            symbols: Vec::new(),
+            dynamic_symbols: Vec::new(),
+        };
+        elf.symbols = elf.symbol_table(SHT_SYMTAB);
+        elf.dynamic_symbols = elf.symbol_table(SHT_DYNSYM);
+        elf.apply_versions();
+        Ok(elf)
+    }
+
+    fn symbol_table(&self, kind: u32) -> Vec<ElfSymbol> {
+        let Some(table) = self.sections.iter().find(|section| section.kind == kind) else {
+            return Vec::new();
+        };
+        let strings = self
+            .sections
+            .get(table.link as usize)
+            .map_or(&[][..], |section| &section.data[..]);
+        table
+            .data
+            .chunks_exact(24)
+            .map(|entry| ElfSymbol {
+                name: string_at(strings, u32_at(entry, 0)),
+                kind: entry[4] & 0xf,
+                binding: entry[4] >> 4,
+                section: u16_at(entry, 6),
+                value: u64_at(entry, 8),
+                size: u64_at(entry, 16),
+            })
+            .collect()
+    }
+
+    /// Appends the symbol version of defined dynamic symbols the way
+    /// binutils prints them: `name@@VERSION`, or `name@VERSION` when hidden.
+    fn apply_versions(&mut self) {
+        let mut names = vec![(1, "Base".to_string())];
+        if let Some(definitions) = self
+            .sections
+            .iter()
+            .find(|section| section.kind == SHT_GNU_VERDEF)
+        {
+            let strings = self
+                .sections
+                .get(definitions.link as usize)
+                .map_or(&[][..], |section| &section.data[..]);
+            let data = &definitions.data;
+            let mut offset = 0;
+            while offset + 20 <= data.len() {
+                let index = u16_at(data, offset + 4);
+                let auxiliary = offset + u32_at(data, offset + 12) as usize;
+                if index > 1 && auxiliary + 8 <= data.len() {
+                    names.push((index, string_at(strings, u32_at(data, auxiliary))));
+                }
+                match u32_at(data, offset + 16) {
+                    0 => break,
+                    next => offset += next as usize,
+                }
+            }
+        }
+
+        let Some(versions) = self
+            .sections
+            .iter()
+            .find(|section| section.kind == SHT_GNU_VERSYM)
+        else {
+            return;
+        };
+        for (symbol, version) in self
+            .dynamic_symbols
+            .iter_mut()
+            .zip(versions.data.chunks_exact(2))
+        {
+            let version = u16::from_le_bytes([version[0], version[1]]);
+            let hidden = version & 0x8000 != 0;
+            if symbol.section == 0 {
+                continue;
+            }
+            if let Some((_, name)) = names.iter().find(|(index, _)| *index == version & 0x7fff) {
+                let separator = if hidden { "@" } else { "@@" };
+                symbol.name = format!("{}{}{}", symbol.name, separator, name);
+            }
+        }
+    }
+
+    pub fn section(&self, name: &str) -> Option<&ElfSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Entries of a `SHT_RELA` section.
+    pub fn relocations(&self, section: &ElfSection) -> Vec<ElfRelocation> {
+        if section.kind != SHT_RELA {
+            return Vec::new();
+        }
+        section
+            .data
+            .chunks_exact(24)
+            .map(|entry| {
+                let info = u64_at(entry, 8);
+                ElfRelocation {
+                    offset: u64_at(entry, 0),
+                    kind: info as u32,
+                    symbol: (info >> 32) as u32,
+                    addend: u64_at(entry, 16) as i64,
+                }
+            })
+            .collect()
+    }
+
+    /// Bytes mapped at `address` up to the end of the containing section.
+    pub fn bytes_at(&self, address: u64) -> Option<&[u8]> {
+        let section = self
+            .sections
+            .iter()
+            .find(|section| section.kind != SHT_NOBITS && section.contains(address))?;
+        section.data.get((address - section.address) as usize..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHT_PROGBITS: u32 = 1;
+    const SHT_STRTAB: u32 = 3;
+
+    struct Section {
+        name: &'static str,
+        kind: u32,
+        flags: u64,
+        address: u64,
+        link: u32,
+        data: Vec<u8>,
+    }
+
+    fn section(name: &'static str, kind: u32, flags: u64, address: u64, data: Vec<u8>) -> Section {
+        Section {
+            name,
+            kind,
+            flags,
+            address,
+            link: 0,
+            data,
+        }
+    }
+
+    fn symbol(name: u32, info: u8, section: u16, value: u64, size: u64) -> Vec<u8> {
+        let mut entry = name.to_le_bytes().to_vec();
+        entry.extend([info, 0]);
+        entry.extend(section.to_le_bytes());
+        entry.extend(value.to_le_bytes());
+        entry.extend(size.to_le_bytes());
+        entry
+    }
+
+    /// An ELF64 x86-64 image with the null section, `sections` and a
+    /// trailing `.shstrtab`, contents first and section headers last.
+    fn image(sections: &[Section]) -> Vec<u8> {
+        let mut names = vec![0];
+        let mut name_offsets = Vec::new();
+        for name in sections
+            .iter()
+            .map(|section| section.name)
+            .chain([".shstrtab"])
+        {
+            name_offsets.push(names.len() as u32);
+            names.extend(name.as_bytes());
+            names.push(0);
+        }
+
+        let mut data = vec![0; 64];
+        data[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        data[18..20].copy_from_slice(&EM_X86_64.to_le_bytes());
+        data[24..32].copy_from_slice(&0x401000u64.to_le_bytes());
+        let mut headers = vec![0; 64];
+        let table = section(".shstrtab", SHT_STRTAB, 0, 0, names);
+        let contents = sections.iter().chain([&table]);
+        for (section, name) in contents.zip(&name_offsets) {
+            let mut header = [0; 64];
+            header[0..4].copy_from_slice(&name.to_le_bytes());
+            header[4..8].copy_from_slice(&section.kind.to_le_bytes());
+            header[8..16].copy_from_slice(&section.flags.to_le_bytes());
+            header[16..24].copy_from_slice(&section.address.to_le_bytes());
+            header[24..32].copy_from_slice(&(data.len() as u64).to_le_bytes());
+            header[32..40].copy_from_slice(&(section.data.len() as u64).to_le_bytes());
+            header[40..44].copy_from_slice(&section.link.to_le_bytes());
+            header[56..64].copy_from_slice(&24u64.to_le_bytes());
+            headers.extend(header);
+            data.extend(&section.data);
+        }
+        let count = (sections.len() + 2) as u16;
+        let header_offset = data.len() as u64;
+        data[40..48].copy_from_slice(&header_offset.to_le_bytes());
+        data[58..60].copy_from_slice(&64u16.to_le_bytes());
+        data[60..62].copy_from_slice(&count.to_le_bytes());
+        data[62..64].copy_from_slice(&(count - 1).to_le_bytes());
+        data.extend(headers);
+        data
+    }
+
+    fn object() -> Vec<u8> {
+        let mut symbols = symbol(0, 0, 0, 0, 0);
+        symbols.extend(symbol(1, STB_GLOBAL << 4 | STT_FUNC, 1, 0x401000, 4));
+        symbols.extend(symbol(6, STB_LOCAL << 4 | STT_OBJECT, 2, 0x402000, 8));
+        let mut relocation = 0x401001u64.to_le_bytes().to_vec();
+        relocation.extend((2u64 << 32 | 2).to_le_bytes());
+        relocation.extend((-4i64).to_le_bytes());
+        image(&[
+            section(
+                ".text",
+                SHT_PROGBITS,
+                SHF_ALLOC | SHF_EXECINSTR,
+                0x401000,
+                vec![0x31, 0xc0, 0xc3, 0x90],
+            ),
+            section(
+                ".data",
+                SHT_PROGBITS,
+                SHF_ALLOC,
+                0x402000,
+                b"abcdefgh".to_vec(),
+            ),
+            section(".bss", SHT_NOBITS, SHF_ALLOC, 0x403000, Vec::new()),
+            Section {
+                link: 5,
+                ..section(".symtab", SHT_SYMTAB, 0, 0, symbols)
+            },
+            section(".strtab", SHT_STRTAB, 0, 0, b"\0main\0table\0".to_vec()),
+            Section {
+                link: 4,
+                ..section(".rela.text", SHT_RELA, 0, 0, relocation)
+            },
+        ])
+    }
+
+    #[test]
+    fn sections_and_symbols() {
+        let elf = ElfFile::parse(&object()).unwrap();
+        assert_eq!(elf.entry, 0x401000);
+        let names: Vec<&str> = elf
+            .sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "",
+                ".text",
+                ".data",
+                ".bss",
+                ".symtab",
+                ".strtab",
+                ".rela.text",
+                ".shstrtab"
+            ]
+        );
+        assert!(elf.section(".text").unwrap().is_executable());
+        assert!(!elf.section(".data").unwrap().is_executable());
+
+        let symbols: Vec<(&str, u8, u8, u64)> = elf
+            .symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    symbol.name.as_str(),
+                    symbol.kind,
+                    symbol.binding,
+                    symbol.value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            symbols,
+            [
+                ("", 0, STB_LOCAL, 0),
+                ("main", STT_FUNC, STB_GLOBAL, 0x401000),
+                ("table", STT_OBJECT, STB_LOCAL, 0x402000),
+            ]
+        );
+        assert!(elf.dynamic_symbols.is_empty());
+
+        let relocations = elf.relocations(elf.section(".rela.text").unwrap());
+        assert_eq!(relocations.len(), 1);
+        assert_eq!(relocations[0].offset, 0x401001);
+        assert_eq!((relocations[0].kind, relocations[0].symbol), (2, 2));
+        assert_eq!(relocations[0].addend, -4);
+        assert!(elf.relocations(elf.section(".text").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn bytes_at_stops_at_the_section_end() {
+        let elf = ElfFile::parse(&object()).unwrap();
+        assert_eq!(elf.bytes_at(0x401002), Some(&[0xc3, 0x90][..]));
+        assert_eq!(elf.bytes_at(0x402006), Some(&b"gh"[..]));
+        assert_eq!(elf.bytes_at(0x401004), None);
+        assert_eq!(elf.bytes_at(0x403000), None);
+    }
+
+    #[test]
+    fn symbols_stay_in_their_section_when_addresses_overlap() {
+        // Like a relocatable object: `.text` and `.rodata` both start at 0,
+        // and `.LC3` at 0x5 falls inside the `fldt` at 0x4.
+        let mut symbols = symbol(0, 0, 0, 0, 0);
+        symbols.extend(symbol(1, STB_GLOBAL << 4 | STT_FUNC, 1, 0, 12));
+        symbols.extend(symbol(6, STB_LOCAL << 4, 2, 0x5, 0));
+        let data = image(&[
+            section(
+                ".text",
+                SHT_PROGBITS,
+                SHF_ALLOC | SHF_EXECINSTR,
+                0,
+                vec![0x55, 0x48, 0x89, 0xe5, 0xdb, 0x2d, 0, 0, 0, 0, 0x5d, 0xc3],
+            ),
+            section(".rodata", SHT_PROGBITS, SHF_ALLOC, 0, vec![0; 16]),
+            Section {
+                link: 4,
+                ..section(".symtab", SHT_SYMTAB, 0, 0, symbols)
+            },
+            section(".strtab", SHT_STRTAB, 0, 0, b"\0main\0.LC3\0".to_vec()),
+        ]);
+        let elf = ElfFile::parse(&data).unwrap();
+        let listing = crate::objdump::listing::ObjDumpListing::disassemble(&elf);
+        assert_eq!(listing.functions.len(), 1);
+        assert_eq!(listing.functions[0].name, "main");
+        let operations: Vec<&str> = listing.functions[0]
+            .instructions
+            .iter()
+            .map(|line| line.instruction.opcode.operation())
+            .collect();
+        assert_eq!(operations, ["push", "mov", "fld", "pop", "ret"]);
+    }
+
+    #[test]
+    fn rejects_unsupported_and_truncated_files() {
+        let error = |data: &[u8]| match ElfFile::parse(data) {
+            Err(ObjumpError::InvalidElf(message)) => message,
+            other => panic!("expected an ELF error, got {:?}", other),
+        };
+        assert_eq!(error(b"\x7fELF"), "not an ELF file");
+        let mut data = object();
+        data[4] = 1;
+        assert_eq!(error(&data), "only little-endian ELF64 is supported");
+        let mut data = object();
+        data[18] = 3;
+        assert_eq!(error(&data), "only x86-64 is supported");
+        let data = object();
+        assert_eq!(error(&data[..data.len() - 1]), "truncated file");
+    }
+}
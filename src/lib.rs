@@ -0,0 +1,61 @@
+pub mod analysis;
+pub mod elf;
+pub mod objdump;
+
+use std::io;
+
+#[derive(Debug)]
+pub enum ObjumpError {
+    ParseError(std::num::ParseIntError),
+    RegexError(regex::Error),
+    InvalidInstruction(String),
+    IOError(io::Error),
+    JsonError(serde_json::Error),
+    UnknownCommand(String),
+    InvalidArgument(String),
+    InvalidElf(String),
+    /// An error reading the given 1-based line of the input.
+    Line(usize, Box<ObjumpError>),
+}
+
+impl std::fmt::Display for ObjumpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjumpError::ParseError(err) => write!(f, "invalid number: {}", err),
+            ObjumpError::RegexError(err) => write!(f, "invalid regex: {}", err),
+            ObjumpError::InvalidInstruction(instruction) => {
+                write!(f, "invalid instruction: {}", instruction)
+            }
+            ObjumpError::IOError(err) => write!(f, "{}", err),
+            ObjumpError::JsonError(err) => write!(f, "{}", err),
+            ObjumpError::UnknownCommand(command) => write!(f, "unknown command: {}", command),
+            ObjumpError::InvalidArgument(argument) => write!(f, "invalid argument: {}", argument),
+            ObjumpError::InvalidElf(message) => write!(f, "invalid ELF file: {}", message),
+            ObjumpError::Line(line, err) => write!(f, "line {}: {}", line, err),
+        }
+    }
+}
+
+impl From<regex::Error> for ObjumpError {
+    fn from(err: regex::Error) -> Self {
+        ObjumpError::RegexError(err)
+    }
+}
+
+impl From<std::num::ParseIntError> for ObjumpError {
+    fn from(err: std::num::ParseIntError) -> Self {
+        ObjumpError::ParseError(err)
+    }
+}
+
+impl From<io::Error> for ObjumpError {
+    fn from(err: io::Error) -> Self {
+        ObjumpError::IOError(err)
+    }
+}
+
+impl From<serde_json::Error> for ObjumpError {
+    fn from(err: serde_json::Error) -> Self {
+        ObjumpError::JsonError(err)
+    }
+}
@@ -1,30 +1,62 @@
+use objump::{analysis, elf, objdump, ObjumpError};
 use std::{
     collections::HashMap,
-    io::{self, Write},
+    io::{self, BufRead, Read, Write},
 };
 
-mod objdump;
-
 fn main() -> Result<(), ObjumpError> {
+    match std::env::args().nth(1).as_deref() {
+        None => discover_unknown_opcodes(),
+        Some("parse") => print_parsed_lines(),
+        Some("roundtrip") => check_roundtrip(),
+        Some("verify") => verify_bytes(),
+        Some("intel") => print_intel(),
+        Some("demangle") => print_demangled(),
+        Some("disasm") => print_disassembly(),
+        Some("loops") => report_loops(),
+        Some("mix") => report_mix(),
+        Some("level") => report_level(),
+        Some("continuity") => report_continuity(),
+        Some("diff") => report_diff(),
+        Some("size") => report_size(),
+        Some("gadgets") => report_gadgets(),
+        Some("audit") => report_hardening(),
+        Some("mca") => report_throughput(),
+        Some("frame") => report_frames(),
+        Some("syscalls") => report_syscalls(),
+        Some("xrefs") => report_xrefs(),
+        Some("grep") => search_pattern(),
+        Some("switches") => report_switches(),
+        Some("sections") => report_sections(),
+        Some("strings") => report_strings(),
+        Some("bench") => run_benchmark(),
+        Some(command) => Err(ObjumpError::UnknownCommand(command.to_string())),
+    }
+}
+
+fn discover_unknown_opcodes() -> Result<(), ObjumpError> {
     let mut opecodemap: HashMap<String, String> = std::collections::HashMap::new();
     for line in io::stdin().lines() {
-        match objdump::line::parse_objdump_line(&line?) {
-            Ok(objdump::line::ObjDumpLineType::Instruction(instruction)) => match instruction.instruction.opcode {
-                objdump::x8664_att::X8664ATTOpcode::Unknown(opcode) => {
-                    if opecodemap.insert(opcode.clone(), capitalize(&opcode)) == None {
-                        println!(
-                            "\t\"{}\" => X8664ATTOpcode::{},",
-                            opcode,
-                            capitalize(&opcode)
-                        );
-                        eprintln!("\t{},", capitalize(&opcode));
-                        io::stdout().flush().unwrap();
-                        io::stderr().flush().unwrap();
-                    }
+        if let Ok(objdump::line::ObjDumpLineType::Instruction(instruction)) =
+            objdump::line::parse_objdump_line(&line?)
+        {
+            if let objdump::x8664_att::X8664ATTOpcode::Unknown(opcode) =
+                instruction.instruction.opcode
+            {
+                if opecodemap
+                    .insert(opcode.clone(), capitalize(&opcode))
+                    .is_none()
+                {
+                    println!(
+                        "\t\"{}\" => X8664ATTOpcode::{},",
+                        opcode,
+                        capitalize(&opcode)
+                    );
+                    eprintln!("\t{},", capitalize(&opcode));
+                    io::stdout().flush().unwrap();
+                    io::stderr().flush().unwrap();
                 }
-                _ => {}
-            },
-            _ => {}
+            }
         }
     }
 
@@ -39,28 +71,1558 @@ fn capitalize(s: &str) -> String {
     }
 }
 
-#[derive(Debug)]
-enum ObjumpError {
-    ParseError(std::num::ParseIntError),
-    RegexError(regex::Error),
-    InvalidInstruction(String),
-    IOError(io::Error),
+/// Streams every parsed line as JSON, either as one array (`--json`) or as
+/// one object per line (`--ndjson`). Lines the parser rejects are emitted as
+/// `{"type": "error", ...}` objects instead of stopping the stream.
+fn print_parsed_lines() -> Result<(), ObjumpError> {
+    let ndjson = match std::env::args().nth(2).as_deref() {
+        Some("--ndjson") => true,
+        Some("--json") | None => false,
+        Some(option) => return Err(ObjumpError::InvalidArgument(option.to_string())),
+    };
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    write_parsed_lines(io::stdin().lock(), &mut out, ndjson)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_parsed_lines<R: BufRead, W: Write>(
+    input: R,
+    out: &mut W,
+    ndjson: bool,
+) -> Result<(), ObjumpError> {
+    let mut first = true;
+    if !ndjson {
+        write!(out, "[")?;
+    }
+    for (number, parsed) in objdump::line::ObjDumpLines::new(input) {
+        let parsed = match parsed {
+            Ok(objdump::line::ObjDumpLineType::Blank) => continue,
+            Err(ObjumpError::IOError(err)) => return Err(err.into()),
+            parsed => parsed,
+        };
+        if !ndjson {
+            if !first {
+                write!(out, ",")?;
+            }
+            writeln!(out)?;
+        }
+        match parsed {
+            Ok(parsed) => serde_json::to_writer(&mut *out, &parsed)?,
+            Err(err) => serde_json::to_writer(
+                &mut *out,
+                &serde_json::json!({
+                    "type": "error",
+                    "value": { "line": number, "message": err.to_string() },
+                }),
+            )?,
+        }
+        if ndjson {
+            writeln!(out)?;
+        }
+        first = false;
+    }
+    if !ndjson {
+        writeln!(out, "\n]")?;
+    }
+    Ok(())
+}
+
+/// Re-renders every instruction of each file given on the command line (or
+/// of stdin) and reports the ones that do not survive parse -> print.
+fn check_roundtrip() -> Result<(), ObjumpError> {
+    let paths: Vec<String> = std::env::args().skip(2).collect();
+    let mut reports = Vec::new();
+    if paths.is_empty() {
+        reports.push((
+            "-".to_string(),
+            objdump::roundtrip::RoundTripReport::check(io::stdin().lock())?,
+        ));
+    }
+    for path in paths {
+        let file = io::BufReader::new(std::fs::File::open(&path)?);
+        reports.push((path, objdump::roundtrip::RoundTripReport::check(file)?));
+    }
+
+    let mut out = io::stdout().lock();
+    let mut report = objdump::roundtrip::RoundTripReport::default();
+    for (path, checked) in reports {
+        for mismatch in &checked.mismatches {
+            writeln!(out, "{}:{}", path, mismatch.line_number)?;
+            writeln!(out, "- {}", mismatch.original)?;
+            writeln!(out, "+ {}", mismatch.rendered)?;
+        }
+        for (line_number, err) in &checked.errors {
+            writeln!(out, "{}:{}: {}", path, line_number, err)?;
+        }
+        report.merge(checked);
+    }
+    writeln!(
+        out,
+        "{} instructions, {} mismatches, {} parse errors",
+        report.instructions,
+        report.mismatches.len(),
+        report.errors.len()
+    )?;
+    if !report.mismatches.is_empty() || !report.errors.is_empty() {
+        out.flush()?;
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Decodes the bytes of every instruction of each file given on the command
+/// line (or of stdin) and reports where the decoder and objdump disagree.
+fn verify_bytes() -> Result<(), ObjumpError> {
+    let paths: Vec<String> = std::env::args().skip(2).collect();
+    let mut reports = Vec::new();
+    if paths.is_empty() {
+        reports.push((
+            "-".to_string(),
+            objdump::verify::VerifyReport::check(io::stdin().lock())?,
+        ));
+    }
+    for path in paths {
+        let file = io::BufReader::new(std::fs::File::open(&path)?);
+        reports.push((path, objdump::verify::VerifyReport::check(file)?));
+    }
+
+    let mut out = io::stdout().lock();
+    let mut report = objdump::verify::VerifyReport::default();
+    for (path, checked) in reports {
+        for disagreement in &checked.disagreements {
+            let bytes: Vec<String> = disagreement
+                .bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            writeln!(
+                out,
+                "{}:{}: {:#x}: {} ({})",
+                path,
+                disagreement.line_number,
+                disagreement.address,
+                bytes.join(" "),
+                disagreement.field
+            )?;
+            writeln!(out, "- {}", disagreement.objdump)?;
+            writeln!(out, "+ {}", disagreement.decoded)?;
+        }
+        for (line_number, err) in &checked.errors {
+            writeln!(out, "{}:{}: {}", path, line_number, err)?;
+        }
+        report.merge(checked);
+    }
+    writeln!(
+        out,
+        "{} instructions, {} disagreements, {} parse errors",
+        report.instructions,
+        report.disagreements.len(),
+        report.errors.len()
+    )?;
+    if !report.disagreements.is_empty() || !report.errors.is_empty() {
+        out.flush()?;
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Copies the listing from stdin, replacing each AT&T instruction with its
+/// Intel-syntax rendering and leaving every other line untouched.
+fn print_intel() -> Result<(), ObjumpError> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    for line in io::stdin().lines() {
+        let line = line?;
+        match objdump::line::parse_objdump_line(&line) {
+            Ok(objdump::line::ObjDumpLineType::Instruction(parsed)) => {
+                let (code, comment) = match line.split_once('#') {
+                    Some((code, comment)) => (code, Some(comment)),
+                    None => (line.as_str(), None),
+                };
+                let column = match code.match_indices('\t').nth(1) {
+                    Some((index, _)) => index + 1,
+                    None => code.find('\t').map_or(0, |index| index + 1),
+                };
+                write!(out, "{}{}", &code[..column], parsed.instruction.to_intel())?;
+                match comment {
+                    Some(comment) => writeln!(out, "        #{}", comment)?,
+                    None => writeln!(out)?,
+                }
+            }
+            _ => writeln!(out, "{}", line)?,
+        }
+    }
+    out.flush()?;
+    Ok(())
 }
 
-impl From<regex::Error> for ObjumpError {
-    fn from(err: regex::Error) -> Self {
-        ObjumpError::RegexError(err)
+/// Copies the listing from stdin with the symbol of every function header and
+/// `<...>` annotation demangled. `--no-hash` drops Rust hashes.
+fn print_demangled() -> Result<(), ObjumpError> {
+    let strip_hash = match std::env::args().nth(2).as_deref() {
+        Some("--no-hash") => true,
+        None => false,
+        Some(option) => return Err(ObjumpError::InvalidArgument(option.to_string())),
+    };
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    for line in io::stdin().lines() {
+        let line = line?;
+        let annotation = line
+            .find('<')
+            .zip(line.rfind('>'))
+            .filter(|(start, end)| start < end)
+            .and_then(|(start, end)| {
+                let symbol = objdump::symbol::SymbolReference::parse(&line[start..=end])?;
+                Some((start, end, symbol))
+            });
+        match annotation {
+            Some((start, end, symbol)) => writeln!(
+                out,
+                "{}{}{}",
+                &line[..start],
+                symbol.render(strip_hash),
+                &line[end + 1..]
+            )?,
+            None => writeln!(out, "{}", line)?,
+        }
     }
+    out.flush()?;
+    Ok(())
+}
+
+/// Reads the listing to analyse: an ELF file is disassembled natively, any
+/// other file (or stdin when no path is given) is parsed as objdump text.
+/// Files are memory-mapped, and their text parsed on all cores. Lines the
+/// parser rejects are reported on stderr.
+fn read_listing(path: Option<&str>) -> Result<objdump::listing::ObjDumpListing, ObjumpError> {
+    let listing = match path {
+        Some(path) => {
+            let file = std::fs::File::open(path)?;
+            if file.metadata()?.len() == 0 {
+                return Ok(objdump::listing::ObjDumpListing::default());
+            }
+            // SAFETY: the mapping is only read, while the listing is built. A
+            // file truncated by another process meanwhile can still fault the
+            // process.
+            let data = unsafe { memmap2::Mmap::map(&file)? };
+            if elf::is_elf(&data) {
+                let elf = elf::ElfFile::parse(&data)?;
+                return Ok(objdump::listing::ObjDumpListing::disassemble(&elf));
+            }
+            objdump::listing::ObjDumpListing::parse_parallel(&data)?
+        }
+        None => objdump::listing::ObjDumpListing::parse(io::stdin().lock())?,
+    };
+    for (line_number, err) in &listing.errors {
+        eprintln!("{}:{}: {}", path.unwrap_or("-"), line_number, err);
+    }
+    Ok(listing)
+}
+
+/// Prints the native disassembly of an ELF file in `objdump -d` format.
+fn print_disassembly() -> Result<(), ObjumpError> {
+    let path = std::env::args()
+        .nth(2)
+        .ok_or(ObjumpError::InvalidArgument("missing ELF path".to_string()))?;
+    let elf = elf::ElfFile::parse(&std::fs::read(&path)?)?;
+    let listing = objdump::listing::ObjDumpListing::disassemble(&elf);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    writeln!(out, "\n{}:     file format elf64-x86-64\n", path)?;
+    let mut section = None;
+    for function in &listing.functions {
+        if section != Some(&function.section) {
+            writeln!(out, "\nDisassembly of section {}:", function.section)?;
+            section = Some(&function.section);
+        }
+        writeln!(out, "\n{:016x} <{}>:", function.address, function.name)?;
+        for line in &function.instructions {
+            writeln!(out, "{}", line)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
 }
 
-impl From<std::num::ParseIntError> for ObjumpError {
-    fn from(err: std::num::ParseIntError) -> Self {
-        ObjumpError::ParseError(err)
+fn report_loops() -> Result<(), ObjumpError> {
+    let mut listing = read_listing(std::env::args().nth(2).as_deref())?;
+    let switches = analysis::switches::SwitchReport::build(&listing);
+    for function in &mut listing.functions {
+        let tables = switches.tables_of(&function.name);
+        let (cfg, forest) = analysis::loops::annotate_loop_depths(function, &tables);
+        if forest.loops.is_empty() {
+            continue;
+        }
+        println!("{} ({:#x})", function.name, function.address);
+        for natural in &forest.loops {
+            let instructions: Vec<_> = natural
+                .blocks
+                .iter()
+                .flat_map(|&block| {
+                    &function.instructions[cfg.blocks[block].start..cfg.blocks[block].end]
+                })
+                .collect();
+            let simd = instructions
+                .iter()
+                .filter_map(|line| line.instruction.vector_width())
+                .max();
+            println!(
+                "\tloop {:#x}\tdepth {}\tblocks {}\tinstructions {}\tsimd {}",
+                function.instructions[cfg.blocks[natural.header].start].address,
+                natural.depth,
+                natural.blocks.len(),
+                instructions.len(),
+                simd.map_or("-".to_string(), |width| width.to_string()),
+            );
+        }
     }
+    Ok(())
 }
 
-impl From<io::Error> for ObjumpError {
-    fn from(err: io::Error) -> Self {
-        ObjumpError::IOError(err)
+fn report_mix() -> Result<(), ObjumpError> {
+    let options: Vec<String> = std::env::args().skip(2).collect();
+    let has = |option: &str| options.iter().any(|argument| argument == option);
+    let path = options.iter().find(|argument| !argument.starts_with("--"));
+    let listing = read_listing(path.map(String::as_str))?;
+    let report = analysis::mix::MixReport::build(&listing);
+    let mut out = io::stdout().lock();
+    if has("--json") {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+    writeln!(out, "overall")?;
+    analysis::mix::write_table(&mut out, &report.overall, has("--opcodes"))?;
+    if has("--functions") {
+        for function in &report.functions {
+            writeln!(out, "{} ({})", function.name, function.address)?;
+            analysis::mix::write_table(&mut out, &function.mix, has("--opcodes"))?;
+        }
+    }
+    Ok(())
+}
+
+fn report_level() -> Result<(), ObjumpError> {
+    let mut allow = Vec::new();
+    let mut max = None;
+    let mut json = false;
+    let mut path = None;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--allow" => allow.extend(options.next()),
+            "--max" => {
+                let level = options.next().unwrap_or_default();
+                max = Some(
+                    analysis::level::MicroarchLevel::parse(&level)
+                        .ok_or(ObjumpError::InvalidArgument(level))?,
+                );
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let report = analysis::level::LevelReport::build(&listing, &allow);
+    let mut out = io::stdout().lock();
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else {
+        writeln!(out, "required {}", report.required.name())?;
+        for offender in report.offenders.iter().chain(&report.outside_levels) {
+            writeln!(
+                out,
+                "  {:<10} {:<11} {}\t{} <{}>",
+                offender.level.name(),
+                offender.extension,
+                offender.address,
+                offender.mnemonic,
+                offender.function
+            )?;
+        }
+        if report.ignored_functions > 0 {
+            writeln!(
+                out,
+                "  ignored {} allowed functions",
+                report.ignored_functions
+            )?;
+        }
+    }
+
+    if max.is_some_and(|max| report.required > max) {
+        out.flush()?;
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Reports gaps, overlaps, misaligned branch targets and data-like runs in
+/// the listing, exiting with status 1 when any are found.
+fn report_continuity() -> Result<(), ObjumpError> {
+    let options: Vec<String> = std::env::args().skip(2).collect();
+    let path = options.iter().find(|argument| !argument.starts_with("--"));
+    let listing = read_listing(path.map(String::as_str))?;
+    let report = analysis::continuity::ContinuityReport::build(&listing);
+    let mut out = io::stdout().lock();
+    if options.iter().any(|option| option == "--json") {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else {
+        for function in &report.functions {
+            writeln!(out, "{} ({:#x})", function.name, function.address)?;
+            for issue in &function.issues {
+                writeln!(out, "  {}", issue)?;
+            }
+        }
+        writeln!(
+            out,
+            "{} issues in {} functions, {} instructions without bytes",
+            report.issues(),
+            report.functions.len(),
+            report.unchecked
+        )?;
+    }
+    if report.issues() > 0 {
+        out.flush()?;
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Compares two listings function by function, ignoring addresses, and
+/// prints the changed functions as unified diffs. Exits with status 1 when
+/// the listings differ, like diff(1).
+fn report_diff() -> Result<(), ObjumpError> {
+    let mut paths = Vec::new();
+    let mut context = 3;
+    let mut json = false;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--context" | "-U" => {
+                let value = options.next().unwrap_or_default();
+                context = value
+                    .parse()
+                    .map_err(|_| ObjumpError::InvalidArgument(value))?;
+            }
+            _ if !option.starts_with('-') => paths.push(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+    let [old_path, new_path] = paths.as_slice() else {
+        return Err(ObjumpError::InvalidArgument(
+            "expected two listings".to_string(),
+        ));
+    };
+    let old = read_listing(Some(old_path))?;
+    let new = read_listing(Some(new_path))?;
+    let diff = analysis::diff::ListingDiff::build(&old, &new);
+
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &diff)?;
+        writeln!(out)?;
+    } else {
+        writeln!(out, "--- {}", old_path)?;
+        writeln!(out, "+++ {}", new_path)?;
+        for function in &diff.functions {
+            let change = match function.change {
+                analysis::diff::FunctionChange::Added => "added",
+                analysis::diff::FunctionChange::Removed => "removed",
+                analysis::diff::FunctionChange::Modified => "modified",
+            };
+            write!(
+                out,
+                "{} {}: {} -> {} bytes ({:+})",
+                change,
+                function.name,
+                function.old_size,
+                function.new_size,
+                function.size_delta()
+            )?;
+            if function.old_padding != function.new_padding {
+                write!(
+                    out,
+                    ", padding {} -> {}",
+                    function.old_padding, function.new_padding
+                )?;
+            }
+            writeln!(
+                out,
+                ", +{} -{} instructions",
+                function.added, function.removed
+            )?;
+            if function.change == analysis::diff::FunctionChange::Modified {
+                function.write_unified(&mut out, context)?;
+            }
+        }
+        writeln!(
+            out,
+            "{} changed, {} unchanged functions",
+            diff.functions.len(),
+            diff.unchanged
+        )?;
+    }
+    out.flush()?;
+    if !diff.functions.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints where the code bytes go: generic functions with all their
+/// instantiations folded together, or single symbols with `--symbols`.
+fn report_size() -> Result<(), ObjumpError> {
+    let mut path = None;
+    let mut json = false;
+    let mut symbols = false;
+    let mut top = None;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--symbols" => symbols = true,
+            "--top" => {
+                let value = options.next().unwrap_or_default();
+                top = Some(
+                    value
+                        .parse()
+                        .map_err(|_| ObjumpError::InvalidArgument(value))?,
+                );
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let report = analysis::size::SizeReport::build(&listing);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else if symbols {
+        report.write_symbols(&mut out, top)?;
+    } else {
+        report.write_groups(&mut out, top)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Lists ROP/JOP gadgets, one per unique instruction sequence, optionally
+/// restricted by a `--filter` on the sequence (see `GadgetFilter`).
+fn report_gadgets() -> Result<(), ObjumpError> {
+    let mut finder = analysis::gadgets::GadgetFinder::default();
+    let mut filter = None;
+    let mut json = false;
+    let mut path = None;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--aligned" => finder.unaligned = false,
+            "--depth" => {
+                let value = options.next().unwrap_or_default();
+                finder.depth = value
+                    .parse()
+                    .ok()
+                    .filter(|&depth| depth > 0)
+                    .ok_or(ObjumpError::InvalidArgument(value))?;
+            }
+            "--filter" => {
+                let value = options.next().unwrap_or_default();
+                filter = Some(analysis::gadgets::GadgetFilter::parse(&value)?);
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let gadgets: Vec<_> = finder
+        .find(&listing)
+        .into_iter()
+        .filter(|gadget| filter.as_ref().is_none_or(|filter| filter.matches(gadget)))
+        .collect();
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &gadgets)?;
+        writeln!(out)?;
+    } else {
+        for gadget in &gadgets {
+            writeln!(
+                out,
+                "{:#018x}: {} {} {}",
+                gadget.address,
+                gadget.kind.name(),
+                if gadget.unaligned { "u" } else { "-" },
+                gadget.text()
+            )?;
+        }
+        let density = gadgets
+            .iter()
+            .map(|gadget| 1 + gadget.duplicates.len())
+            .sum::<usize>();
+        writeln!(
+            out,
+            "{} unique gadgets, {} in total, {} unaligned",
+            gadgets.len(),
+            density,
+            gadgets.iter().filter(|gadget| gadget.unaligned).count()
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Prints a per-function pass/fail table of CET, stack protector and
+/// retpoline checks. Exits with status 1 when a check named with
+/// `--require endbr,canary,...` fails in any function.
+fn report_hardening() -> Result<(), ObjumpError> {
+    use analysis::hardening::{CheckStatus, HardeningCheck};
+    let mut path = None;
+    let mut json = false;
+    let mut failed_only = false;
+    let mut required = Vec::new();
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--failed" => failed_only = true,
+            "--require" => {
+                for name in options.next().unwrap_or_default().split(',') {
+                    required.push(
+                        HardeningCheck::parse(name)
+                            .ok_or(ObjumpError::InvalidArgument(name.to_string()))?,
+                    );
+                }
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let report = analysis::hardening::HardeningReport::build(&listing);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else {
+        write!(out, "{:<18}", "address")?;
+        for check in HardeningCheck::ALL {
+            write!(out, " {:<9}", check.name())?;
+        }
+        writeln!(out, " function")?;
+        for function in &report.functions {
+            let statuses = HardeningCheck::ALL.map(|check| function.status(check));
+            if failed_only && !statuses.contains(&CheckStatus::Fail) {
+                continue;
+            }
+            write!(out, "{:#018x}", function.address)?;
+            for status in statuses {
+                write!(out, " {:<9}", status.name())?;
+            }
+            writeln!(out, " {}", function.name)?;
+        }
+        write!(out, "{:<18}", "failures")?;
+        for check in HardeningCheck::ALL {
+            write!(out, " {:<9}", report.failures(check))?;
+        }
+        writeln!(out, " of {} functions", report.functions.len())?;
+    }
+    out.flush()?;
+    if required.iter().any(|&check| report.failures(check) > 0) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Estimates cycles per iteration of a loop (`--loop` with the address of
+/// its header) or of a whole function body taken as straight-line code.
+fn report_throughput() -> Result<(), ObjumpError> {
+    use analysis::throughput::{Microarchitecture, ThroughputReport, MICROARCHITECTURES};
+    let mut path = None;
+    let mut json = false;
+    let mut name = None;
+    let mut header = None;
+    let mut uarch = &MICROARCHITECTURES[0];
+    let mut iterations = 100;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--function" => name = options.next(),
+            "--loop" => {
+                let value = options.next().unwrap_or_default();
+                header = Some(
+                    u64::from_str_radix(value.trim_start_matches("0x"), 16)
+                        .map_err(|_| ObjumpError::InvalidArgument(value))?,
+                );
+            }
+            "--uarch" => {
+                let value = options.next().unwrap_or_default();
+                uarch =
+                    Microarchitecture::find(&value).ok_or(ObjumpError::InvalidArgument(value))?;
+            }
+            "--iterations" => {
+                let value = options.next().unwrap_or_default();
+                iterations = value
+                    .parse()
+                    .ok()
+                    .filter(|&iterations| iterations > 1)
+                    .ok_or(ObjumpError::InvalidArgument(value))?;
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+    let name = name.ok_or(ObjumpError::InvalidArgument("--function".to_string()))?;
+
+    let mut listing = read_listing(path.as_deref())?;
+    let switches = analysis::switches::SwitchReport::build(&listing);
+    let function = listing
+        .functions
+        .iter_mut()
+        .find(|function| function.name == name || function.demangled().as_deref() == Some(&name))
+        .ok_or(ObjumpError::InvalidArgument(name))?;
+    let tables = switches.tables_of(&function.name);
+    let (cfg, forest) = analysis::loops::annotate_loop_depths(function, &tables);
+    let lines: Vec<_> = match header {
+        Some(header) => {
+            let natural = forest
+                .loops
+                .iter()
+                .find(|natural| {
+                    function.instructions[cfg.blocks[natural.header].start].address == header
+                })
+                .ok_or(ObjumpError::InvalidArgument(format!("{:#x}", header)))?;
+            let mut blocks = natural.blocks.clone();
+            blocks.sort_by_key(|&block| cfg.blocks[block].start);
+            blocks
+                .iter()
+                .flat_map(|&block| {
+                    &function.instructions[cfg.blocks[block].start..cfg.blocks[block].end]
+                })
+                .collect()
+        }
+        None => function.instructions.iter().collect(),
+    };
+    let report = ThroughputReport::build(uarch, &lines, iterations);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else {
+        report.write(&mut out)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Prints each function's stack frame size, saved registers and, with
+/// `--slots`, the stack slots it accesses. `--min-size N` keeps frames of at
+/// least N bytes.
+fn report_frames() -> Result<(), ObjumpError> {
+    use analysis::frame::{FrameReport, StackFrame};
+    let mut path = None;
+    let mut json = false;
+    let mut slots = false;
+    let mut name = None;
+    let mut min_size = 0;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--slots" => slots = true,
+            "--function" => name = options.next(),
+            "--min-size" => {
+                let value = options.next().unwrap_or_default();
+                min_size = value
+                    .parse()
+                    .map_err(|_| ObjumpError::InvalidArgument(value))?;
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let mut report = FrameReport::build(&listing);
+    report.frames.retain(|frame| {
+        frame.size >= min_size && name.as_ref().is_none_or(|name| &frame.name == name)
+    });
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+        out.flush()?;
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "{:<18} {:>6} {:>6} {:<3} {:<3} {:<5} {:<28} function",
+        "address", "frame", "alloc", "fp", "dyn", "red", "saved"
+    )?;
+    for frame in &report.frames {
+        writeln!(
+            out,
+            "{:#018x} {:>6} {:>6} {:<3} {:<3} {:<5} {:<28} {}",
+            frame.address,
+            frame.size,
+            frame.allocation,
+            if frame.frame_pointer { "fp" } else { "-" },
+            if frame.dynamic { "dyn" } else { "-" },
+            frame.red_zone,
+            match frame.saved_registers.is_empty() {
+                true => "-".to_string(),
+                false => frame.saved_registers.join(","),
+            },
+            frame.name
+        )?;
+        if !slots {
+            continue;
+        }
+        for slot in &frame.slots {
+            let widths: Vec<String> = slot.widths.iter().map(u32::to_string).collect();
+            writeln!(
+                out,
+                "    {:<12} {:<8} r{:<4} w{:<4} {}{}",
+                StackFrame::slot_name(slot),
+                match widths.is_empty() {
+                    true => "-".to_string(),
+                    false => widths.join(","),
+                },
+                slot.reads,
+                slot.writes,
+                if slot.address_taken { "&" } else { "" },
+                if slot.indexed { "[]" } else { "" }
+            )?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Lists every syscall site with its number and name when the basic block
+/// loads a constant. `--allowlist` prints only the distinct syscall names,
+/// one per line, for seccomp filters, with unresolved sites as comments.
+fn report_syscalls() -> Result<(), ObjumpError> {
+    let mut path = None;
+    let mut json = false;
+    let mut allowlist = false;
+    for option in std::env::args().skip(2) {
+        match option.as_str() {
+            "--json" => json = true,
+            "--allowlist" => allowlist = true,
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let report = analysis::syscalls::SyscallReport::build(&listing);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else if allowlist {
+        for syscall in &report.syscalls {
+            match syscall.name {
+                Some(name) => writeln!(out, "{}", name)?,
+                None => writeln!(out, "# {} {}", syscall.abi.name(), syscall.number)?,
+            }
+        }
+        for site in report.unresolved() {
+            writeln!(
+                out,
+                "# unresolved {} at {:#x} in {}",
+                site.abi.name(),
+                site.address,
+                site.function
+            )?;
+        }
+    } else {
+        for site in &report.sites {
+            let number = match (site.number, &site.from_register) {
+                (Some(number), _) => number.to_string(),
+                (None, Some(register)) => register.clone(),
+                (None, None) => "?".to_string(),
+            };
+            writeln!(
+                out,
+                "{:#018x} {:<6} {:>5} {:<24} {}",
+                site.address,
+                site.abi.name(),
+                number,
+                site.name.unwrap_or("-"),
+                site.function
+            )?;
+        }
+        writeln!(
+            out,
+            "{} sites, {} distinct syscalls, {} unresolved",
+            report.sites.len(),
+            report.syscalls.len(),
+            report.unresolved().count()
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Answers cross-reference queries: `--to ADDR|SYMBOL` (with `--size N` for
+/// any byte of a data object), `--from ADDR`, `--register REG` and
+/// `--callers SYMBOL`. Without a query it lists every called function with
+/// its number of call sites and calling functions.
+fn report_xrefs() -> Result<(), ObjumpError> {
+    use analysis::xrefs::{Xref, XrefDatabase};
+    let mut path = None;
+    let mut json = false;
+    let mut to = None;
+    let mut size = 1;
+    let mut from = None;
+    let mut register = None;
+    let mut callers = None;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--to" => to = options.next(),
+            "--from" => from = options.next(),
+            "--register" => register = options.next(),
+            "--callers" => callers = options.next(),
+            "--size" => {
+                let value = options.next().unwrap_or_default();
+                size = match value.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                    None => value.parse().ok(),
+                }
+                .filter(|&size| size > 0)
+                .ok_or(ObjumpError::InvalidArgument(value))?;
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let database = XrefDatabase::build(&listing);
+    let resolve = |value: String| match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).map_err(|_| ObjumpError::InvalidArgument(value)),
+        None => database
+            .function(&value)
+            .map(|function| function.address)
+            .ok_or(ObjumpError::InvalidArgument(value)),
+    };
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if let Some(register) = register {
+        let references = database.register_references(&register);
+        if json {
+            serde_json::to_writer_pretty(&mut out, references)?;
+            writeln!(out)?;
+        }
+        for reference in references.iter().filter(|_| !json) {
+            let text = database
+                .instruction(reference.from)
+                .map(|line| line.instruction.to_string())
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{:#018x} {:<7} {:<32} {}",
+                reference.from,
+                reference.kind.name(),
+                database.symbolize(reference.from),
+                text
+            )?;
+        }
+        out.flush()?;
+        return Ok(());
+    }
+    let xrefs: Vec<&Xref> = match (to, from, callers) {
+        (Some(to), _, _) => {
+            let to = resolve(to)?;
+            database
+                .xrefs_to_range(to..=to.saturating_add(size - 1))
+                .collect()
+        }
+        (_, Some(from), _) => database.xrefs_from(resolve(from)?).iter().collect(),
+        (_, _, Some(callers)) => database.callers(resolve(callers)?).collect(),
+        (None, None, None) => {
+            let mut called: Vec<_> = listing
+                .functions
+                .iter()
+                .map(|function| {
+                    let sites: Vec<&Xref> = database.callers(function.address).collect();
+                    let mut names: Vec<&str> = sites
+                        .iter()
+                        .filter_map(|xref| database.function_containing(xref.from))
+                        .map(|caller| caller.name.as_str())
+                        .collect();
+                    names.sort();
+                    names.dedup();
+                    (function, sites.len(), names)
+                })
+                .filter(|(_, sites, _)| *sites > 0)
+                .collect();
+            called.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.address.cmp(&b.0.address)));
+            if json {
+                let called: Vec<_> = called
+                    .iter()
+                    .map(|(function, sites, names)| {
+                        serde_json::json!({
+                            "name": function.name,
+                            "address": format!("{:#x}", function.address),
+                            "call_sites": sites,
+                            "callers": names,
+                        })
+                    })
+                    .collect();
+                serde_json::to_writer_pretty(&mut out, &called)?;
+                writeln!(out)?;
+            } else {
+                for (function, sites, names) in &called {
+                    writeln!(
+                        out,
+                        "{:#018x} {:>6} sites {:>5} callers  {}",
+                        function.address,
+                        sites,
+                        names.len(),
+                        function.name
+                    )?;
+                }
+            }
+            out.flush()?;
+            return Ok(());
+        }
+    };
+    if json {
+        serde_json::to_writer_pretty(&mut out, &xrefs)?;
+        writeln!(out)?;
+    } else {
+        for xref in xrefs {
+            let text = database
+                .instruction(xref.from)
+                .map(|line| line.instruction.to_string())
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{:#018x} {:<7} {:<32} {:<24} {}",
+                xref.from,
+                xref.kind.name(),
+                database.symbolize(xref.from),
+                database.symbolize(xref.to),
+                text
+            )?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Prints every window of instructions matching an instruction sequence
+/// pattern (see [`analysis::pattern::Pattern`]), with its function and
+/// captures. `--count` prints the number of matches per function instead.
+fn search_pattern() -> Result<(), ObjumpError> {
+    use analysis::pattern::{Pattern, SearchReport};
+    let mut pattern = None;
+    let mut path = None;
+    let mut json = false;
+    let mut count = false;
+    let mut name = None;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--count" => count = true,
+            "--function" => name = options.next(),
+            _ if option.starts_with("--") => return Err(ObjumpError::InvalidArgument(option)),
+            _ if pattern.is_none() => pattern = Some(option),
+            _ if path.is_none() => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+    let pattern = pattern.ok_or(ObjumpError::InvalidArgument("pattern".to_string()))?;
+    let pattern = Pattern::try_from(pattern.as_str())?;
+
+    let mut listing = read_listing(path.as_deref())?;
+    if let Some(name) = &name {
+        listing.functions.retain(|function| {
+            function.name == *name || function.demangled().as_deref() == Some(name.as_str())
+        });
+    }
+    let report = SearchReport::build(&listing, &pattern);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else if count {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for found in &report.matches {
+            match counts.last_mut() {
+                Some((function, matches)) if *function == found.function => *matches += 1,
+                _ => counts.push((&found.function, 1)),
+            }
+        }
+        for (function, matches) in counts {
+            writeln!(out, "{:>6} {}", matches, function)?;
+        }
+    } else {
+        for found in &report.matches {
+            let captures: Vec<String> = found
+                .captures
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            writeln!(
+                out,
+                "{} {:#x} {}",
+                found.function,
+                found.address,
+                captures.join(" ")
+            )?;
+            for line in &found.lines {
+                writeln!(out, "  {:#10x}:  {}", line.address, line.text)?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Lists the jump tables behind indirect jumps, with the cases leading to
+/// each target and how many basic blocks they make reachable.
+/// Tables are read from ELF input or the data sections of `objdump -D`.
+fn report_switches() -> Result<(), ObjumpError> {
+    use analysis::cfg::ControlFlowGraph;
+    use analysis::switches::SwitchReport;
+    let mut path = None;
+    let mut json = false;
+    let mut name = None;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--function" => name = options.next(),
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let mut listing = read_listing(path.as_deref())?;
+    if let Some(name) = &name {
+        listing.functions.retain(|function| {
+            function.name == *name || function.demangled().as_deref() == Some(name.as_str())
+        });
+    }
+    let report = SwitchReport::build(&listing);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+        out.flush()?;
+        return Ok(());
+    }
+    for function in &listing.functions {
+        let tables = report.tables_of(&function.name);
+        if tables.is_empty() {
+            continue;
+        }
+        let before = ControlFlowGraph::build(function).reverse_postorder().len();
+        let after = ControlFlowGraph::build_with_tables(function, &tables)
+            .reverse_postorder()
+            .len();
+        writeln!(
+            out,
+            "{} ({} reachable blocks, {} without tables)",
+            function.name, after, before
+        )?;
+        for table in tables {
+            let default = table
+                .default
+                .map_or("none".to_string(), |default| format!("{:#x}", default));
+            writeln!(
+                out,
+                "  {:#x}: {} table at {:#x}, {} entries{}, default {}",
+                table.jump,
+                table.kind.name(),
+                table.table,
+                table.targets.len(),
+                if table.bounded { "" } else { " (unbounded)" },
+                default
+            )?;
+            for target in table.successors() {
+                let cases: Vec<String> = table
+                    .targets
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &entry)| entry == target)
+                    .map(|(case, _)| case.to_string())
+                    .collect();
+                writeln!(out, "    {:#x} <- {}", target, cases.join(","))?;
+            }
+        }
+    }
+    writeln!(
+        out,
+        "{} jump tables, {} other indirect jumps",
+        report.tables.len(),
+        report.unresolved.len()
+    )?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Lists the section images of the input: the contents `objdump -s`
+/// dumped, the data sections of `objdump -D`, or the ELF file's. With
+/// `--address ADDR [--size N]` it dumps bytes the way `objdump -s` does, and
+/// with `--loads` it resolves every RIP-relative or absolute load to the
+/// value it reads.
+fn report_sections() -> Result<(), ObjumpError> {
+    let mut path = None;
+    let mut json = false;
+    let mut loads = false;
+    let mut address = None;
+    let mut size = 64;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--loads" => loads = true,
+            "--address" | "--size" => {
+                let value = options.next().unwrap_or_default();
+                let number = match value.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None if option == "--address" => u64::from_str_radix(&value, 16),
+                    None => value.parse(),
+                }
+                .map_err(|_| ObjumpError::InvalidArgument(value))?;
+                match option.as_str() {
+                    "--address" => address = Some(number),
+                    _ => size = number,
+                }
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if loads {
+        let report = analysis::constants::ConstantReport::build(&listing);
+        if json {
+            serde_json::to_writer_pretty(&mut out, &report)?;
+            writeln!(out)?;
+        }
+        for load in report.loads.iter().filter(|_| !json) {
+            writeln!(
+                out,
+                "{:#018x} {:<24} {:#x} {:<12} {}",
+                load.address, load.function, load.target, load.section, load.value
+            )?;
+        }
+    } else if let Some(address) = address {
+        let bytes = listing
+            .bytes_at(address)
+            .ok_or(ObjumpError::InvalidArgument(format!("{:#x}", address)))?;
+        let bytes = &bytes[..bytes.len().min(size as usize)];
+        for (index, chunk) in bytes.chunks(16).enumerate() {
+            let mut hex = String::new();
+            for (group, bytes) in chunk.chunks(4).enumerate() {
+                if group > 0 {
+                    hex.push(' ');
+                }
+                for byte in bytes {
+                    hex.push_str(&format!("{:02x}", byte));
+                }
+            }
+            let text: String = chunk
+                .iter()
+                .map(|&byte| match byte {
+                    0x20..=0x7e => byte as char,
+                    _ => '.',
+                })
+                .collect();
+            writeln!(
+                out,
+                " {:x} {:<35}  {}",
+                address + index as u64 * 16,
+                hex,
+                text
+            )?;
+        }
+    } else if json {
+        let sections: Vec<_> = listing
+            .sections
+            .iter()
+            .map(|section| {
+                serde_json::json!({
+                    "name": section.name,
+                    "address": format!("{:#x}", section.address),
+                    "size": section.bytes.len(),
+                })
+            })
+            .collect();
+        serde_json::to_writer_pretty(&mut out, &sections)?;
+        writeln!(out)?;
+    } else {
+        for section in &listing.sections {
+            writeln!(
+                out,
+                "{:#018x} {:>8} {}",
+                section.address,
+                section.bytes.len(),
+                section.name
+            )?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Lists the instructions taking the address of a string literal in
+/// `.rodata`, with the C string or Rust `&str` there. With `--annotate` it
+/// prints the whole disassembly instead, the string after each such
+/// instruction as objdump prints symbols.
+fn report_strings() -> Result<(), ObjumpError> {
+    let mut path = None;
+    let mut json = false;
+    let mut annotate = false;
+    let mut min_length = 1;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--annotate" => annotate = true,
+            "--min-length" => {
+                let value = options.next().unwrap_or_default();
+                min_length = value
+                    .parse()
+                    .map_err(|_| ObjumpError::InvalidArgument(value))?;
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let listing = read_listing(path.as_deref())?;
+    let mut report = analysis::strings::StringReport::build(&listing);
+    report
+        .references
+        .retain(|reference| reference.text.chars().count() >= min_length);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, &report)?;
+        writeln!(out)?;
+    } else if annotate {
+        let mut section = None;
+        for function in &listing.functions {
+            if section != Some(&function.section) {
+                writeln!(out, "\nDisassembly of section {}:", function.section)?;
+                section = Some(&function.section);
+            }
+            writeln!(out, "\n{:016x} <{}>:", function.address, function.name)?;
+            for line in &function.instructions {
+                match report.at(line.address) {
+                    Some(reference) => writeln!(out, "{}  # {:?}", line, reference.text)?,
+                    None => writeln!(out, "{}", line)?,
+                }
+            }
+        }
+    } else {
+        for reference in &report.references {
+            writeln!(
+                out,
+                "{:#018x} {:<24} {:#x} {:<4} {:?}",
+                reference.address,
+                reference.function,
+                reference.target,
+                reference.kind.name(),
+                reference.text
+            )?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Measures how fast objdump text parses, line by line and into a listing,
+/// sequentially and in parallel, over `--iterations N` passes of the input
+/// held in memory, and compares the fastest pass with the line parser's
+/// throughput target.
+fn run_benchmark() -> Result<(), ObjumpError> {
+    let mut path = None;
+    let mut json = false;
+    let mut iterations = 5;
+    let mut options = std::env::args().skip(2);
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--json" => json = true,
+            "--iterations" => {
+                let value = options.next().unwrap_or_default();
+                iterations = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&iterations| iterations > 0)
+                    .ok_or(ObjumpError::InvalidArgument(value))?;
+            }
+            _ if path.is_none() && !option.starts_with("--") => path = Some(option),
+            _ => return Err(ObjumpError::InvalidArgument(option)),
+        }
+    }
+
+    let data = match path {
+        Some(path) => std::fs::read(path)?,
+        None => {
+            let mut data = Vec::new();
+            io::stdin().lock().read_to_end(&mut data)?;
+            data
+        }
+    };
+    if elf::is_elf(&data) {
+        return Err(ObjumpError::InvalidArgument(
+            "expected objdump output, not an ELF file".to_string(),
+        ));
+    }
+    let mut lines = 0;
+    let mut instructions = 0;
+    let mut line_seconds = f64::MAX;
+    let mut listing_seconds = f64::MAX;
+    let mut parallel_seconds = f64::MAX;
+    for _ in 0..iterations {
+        let start = std::time::Instant::now();
+        (lines, instructions) = (0, 0);
+        for (_, line) in objdump::line::ObjDumpLines::new(data.as_slice()) {
+            lines += 1;
+            if let Ok(objdump::line::ObjDumpLineType::Instruction(_)) = line {
+                instructions += 1;
+            }
+        }
+        line_seconds = line_seconds.min(start.elapsed().as_secs_f64());
+
+        let start = std::time::Instant::now();
+        let listing = objdump::listing::ObjDumpListing::parse(data.as_slice())?;
+        listing_seconds = listing_seconds.min(start.elapsed().as_secs_f64());
+        std::hint::black_box(listing);
+
+        let start = std::time::Instant::now();
+        let listing = objdump::listing::ObjDumpListing::parse_parallel(&data)?;
+        parallel_seconds = parallel_seconds.min(start.elapsed().as_secs_f64());
+        std::hint::black_box(listing);
+    }
+
+    let megabytes = data.len() as f64 / 1e6;
+    let target = objdump::line::TARGET_THROUGHPUT;
+    let throughput = megabytes / line_seconds;
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if json {
+        serde_json::to_writer_pretty(
+            &mut out,
+            &serde_json::json!({
+                "bytes": data.len(),
+                "lines": lines,
+                "instructions": instructions,
+                "iterations": iterations,
+                "line_seconds": line_seconds,
+                "listing_seconds": listing_seconds,
+                "megabytes_per_second": throughput,
+                "listing_megabytes_per_second": megabytes / listing_seconds,
+                "parallel_seconds": parallel_seconds,
+                "parallel_megabytes_per_second": megabytes / parallel_seconds,
+                "threads": rayon::current_num_threads(),
+                "target_megabytes_per_second": target,
+                "met": throughput >= target,
+            }),
+        )?;
+        writeln!(out)?;
+    } else {
+        writeln!(
+            out,
+            "{:.1} MB, {} lines, {} instructions, best of {}",
+            megabytes, lines, instructions, iterations
+        )?;
+        writeln!(
+            out,
+            "lines    {:>8.3} s {:>8.1} MB/s {:>10.0} lines/s",
+            line_seconds,
+            throughput,
+            lines as f64 / line_seconds
+        )?;
+        writeln!(
+            out,
+            "listing  {:>8.3} s {:>8.1} MB/s {:>10.0} lines/s",
+            listing_seconds,
+            megabytes / listing_seconds,
+            lines as f64 / listing_seconds
+        )?;
+        writeln!(
+            out,
+            "parallel {:>8.3} s {:>8.1} MB/s {:>10.0} lines/s on {} threads",
+            parallel_seconds,
+            megabytes / parallel_seconds,
+            lines as f64 / parallel_seconds,
+            rayon::current_num_threads()
+        )?;
+        writeln!(
+            out,
+            "target   {:>19.1} MB/s {}",
+            target,
+            match throughput >= target {
+                true => "met",
+                false => "missed",
+            }
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "
+0000000000401000 <f>:
+  401000:\t48 c7 c0 2a 00 00 00 \tmov    $0x2a,%rax
+  401007:\t66 2e 0f 1f 84 00 00 \tcs nopw 0x0(%rax,%rax,1)
+  40100e:\t00 00 00 
+";
+
+    const SYMBOL: &str = r#"{"type":"symbol","value":{"address":"0x401000","name":"f"}}"#;
+    const MOV: &str = concat!(
+        r#"{"type":"instruction","value":{"address":"0x401000","bytes":"48c7c02a000000","#,
+        r#""instruction":{"opcode":"mov","operands":["#,
+        r#"{"type":"immediate","value":"0x2a"},{"type":"register","value":"%rax"}]}}}"#,
+    );
+    const NOPW: &str = concat!(
+        r#"{"type":"instruction","value":{"address":"0x401007","bytes":"662e0f1f840000","#,
+        r#""instruction":{"prefixes":["cs"],"opcode":"nopw","operands":["#,
+        r#"{"type":"address","value":{"displacement":0,"base":"%rax","offset":"%rax","scaler":1}}]}}}"#,
+    );
+    const BYTES: &str = r#"{"type":"bytes","value":{"address":"0x40100e","bytes":"000000"}}"#;
+
+    fn parsed_lines(ndjson: bool) -> String {
+        let mut out = Vec::new();
+        write_parsed_lines(LISTING.as_bytes(), &mut out, ndjson).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn ndjson_writes_one_object_per_line() {
+        let expected = [SYMBOL, MOV, NOPW, BYTES].map(|line| format!("{}\n", line));
+        assert_eq!(parsed_lines(true), expected.concat());
+    }
+
+    #[test]
+    fn json_writes_one_array() {
+        let expected = format!("[\n{},\n{},\n{},\n{}\n]\n", SYMBOL, MOV, NOPW, BYTES);
+        assert_eq!(parsed_lines(false), expected);
+        let lines: serde_json::Value = serde_json::from_str(&expected).unwrap();
+        assert_eq!(lines.as_array().unwrap().len(), 4);
     }
 }
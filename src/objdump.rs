@@ -1,2 +1,8 @@
+pub mod decode;
+pub mod hex;
 pub mod line;
-pub mod x8664_att;
\ No newline at end of file
+pub mod listing;
+pub mod roundtrip;
+pub mod symbol;
+pub mod verify;
+pub mod x8664_att;
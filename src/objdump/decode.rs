@@ -0,0 +1,570 @@
+use crate::objdump::x8664_att::{
+    parse_x8664_att_instruction, X8664ATTAddress, X8664ATTImmediate, X8664ATTInstruction,
+    X8664ATTInteger, X8664ATTOpcode, X8664ATTOperand, X8664ATTRegister, X8664ATTValue,
+};
+use iced_x86::{
+    Code, Decoder, DecoderError, DecoderOptions, FormatMnemonicOptions, Formatter, GasFormatter,
+    Instruction, Mnemonic, OpCodeOperandKind, OpKind, Register, RoundingControl,
+};
+
+const LEGACY_PREFIXES: [u8; 11] = [
+    0x26, 0x2e, 0x36, 0x3e, 0x64, 0x65, 0x66, 0x67, 0xf0, 0xf2, 0xf3,
+];
+
+/// Native x86-64 decoder producing the same `X8664ATTInstruction` model as
+/// the objdump text parser. Operands are built from the fields of iced's
+/// `Instruction`; its GAS formatter only names the mnemonic and prefixes and
+/// says where the AT&T syntax puts each operand.
+pub struct X8664Decoder {
+    formatter: GasFormatter,
+    text: String,
+}
+
+impl Default for X8664Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl X8664Decoder {
+    pub fn new() -> Self {
+        let mut formatter = GasFormatter::new();
+        let options = formatter.options_mut();
+        options.set_gas_show_mnemonic_size_suffix(false);
+        options.set_show_branch_size(false);
+        options.set_show_useless_prefixes(true);
+        X8664Decoder {
+            formatter,
+            text: String::new(),
+        }
+    }
+
+    /// Decodes the instruction at the start of `bytes`, located at
+    /// `address`, and returns it with its length. Undecodable bytes become
+    /// a one-byte `(bad)` instruction and an instruction cut short by the
+    /// end of `bytes` a one-byte `.byte` directive (or the prefix it starts
+    /// with), as in objdump.
+    pub fn decode(&mut self, bytes: &[u8], address: u64) -> (X8664ATTInstruction, usize) {
+        let mut decoder = Decoder::with_ip(64, bytes, address, DecoderOptions::NONE);
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            return match decoder.last_error() {
+                DecoderError::NoMoreBytes if !bytes.is_empty() => (truncated(bytes[0]), 1),
+                _ => (bad(), 1),
+            };
+        }
+        // objdump folds `fwait` into the following no-wait x87 control
+        // instruction: `9b d9 7d 00` is `fstcw`, not `fwait; fnstcw`.
+        if instruction.code() == Code::Wait {
+            let (mut next, length) = self.decode(&bytes[1..], address + 1);
+            if let Some(waiting) = next.opcode.mnemonic().strip_prefix("fn") {
+                if matches!(
+                    waiting,
+                    "stcw" | "stsw" | "clex" | "init" | "stenv" | "save"
+                ) && next.prefixes.is_empty()
+                {
+                    next.opcode = X8664ATTOpcode::from(format!("f{}", waiting).as_str());
+                    return (next, length + 1);
+                }
+            }
+        }
+        let (mut prefixes, opcode) = self.mnemonic(&instruction);
+        let operands = match instruction.code() {
+            // `66 90` has no operands but prints as `xchg %ax,%ax`.
+            Code::Nopw => vec![X8664ATTOperand::Register(X8664ATTRegister::from("%ax")); 2],
+            _ => self
+                .operand_order(&instruction)
+                .into_iter()
+                .map(|operand| match operand {
+                    Some(operand) => self.operand(&instruction, operand),
+                    None => added(&instruction),
+                })
+                .collect(),
+        };
+        // objdump prints segment overrides of ordinary memory operands as
+        // prefixes; only `%fs` and `%gs` stay in the operand.
+        let segment = instruction.segment_prefix();
+        if has_memory_operand(&instruction) && !matches!(segment, Register::FS | Register::GS) {
+            match segment {
+                Register::CS => prefixes.push(X8664ATTOpcode::Cs),
+                Register::DS => prefixes.push(X8664ATTOpcode::Ds),
+                Register::ES => prefixes.push(X8664ATTOpcode::Es),
+                Register::SS => prefixes.push(X8664ATTOpcode::Ss),
+                _ => {}
+            }
+        }
+        for _ in 0..unused_size_prefixes(&bytes[..instruction.len()], &instruction) {
+            prefixes.insert(0, X8664ATTOpcode::Data16);
+        }
+        let decoded = X8664ATTInstruction {
+            prefixes,
+            opcode,
+            operands,
+            data: String::new(),
+        };
+        (decoded, instruction.len())
+    }
+
+    /// Prefixes and mnemonic, spelled as objdump spells them.
+    fn mnemonic(&mut self, instruction: &Instruction) -> (Vec<X8664ATTOpcode>, X8664ATTOpcode) {
+        self.text.clear();
+        self.formatter.format_mnemonic_options(
+            instruction,
+            &mut self.text,
+            FormatMnemonicOptions::NONE,
+        );
+        let mut names: Vec<&str> = self.text.split_whitespace().collect();
+        let mnemonic = names.pop().unwrap_or("(bad)");
+        // objdump drops the suffix of stack and branch operations whose
+        // operand size is implied: `push`, `call`, `ljmp`, `lret`, ...
+        let mnemonic = match mnemonic {
+            "pushq" | "popq" | "callq" | "jmpq" | "retq" | "ljmpl" | "lcalll" | "lretl" => {
+                &mnemonic[..mnemonic.len() - 1]
+            }
+            _ => mnemonic,
+        };
+        let string_operation = matches!(
+            instruction.mnemonic(),
+            Mnemonic::Movsb
+                | Mnemonic::Movsw
+                | Mnemonic::Movsd
+                | Mnemonic::Movsq
+                | Mnemonic::Stosb
+                | Mnemonic::Stosw
+                | Mnemonic::Stosd
+                | Mnemonic::Stosq
+                | Mnemonic::Lodsb
+                | Mnemonic::Lodsw
+                | Mnemonic::Lodsd
+                | Mnemonic::Lodsq
+                | Mnemonic::Insb
+                | Mnemonic::Insw
+                | Mnemonic::Insd
+                | Mnemonic::Outsb
+                | Mnemonic::Outsw
+                | Mnemonic::Outsd
+        );
+        let elided = instruction.has_xacquire_prefix() || instruction.has_xrelease_prefix();
+        let prefixes = names
+            .into_iter()
+            .filter_map(|name| match X8664ATTOpcode::from(name) {
+                // The `f2`/`f3` bytes of `xacquire`/`xrelease` are not
+                // repeated as `repne`/`rep`.
+                X8664ATTOpcode::Rep | X8664ATTOpcode::Repne if elided => None,
+                // objdump spells `f3` as `repz` outside the string
+                // instructions and `f2` as `repnz` everywhere.
+                X8664ATTOpcode::Rep if !string_operation => Some(X8664ATTOpcode::Repz),
+                X8664ATTOpcode::Repne => Some(X8664ATTOpcode::Repnz),
+                prefix => Some(prefix),
+            })
+            .collect();
+        (prefixes, X8664ATTOpcode::from(mnemonic))
+    }
+
+    /// Indexes of the instruction operands in AT&T order, with `None` where
+    /// the formatter adds an operand. The formatter knows the order and
+    /// which immediates a pseudo-op mnemonic such as `vcmpltps` absorbs;
+    /// where it hides other operands, objdump still prints them.
+    fn operand_order(&mut self, instruction: &Instruction) -> Vec<Option<u32>> {
+        let mut order: Vec<Option<u32>> = (0..self.formatter.operand_count(instruction))
+            .map(|operand| {
+                self.formatter
+                    .get_instruction_operand(instruction, operand)
+                    .ok()
+                    .flatten()
+            })
+            .collect();
+        let hidden = (0..instruction.op_count()).any(|operand| {
+            !order.contains(&Some(operand)) && !is_immediate(instruction.op_kind(operand))
+        });
+        if hidden {
+            order = (0..instruction.op_count()).rev().map(Some).collect();
+        }
+        // `enter` keeps the Intel operand order.
+        if instruction.mnemonic() == Mnemonic::Enter {
+            order = (0..instruction.op_count()).map(Some).collect();
+        }
+        let operation = instruction.op_code();
+        order.retain(|&operand| {
+            let Some(operand) = operand else {
+                return true;
+            };
+            // The shift-by-one encodings have no immediate.
+            if operation.op_kind(operand) == OpCodeOperandKind::imm8_const_1 {
+                return false;
+            }
+            // `%st` is implied by the one-operand x87 forms.
+            let implied_top = matches!(
+                instruction.mnemonic(),
+                Mnemonic::Fxch
+                    | Mnemonic::Fcom
+                    | Mnemonic::Fcomp
+                    | Mnemonic::Fucom
+                    | Mnemonic::Fucomp
+            );
+            !(implied_top && operation.op_kind(operand) == OpCodeOperandKind::st0)
+        });
+        order
+    }
+
+    fn operand(&mut self, instruction: &Instruction, operand: u32) -> X8664ATTOperand {
+        let value = match instruction.op_kind(operand) {
+            OpKind::Register => {
+                let register = instruction.op_register(operand);
+                let port = register == Register::DX
+                    && matches!(
+                        instruction.mnemonic(),
+                        Mnemonic::In
+                            | Mnemonic::Out
+                            | Mnemonic::Insb
+                            | Mnemonic::Insw
+                            | Mnemonic::Insd
+                            | Mnemonic::Outsb
+                            | Mnemonic::Outsw
+                            | Mnemonic::Outsd
+                    );
+                // The x87 stack top is `%st` where the encoding implies it
+                // and `%st(0)` where a register field names it.
+                let register = match instruction.op_code().op_kind(operand) {
+                    OpCodeOperandKind::st0 => X8664ATTRegister::from("%st"),
+                    _ => self.register(register),
+                };
+                match port {
+                    // objdump prints the port operand as `(%dx)`.
+                    true => X8664ATTOperand::Address(X8664ATTAddress {
+                        segment: None,
+                        displacement: None,
+                        base: Some(X8664ATTValue::Register(register)),
+                        offset: None,
+                        scaler: None,
+                    }),
+                    false => X8664ATTOperand::Register(register),
+                }
+            }
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => {
+                return X8664ATTOperand::Target(instruction.near_branch_target())
+            }
+            OpKind::FarBranch16 | OpKind::FarBranch32 => return X8664ATTOperand::Bad,
+            OpKind::Memory => X8664ATTOperand::Address(self.memory(instruction)),
+            OpKind::MemorySegSI
+            | OpKind::MemorySegESI
+            | OpKind::MemorySegRSI
+            | OpKind::MemorySegDI
+            | OpKind::MemorySegEDI
+            | OpKind::MemorySegRDI => {
+                let segment = instruction.memory_segment();
+                X8664ATTOperand::Address(self.string(segment, instruction.op_kind(operand)))
+            }
+            OpKind::MemoryESDI | OpKind::MemoryESEDI | OpKind::MemoryESRDI => {
+                X8664ATTOperand::Address(self.string(Register::ES, instruction.op_kind(operand)))
+            }
+            kind => X8664ATTOperand::Immediate(X8664ATTImmediate(immediate(instruction, kind))),
+        };
+        // An indirect branch takes its target from a register or memory.
+        if matches!(instruction.mnemonic(), Mnemonic::Call | Mnemonic::Jmp) {
+            return X8664ATTOperand::Indirect(Box::new(value));
+        }
+        let mut decorations = String::new();
+        if operand == 0 && instruction.op_mask() != Register::None {
+            decorations = format!("{{{}}}", self.register(instruction.op_mask()));
+            if instruction.zeroing_masking() {
+                decorations.push_str("{z}");
+            }
+        }
+        if instruction.is_broadcast() && matches!(value, X8664ATTOperand::Address(_)) {
+            let vector = 16 << instruction.op_code().l();
+            let element = instruction.memory_size().element_size();
+            decorations = format!("{{1to{}}}", vector / element.max(1));
+        }
+        match decorations.is_empty() {
+            true => value,
+            false => X8664ATTOperand::Decorated(Box::new(value), decorations),
+        }
+    }
+
+    fn register(&mut self, register: Register) -> X8664ATTRegister {
+        X8664ATTRegister::from(self.formatter.format_register(register))
+    }
+
+    fn memory(&mut self, instruction: &Instruction) -> X8664ATTAddress {
+        let base = instruction.memory_base();
+        let index = instruction.memory_index();
+        let displacement = match base {
+            // RIP-relative displacements are relative to the next instruction.
+            Register::RIP | Register::EIP => Some(
+                instruction
+                    .memory_displacement64()
+                    .wrapping_sub(instruction.next_ip()) as i64,
+            ),
+            // With 32-bit addressing the displacement wraps at 32 bits.
+            _ if instruction.memory_displ_size() > 0 && base.is_gpr32() => {
+                Some(instruction.memory_displacement32() as i32 as i64)
+            }
+            _ if instruction.memory_displ_size() > 0 => {
+                Some(instruction.memory_displacement64() as i64)
+            }
+            _ => None,
+        };
+        let segment = match instruction.segment_prefix() {
+            Register::FS | Register::GS => Some(self.register(instruction.segment_prefix())),
+            _ => None,
+        };
+        // `xlat` addresses `(%rbx,%al)` but objdump prints `%ds:(%rbx)`.
+        if instruction.mnemonic() == Mnemonic::Xlatb {
+            return X8664ATTAddress {
+                segment: Some(self.register(instruction.memory_segment())),
+                displacement: None,
+                base: Some(X8664ATTValue::Register(self.register(base))),
+                offset: None,
+                scaler: None,
+            };
+        }
+        X8664ATTAddress {
+            segment,
+            displacement: displacement.map(X8664ATTInteger),
+            base: (base != Register::None).then(|| X8664ATTValue::Register(self.register(base))),
+            offset: (index != Register::None)
+                .then(|| X8664ATTValue::Register(self.register(index))),
+            scaler: (index != Register::None)
+                .then(|| X8664ATTInteger(instruction.memory_index_scale() as i64)),
+        }
+    }
+
+    /// Implicit operand of a string instruction, which objdump prints with
+    /// its segment: `%ds:(%rsi)`, `%es:(%rdi)`.
+    fn string(&mut self, segment: Register, kind: OpKind) -> X8664ATTAddress {
+        let base = match kind {
+            OpKind::MemorySegSI => Register::SI,
+            OpKind::MemorySegESI => Register::ESI,
+            OpKind::MemorySegRSI => Register::RSI,
+            OpKind::MemorySegDI | OpKind::MemoryESDI => Register::DI,
+            OpKind::MemorySegEDI | OpKind::MemoryESEDI => Register::EDI,
+            _ => Register::RDI,
+        };
+        X8664ATTAddress {
+            segment: Some(self.register(segment)),
+            displacement: None,
+            base: Some(X8664ATTValue::Register(self.register(base))),
+            offset: None,
+            scaler: None,
+        }
+    }
+}
+
+fn is_immediate(kind: OpKind) -> bool {
+    matches!(
+        kind,
+        OpKind::Immediate8
+            | OpKind::Immediate8_2nd
+            | OpKind::Immediate16
+            | OpKind::Immediate32
+            | OpKind::Immediate64
+            | OpKind::Immediate8to16
+            | OpKind::Immediate8to32
+            | OpKind::Immediate8to64
+            | OpKind::Immediate32to64
+    )
+}
+
+/// Value of an immediate operand, sign-extended to the operand size the way
+/// objdump prints it: `$0xfff0` for a 16-bit `-0x10`, `$0xfffffffffffffff0`
+/// for a 64-bit one.
+fn immediate(instruction: &Instruction, kind: OpKind) -> u64 {
+    match kind {
+        OpKind::Immediate8 => instruction.immediate8() as u64,
+        OpKind::Immediate8_2nd => instruction.immediate8_2nd() as u64,
+        OpKind::Immediate16 => instruction.immediate16() as u64,
+        OpKind::Immediate32 => instruction.immediate32() as u64,
+        OpKind::Immediate64 => instruction.immediate64(),
+        OpKind::Immediate8to16 => instruction.immediate8to16() as u16 as u64,
+        OpKind::Immediate8to32 => instruction.immediate8to32() as u32 as u64,
+        OpKind::Immediate8to64 => instruction.immediate8to64() as u64,
+        OpKind::Immediate32to64 => instruction.immediate32to64() as u64,
+        _ => 0,
+    }
+}
+
+/// An operand the AT&T syntax shows but iced's `Instruction` does not
+/// have: the `{rn-sae}`-style operand of an EVEX instruction, or the
+/// `%xmm0` the SSE4.1 blends and `sha256rnds2` read implicitly.
+fn added(instruction: &Instruction) -> X8664ATTOperand {
+    let rounding = match instruction.rounding_control() {
+        RoundingControl::RoundToNearest => "{rn-sae}",
+        RoundingControl::RoundDown => "{rd-sae}",
+        RoundingControl::RoundUp => "{ru-sae}",
+        RoundingControl::RoundTowardZero => "{rz-sae}",
+        RoundingControl::None if instruction.suppress_all_exceptions() => "{sae}",
+        RoundingControl::None => return X8664ATTOperand::Register(X8664ATTRegister::from("%xmm0")),
+    };
+    X8664ATTOperand::Rounding(rounding.to_string())
+}
+
+fn has_memory_operand(instruction: &Instruction) -> bool {
+    (0..instruction.op_count()).any(|operand| instruction.op_kind(operand) == OpKind::Memory)
+}
+
+/// Number of `66` prefixes objdump prints as `data16` because the
+/// instruction decodes the same without them.
+fn unused_size_prefixes(bytes: &[u8], instruction: &Instruction) -> usize {
+    let prefixes = bytes
+        .iter()
+        .take_while(|byte| LEGACY_PREFIXES.contains(byte))
+        .count();
+    let count = bytes[..prefixes]
+        .iter()
+        .filter(|&&byte| byte == 0x66)
+        .count();
+    if count == 0 {
+        return 0;
+    }
+    let stripped: Vec<u8> = bytes[..prefixes]
+        .iter()
+        .filter(|&&byte| byte != 0x66)
+        .chain(&bytes[prefixes..])
+        .copied()
+        .collect();
+    let mut decoder = Decoder::with_ip(64, &stripped, instruction.ip(), DecoderOptions::NONE);
+    match decoder.decode().code() == instruction.code() {
+        true => count,
+        false => count - 1,
+    }
+}
+
+fn bad() -> X8664ATTInstruction {
+    parse_x8664_att_instruction("(bad)").unwrap()
+}
+
+/// What objdump prints for the first byte of an incomplete instruction.
+fn truncated(byte: u8) -> X8664ATTInstruction {
+    let prefix = match byte {
+        0x26 => "es",
+        0x2e => "cs",
+        0x36 => "ss",
+        0x3e => "ds",
+        0x64 => "fs",
+        0x65 => "gs",
+        0x66 => "data16",
+        0x67 => "addr32",
+        0xf0 => "lock",
+        0xf2 => "repnz",
+        0xf3 => "repz",
+        0x40..=0x4f => return rex(byte),
+        _ => return parse_x8664_att_instruction(&format!(".byte {:#x}", byte)).unwrap(),
+    };
+    parse_x8664_att_instruction(prefix).unwrap()
+}
+
+fn rex(byte: u8) -> X8664ATTInstruction {
+    let mut name = "rex".to_string();
+    if byte & 0xf != 0 {
+        name.push('.');
+    }
+    for (bit, flag) in [(8, 'W'), (4, 'R'), (2, 'X'), (1, 'B')] {
+        if byte & bit != 0 {
+            name.push(flag);
+        }
+    }
+    parse_x8664_att_instruction(&name).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodings and what objdump prints for them.
+    const CASES: &[(&[u8], &str)] = &[
+        (&[0xd8, 0xc0], "fadd   %st(0),%st"),
+        (&[0xde, 0xc1], "faddp  %st,%st(1)"),
+        (&[0xd9, 0xc9], "fxch   %st(1)"),
+        (&[0xdd, 0xe1], "fucom  %st(1)"),
+        (&[0xd1, 0xe0], "shl    %eax"),
+        (&[0xc1, 0xe0, 0x01], "shl    $0x1,%eax"),
+        (&[0x64, 0xa4], "movsb  %fs:(%rsi),%es:(%rdi)"),
+        (&[0xf2, 0xae], "repnz scas %es:(%rdi),%al"),
+        (&[0xd7], "xlat   %ds:(%rbx)"),
+        (&[0xec], "in     (%dx),%al"),
+        (&[0xc8, 0x10, 0x00, 0x01], "enter  $0x10,$0x1"),
+        (&[0x66, 0x83, 0xe0, 0xf0], "and    $0xfff0,%ax"),
+        (&[0x6a, 0xff], "push   $0xffffffffffffffff"),
+        (
+            &[0x8b, 0x04, 0x25, 0x00, 0x00, 0x00, 0x00],
+            "mov    0x0,%eax",
+        ),
+        (
+            &[0x64, 0x48, 0x8b, 0x04, 0x25, 0x28, 0x00, 0x00, 0x00],
+            "mov    %fs:0x28,%rax",
+        ),
+        (
+            &[0x48, 0x8d, 0x05, 0x10, 0x00, 0x00, 0x00],
+            "lea    0x10(%rip),%rax",
+        ),
+        (&[0x67, 0x8d, 0x51, 0xff], "lea    -0x1(%ecx),%edx"),
+        (&[0xe8, 0xfb, 0x0f, 0x00, 0x00], "call   2000"),
+        (&[0x3e, 0xff, 0xe0], "notrack jmp *%rax"),
+        (&[0xff, 0x28], "ljmp   *(%rax)"),
+        (
+            &[0x2e, 0x66, 0x0f, 0x1f, 0x04, 0x00],
+            "cs nopw (%rax,%rax,1)",
+        ),
+        (&[0x66, 0x90], "xchg   %ax,%ax"),
+        (&[0xf2, 0xf0, 0xff, 0x00], "xacquire lock incl (%rax)"),
+        (
+            &[0x66, 0x0f, 0x38, 0x10, 0xd1],
+            "pblendvb %xmm0,%xmm1,%xmm2",
+        ),
+        (
+            &[0x62, 0xf1, 0x6c, 0xc9, 0x58, 0xd9],
+            "vaddps %zmm1,%zmm2,%zmm3{%k1}{z}",
+        ),
+        (
+            &[0x62, 0xf1, 0x6c, 0x58, 0x58, 0x18],
+            "vaddps (%rax){1to16},%zmm2,%zmm3",
+        ),
+        (
+            &[0x62, 0xf1, 0x6c, 0x18, 0x58, 0xd9],
+            "vaddps {rn-sae},%zmm1,%zmm2,%zmm3",
+        ),
+        (
+            &[0x62, 0xf1, 0x6c, 0x18, 0xc2, 0xc9, 0x01],
+            "vcmpltps {sae},%zmm1,%zmm2,%k1",
+        ),
+        (
+            &[0x62, 0xf1, 0xf7, 0x18, 0x2a, 0xd0],
+            "vcvtsi2sd %rax,{rn-sae},%xmm1,%xmm2",
+        ),
+    ];
+
+    #[test]
+    fn matches_objdump() {
+        let mut decoder = X8664Decoder::new();
+        for (bytes, objdump) in CASES {
+            let (decoded, length) = decoder.decode(bytes, 0x1000);
+            assert_eq!(length, bytes.len(), "{}", objdump);
+            assert_eq!(
+                decoded,
+                parse_x8664_att_instruction(objdump).unwrap(),
+                "{}",
+                objdump
+            );
+        }
+    }
+
+    #[test]
+    fn folds_fwait_into_control_instructions() {
+        let mut decoder = X8664Decoder::new();
+        let (decoded, length) = decoder.decode(&[0x9b, 0xd9, 0x7d, 0x00], 0);
+        assert_eq!(length, 4);
+        assert_eq!(decoded.to_string(), "fstcw  0x0(%rbp)");
+    }
+
+    #[test]
+    fn undecodable_bytes() {
+        let mut decoder = X8664Decoder::new();
+        assert_eq!(decoder.decode(&[0x48], 0).0.to_string(), "rex.W");
+        assert_eq!(
+            decoder.decode(&[0xe8, 0x00], 0).0.to_string(),
+            ".byte  0xe8"
+        );
+        assert!(decoder.decode(&[0x06, 0x90], 0).0.is_bad());
+    }
+}
@@ -0,0 +1,30 @@
+//! Serde helpers that keep addresses and encodings in the hexadecimal form
+//! objdump prints them in.
+
+use serde::Serializer;
+
+pub fn address<S: Serializer>(address: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{:#x}", address))
+}
+
+pub fn optional_address<S: Serializer>(
+    address: &Option<u64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match address {
+        Some(address) => self::address(address, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn addresses<S: Serializer>(addresses: &[u64], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(addresses.iter().map(|address| format!("{:#x}", address)))
+}
+
+pub fn bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    serializer.serialize_str(&hex)
+}
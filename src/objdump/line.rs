@@ -1,18 +1,54 @@
+use crate::objdump::decode::X8664Decoder;
+use crate::objdump::symbol::{demangle, SymbolReference};
+use crate::objdump::x8664_att::X8664ATTInstruction;
 use crate::ObjumpError;
-use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::io::BufRead;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum ObjDumpLineType {
     Instruction(ObjDumpInstructionLine),
+    Bytes(ObjDumpBytesLine),
+    Symbol(ObjDumpSymbolLine),
     Data(ObjDumpDataLine),
     Other(String),
     Blank,
 }
 
 pub struct ObjDumpInstructionLine {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    pub instruction: X8664ATTInstruction,
+    pub loop_depth: u32,
+}
+
+/// The serialized form of an instruction line, with its symbol annotation.
+#[derive(Serialize)]
+#[serde(rename = "ObjDumpInstructionLine")]
+struct InstructionLineFields<'a> {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
     address: u64,
-    bytes: Vec<u8>,
-    pub instruction: crate::objdump::x8664_att::X8664ATTInstruction,
+    #[serde(serialize_with = "crate::objdump::hex::bytes")]
+    bytes: &'a [u8],
+    instruction: &'a X8664ATTInstruction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<SymbolReference<'a>>,
+    #[serde(skip_serializing_if = "is_zero")]
+    loop_depth: u32,
+}
+
+impl Serialize for ObjDumpInstructionLine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        InstructionLineFields {
+            address: self.address,
+            bytes: &self.bytes,
+            instruction: &self.instruction,
+            symbol: self.symbol(),
+            loop_depth: self.loop_depth,
+        }
+        .serialize(serializer)
+    }
 }
 
 impl std::fmt::Debug for ObjDumpInstructionLine {
@@ -21,54 +57,417 @@ impl std::fmt::Debug for ObjDumpInstructionLine {
             .field("address", &format!("{:#x}", self.address))
             .field("bytes", &format!("{:?}", self.bytes))
             .field("instruction", &self.instruction)
+            .field("loop_depth", &self.loop_depth)
+            .finish()
+    }
+}
+
+impl ObjDumpInstructionLine {
+    /// The `<name+0x10>` annotation of the instruction.
+    pub fn symbol(&self) -> Option<SymbolReference<'_>> {
+        SymbolReference::parse(&self.instruction.data)
+    }
+
+    /// Decodes `bytes` natively, independently of the textual instruction.
+    /// The second value is the number of bytes the decoder consumed.
+    pub fn decode(&self, decoder: &mut X8664Decoder) -> (X8664ATTInstruction, usize) {
+        decoder.decode(&self.bytes, self.address)
+    }
+}
+
+/// Renders the line the way `objdump -d` does, wrapping the encoding after
+/// seven bytes onto continuation lines.
+impl std::fmt::Display for ObjDumpInstructionLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut chunks = self.bytes.chunks(7);
+        let first: String = chunks
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .map(|byte| format!("{:02x} ", byte))
+            .collect();
+        write!(
+            f,
+            "{:>8x}:\t{:<21}\t{}",
+            self.address, first, self.instruction
+        )?;
+        let mut address = self.address;
+        for chunk in chunks {
+            address += 7;
+            let bytes: String = chunk.iter().map(|byte| format!("{:02x} ", byte)).collect();
+            write!(f, "\n{:>8x}:\t{}", address, bytes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Continuation of an instruction whose encoding did not fit on one line.
+#[derive(Serialize)]
+pub struct ObjDumpBytesLine {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    #[serde(serialize_with = "crate::objdump::hex::bytes")]
+    pub bytes: Vec<u8>,
+}
+
+impl std::fmt::Debug for ObjDumpBytesLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjDumpBytesLine")
+            .field("address", &format!("{:#x}", self.address))
+            .field("bytes", &format!("{:?}", self.bytes))
             .finish()
     }
 }
 
-struct ObjDumpDataLine {
+/// Symbol header such as `0000000000401126 <main>:`.
+pub struct ObjDumpSymbolLine {
+    pub address: u64,
+    pub name: String,
+}
+
+impl ObjDumpSymbolLine {
+    /// `name` demangled, for Rust and C++ symbols.
+    pub fn demangled(&self) -> Option<String> {
+        demangle(&self.name, false)
+    }
+}
+
+/// The serialized form of a symbol header, with its demangled name.
+#[derive(Serialize)]
+#[serde(rename = "ObjDumpSymbolLine")]
+struct SymbolLineFields<'a> {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
     address: u64,
-    data: String,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    demangled: Option<String>,
+}
+
+impl Serialize for ObjDumpSymbolLine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SymbolLineFields {
+            address: self.address,
+            name: &self.name,
+            demangled: self.demangled(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl std::fmt::Debug for ObjDumpSymbolLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjDumpSymbolLine")
+            .field("address", &format!("{:#x}", self.address))
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// Line of an `objdump -s` section dump, up to sixteen bytes:
+/// ` 402000 01000200 48656c6c 6f000000  ....Hello...`.
+#[derive(Serialize)]
+pub struct ObjDumpDataLine {
+    #[serde(serialize_with = "crate::objdump::hex::address")]
+    pub address: u64,
+    #[serde(serialize_with = "crate::objdump::hex::bytes")]
+    pub bytes: Vec<u8>,
 }
 
 impl std::fmt::Debug for ObjDumpDataLine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ObjDumpDataLine")
             .field("address", &format!("{:#x}", self.address))
-            .field("data", &self.data)
+            .field("bytes", &format!("{:?}", self.bytes))
             .finish()
     }
 }
 
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+fn parse_bytes(bytes: &str) -> Option<Vec<u8>> {
+    let mut parsed = Vec::with_capacity(bytes.len() / 3 + 1);
+    for byte in bytes.split_ascii_whitespace() {
+        match byte.len() {
+            2 => parsed.push(u8::from_str_radix(byte, 16).ok()?),
+            _ => return None,
+        }
+    }
+    Some(parsed)
+}
+
+fn is_hex(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// `  401126:`, the address column of an instruction line.
+fn instruction_address(column: &str) -> Option<&str> {
+    let address = column.trim().strip_suffix(':')?;
+    (address.len() <= 16 && is_hex(address)).then_some(address)
+}
+
+/// `0000000000401126 <main>:`, a symbol header.
+fn symbol_header(column: &str) -> Option<(&str, &str)> {
+    let (address, rest) = column.split_once(' ')?;
+    let name = rest
+        .trim_end()
+        .strip_prefix('<')?
+        .strip_suffix(">:")
+        .filter(|name| !name.is_empty())?;
+    is_hex(address).then_some((address, name))
+}
+
+/// Whether `line` is a symbol header, where a function's lines start.
+pub(crate) fn is_symbol_header(line: &str) -> bool {
+    let line = line.split('#').next().unwrap();
+    let first = line.split('\t').next().unwrap();
+    instruction_address(first).is_none() && symbol_header(first).is_some()
+}
+
+/// ` 402000 01000200 ...`, the address of a section contents line followed
+/// by the hex region after it.
+fn data_address(column: &str) -> Option<(&str, &str)> {
+    let (address, hex) = column.strip_prefix(' ')?.split_once(' ')?;
+    let valid = is_hex(address) && hex.get(..2).is_some_and(is_hex);
+    valid.then_some((address, hex))
+}
+
+/// Classifies and parses one line of objdump output. Lines are recognised
+/// by scanning their leading columns rather than with regular expressions,
+/// and nothing is allocated beyond the parsed values themselves.
 pub fn parse_objdump_line(line: &str) -> Result<ObjDumpLineType, ObjumpError> {
-    let line = line.split("#").next().unwrap();
+    let line = line.split('#').next().unwrap();
 
-    let parts: Vec<&str> = line.split("\t").collect();
-    if parts.len() < 1 || parts[0].is_empty() {
+    let (first, rest) = line.split_once('\t').unwrap_or((line, ""));
+    if first.is_empty() {
         return Ok(ObjDumpLineType::Blank);
     }
 
-    let data_regex = Regex::new(r"^[0-9a-fA-F]{16}.*")?;
-    let instruction_regex = Regex::new(r"^\W*[0-9a-fA-F]{1,16}:.*")?;
-
-    if instruction_regex.is_match(parts[0]) {
-        let parts_parts: Vec<&str> = parts[0].split_whitespace().collect();
-        let address = u64::from_str_radix(parts_parts[0].trim_end_matches(":"), 16)?;
-        let bytes: Vec<u8> = parts_parts[1..]
-            .iter()
-            .map(|byte| u8::from_str_radix(byte, 16).unwrap())
-            .collect();
-        let instruction = crate::objdump::x8664_att::parse_x8664_att_instruction(parts[1])?;
-        return Ok(ObjDumpLineType::Instruction(ObjDumpInstructionLine {
+    if let Some(address) = instruction_address(first) {
+        let address = u64::from_str_radix(address, 16)?;
+        if !line.contains('\t') {
+            return Ok(ObjDumpLineType::Other(line.to_string()));
+        }
+        let (bytes, instruction) = match rest.split_once('\t') {
+            Some((bytes, instruction)) => {
+                let instruction = instruction.split('\t').next().unwrap();
+                (parse_bytes(bytes), instruction)
+            }
+            None => match parse_bytes(rest) {
+                Some(bytes) => {
+                    return Ok(ObjDumpLineType::Bytes(ObjDumpBytesLine { address, bytes }))
+                }
+                None => (Some(Vec::new()), rest),
+            },
+        };
+        let bytes = bytes.ok_or(ObjumpError::InvalidInstruction(line.to_string()))?;
+        let instruction = crate::objdump::x8664_att::parse_x8664_att_instruction(instruction)?;
+        Ok(ObjDumpLineType::Instruction(ObjDumpInstructionLine {
             address,
             bytes,
             instruction,
-        }));
-    } else if data_regex.is_match(parts[0]) {
-        let parts_parts: Vec<&str> = parts[0].split_whitespace().collect();
-        let address = u64::from_str_radix(parts_parts[0], 16)?;
-        let data = parts_parts[1..].join(" ");
-        return Ok(ObjDumpLineType::Data(ObjDumpDataLine { address, data }));
+            loop_depth: 0,
+        }))
+    } else if let Some((address, name)) = symbol_header(first) {
+        let address = u64::from_str_radix(address, 16)?;
+        Ok(ObjDumpLineType::Symbol(ObjDumpSymbolLine {
+            address,
+            name: name.to_string(),
+        }))
+    } else if let Some((address, hex)) = data_address(first) {
+        let address = u64::from_str_radix(address, 16)?;
+        // Four space-terminated groups of up to four bytes, padded to full
+        // width on a short last line, then the ASCII column.
+        let hex = hex.get(..36).unwrap_or(hex);
+        let mut bytes = Vec::with_capacity(16);
+        for group in hex.split_ascii_whitespace() {
+            for index in (0..group.len()).step_by(2) {
+                let byte = group
+                    .get(index..index + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or(ObjumpError::InvalidInstruction(line.to_string()))?;
+                bytes.push(byte);
+            }
+        }
+        Ok(ObjDumpLineType::Data(ObjDumpDataLine { address, bytes }))
     } else {
-        return Ok(ObjDumpLineType::Other(line.to_string()));
+        Ok(ObjDumpLineType::Other(line.to_string()))
     }
-}
\ No newline at end of file
+}
+
+/// Single-core throughput [`ObjDumpLines`] is held to, in megabytes of
+/// `objdump -d` output per second in release builds: a gigabyte in twenty
+/// seconds. `objump bench` measures it.
+pub const TARGET_THROUGHPUT: f64 = 50.0;
+
+/// Streaming parser over objdump output, yielding each line with its
+/// 1-based line number.
+///
+/// Lines are read into one reused buffer and parsed in place, so the only
+/// allocations are those of the parsed values; see [`TARGET_THROUGHPUT`].
+/// A read error ends the iteration after being yielded.
+///
+/// The parsed values own their data rather than borrowing from the buffer:
+/// the buffer is overwritten by the next line, which an `Iterator` cannot
+/// express, and [`ObjDumpListing`] keeps every instruction long after the
+/// reader is gone, including when reading from a pipe. The allocations
+/// left per instruction are its bytes, its operand list and the text of a
+/// `<symbol>` annotation; symbol names are borrowed from that text and
+/// demangled only on demand. At about 190 MB/s on libc the parser runs at
+/// several times its target, so lifetimes on the line and instruction types
+/// would cost every consumer without a measurable gain.
+///
+/// [`ObjDumpListing`]: crate::objdump::listing::ObjDumpListing
+pub struct ObjDumpLines<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    done: bool,
+}
+
+impl<R: BufRead> ObjDumpLines<R> {
+    pub fn new(reader: R) -> Self {
+        ObjDumpLines {
+            reader,
+            buffer: String::with_capacity(256),
+            line_number: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ObjDumpLines<R> {
+    type Item = (usize, Result<ObjDumpLineType, ObjumpError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.buffer.clear();
+        self.line_number += 1;
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => {
+                self.done = true;
+                None
+            }
+            Ok(_) => {
+                let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                Some((self.line_number, parse_objdump_line(line)))
+            }
+            Err(err) => {
+                self.done = true;
+                Some((self.line_number, Err(err.into())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &[u8]) -> Vec<(usize, &'static str)> {
+        ObjDumpLines::new(input)
+            .map(|(number, line)| {
+                let kind = match line {
+                    Ok(ObjDumpLineType::Instruction(_)) => "instruction",
+                    Ok(ObjDumpLineType::Bytes(_)) => "bytes",
+                    Ok(ObjDumpLineType::Symbol(_)) => "symbol",
+                    Ok(ObjDumpLineType::Data(_)) => "data",
+                    Ok(ObjDumpLineType::Other(_)) => "other",
+                    Ok(ObjDumpLineType::Blank) => "blank",
+                    Err(ObjumpError::IOError(_)) => "io error",
+                    Err(_) => "error",
+                };
+                (number, kind)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn numbers_lines_from_one_including_blank_lines() {
+        let input = concat!(
+            "\nDisassembly of section .text:\n\n0000000000401000 <f>:\n",
+            "  401000:\tc3                   \tret\n\n",
+            "  401001:\tzz\tnop",
+        );
+        assert_eq!(
+            kinds(input.as_bytes()),
+            [
+                (1, "blank"),
+                (2, "other"),
+                (3, "blank"),
+                (4, "symbol"),
+                (5, "instruction"),
+                (6, "blank"),
+                (7, "error"),
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_crlf_line_endings() {
+        let input = concat!(
+            "0000000000401000 <f>:\r\n",
+            "  401000:\t48 89 e5             \tmov    %rsp,%rbp\r\n\r\n",
+        );
+        let lines: Vec<_> = ObjDumpLines::new(input.as_bytes()).collect();
+        assert_eq!(lines.len(), 3);
+        match &lines[0].1 {
+            Ok(ObjDumpLineType::Symbol(symbol)) => assert_eq!(symbol.name, "f"),
+            other => panic!("expected a symbol, got {:?}", other),
+        }
+        match &lines[1].1 {
+            Ok(ObjDumpLineType::Instruction(line)) => {
+                assert_eq!(line.bytes, [0x48, 0x89, 0xe5]);
+                assert_eq!(line.instruction.to_string(), "mov    %rsp,%rbp");
+            }
+            other => panic!("expected an instruction, got {:?}", other),
+        }
+        assert!(matches!(lines[2].1, Ok(ObjDumpLineType::Blank)));
+    }
+
+    #[test]
+    fn read_errors_end_the_iteration() {
+        let input = b"0000000000401000 <f>:\n\xff\xfe\n  401000:\tc3 \tret\n";
+        assert_eq!(kinds(input), [(1, "symbol"), (2, "io error")]);
+    }
+
+    #[test]
+    fn symbol_annotations_stay_mangled_until_rendered() {
+        let line = "  401000:\te8 00 00 00 00       \tcall   401005 <_ZdlPv@plt>";
+        let Ok(ObjDumpLineType::Instruction(instruction)) = parse_objdump_line(line) else {
+            panic!("expected an instruction");
+        };
+        let symbol = instruction.symbol().unwrap();
+        assert_eq!(symbol.name, "_ZdlPv@plt");
+        assert_eq!(symbol.render(false), "<operator delete(void*)@plt>");
+        let json = serde_json::to_value(&instruction).unwrap();
+        assert_eq!(json["symbol"]["name"], "_ZdlPv@plt");
+        assert_eq!(json["symbol"]["mangling"], "itanium");
+    }
+
+    #[test]
+    fn lines_serialize_addresses_and_bytes_as_hex() {
+        let line = "  401000:\t48 8b 44 24 08       \tmov    0x8(%rsp),%rax";
+        let json = serde_json::to_string(&parse_objdump_line(line).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"type":"instruction","value":{"address":"0x401000","bytes":"488b442408","#,
+                r#""instruction":{"opcode":"mov","operands":["#,
+                r#"{"type":"address","value":{"displacement":8,"base":"%rsp"}},"#,
+                r#"{"type":"register","value":"%rax"}]}}}"#,
+            )
+        );
+
+        let line = "  401007:\t00 00 00 ";
+        let json = serde_json::to_string(&parse_objdump_line(line).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"bytes","value":{"address":"0x401007","bytes":"000000"}}"#
+        );
+    }
+}
@@ -0,0 +1,632 @@
+use crate::elf::{
+    ElfFile, ElfSymbol, SHF_ALLOC, SHT_NOBITS, STB_GLOBAL, STB_LOCAL, STT_FILE, STT_FUNC,
+    STT_GNU_IFUNC, STT_OBJECT, STT_SECTION,
+};
+use crate::objdump::decode::X8664Decoder;
+use crate::objdump::line::{
+    is_symbol_header, parse_objdump_line, ObjDumpInstructionLine, ObjDumpLineType, ObjDumpLines,
+};
+use crate::objdump::symbol::demangle;
+use crate::objdump::x8664_att::{X8664ATTOperand, X8664ATTValue};
+use crate::ObjumpError;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::io::{self, BufRead};
+
+/// Address, alias preference, name and section index of a symbol.
+type CodeSymbol = (u64, (u8, Reverse<u64>, bool), String, usize);
+
+const R_X86_64_GLOB_DAT: u32 = 6;
+const R_X86_64_JUMP_SLOT: u32 = 7;
+const R_X86_64_IRELATIVE: u32 = 37;
+
+#[derive(Debug)]
+pub struct ObjDumpFunction {
+    pub address: u64,
+    pub name: String,
+    pub section: String,
+    pub instructions: Vec<ObjDumpInstructionLine>,
+}
+
+impl ObjDumpFunction {
+    /// `name` demangled, for Rust and C++ symbols.
+    pub fn demangled(&self) -> Option<String> {
+        demangle(&self.name, false)
+    }
+}
+
+/// Contents of a section, from `objdump -s`, the data sections of
+/// `objdump -D` or the ELF file.
+#[derive(Debug)]
+pub struct ObjDumpSection {
+    pub name: String,
+    pub address: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl ObjDumpSection {
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.address && address - self.address < self.bytes.len() as u64
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ObjDumpListing {
+    pub functions: Vec<ObjDumpFunction>,
+    /// Sections whose bytes the listing includes, for the jump tables,
+    /// constants and strings code refers to.
+    pub sections: Vec<ObjDumpSection>,
+    pub skipped: usize,
+    /// Line number and message of each line the parser rejected.
+    pub errors: Vec<(usize, String)>,
+}
+
+/// Whether a section holds code, as opposed to the data `objdump -D` also
+/// disassembles.
+fn is_code_section(name: &str) -> bool {
+    name.is_empty()
+        || [".text", ".init", ".fini", ".plt"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Largest run of elided zeros filled in within a section image.
+const MAX_SECTION_GAP: u64 = 1 << 16;
+
+/// Smallest chunk [`ObjDumpListing::parse_parallel`] splits a dump into.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Name of the section a `Disassembly of section .text:` or `Contents of
+/// section .rodata:` header starts.
+fn section_header(line: &str) -> Option<&str> {
+    line.strip_prefix("Disassembly of section ")
+        .or_else(|| line.strip_prefix("Contents of section "))
+        .and_then(|name| name.strip_suffix(':'))
+}
+
+/// Offset of the first symbol header line starting after `from`.
+fn next_symbol_header(data: &[u8], from: usize) -> Option<usize> {
+    let mut start = from;
+    loop {
+        start += data.get(start..)?.iter().position(|&byte| byte == b'\n')? + 1;
+        let end = data[start..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(data.len(), |end| start + end);
+        if std::str::from_utf8(&data[start..end]).is_ok_and(is_symbol_header) {
+            return Some(start);
+        }
+    }
+}
+
+/// A chunk of a dump checked to be text: its number of lines and the last
+/// section header in it. Invalid UTF-8 gives the line it is on instead.
+fn scan(chunk: &[u8]) -> Result<(&str, usize, Option<&str>), usize> {
+    let lines = |bytes: &[u8]| bytes.iter().filter(|&&byte| byte == b'\n').count();
+    let text = std::str::from_utf8(chunk).map_err(|err| lines(&chunk[..err.valid_up_to()]) + 1)?;
+    let section = ["\nDisassembly of section ", "\nContents of section "]
+        .iter()
+        .filter_map(|header| text.rfind(header))
+        .max()
+        .map(|start| &text[start + 1..])
+        .or(Some(text))
+        .and_then(|rest| section_header(rest.lines().next()?.trim_end()));
+    Ok((text, lines(chunk), section))
+}
+
+/// A listing being built from lines in order, with the section they are in.
+struct ListingParser {
+    listing: ObjDumpListing,
+    section: String,
+}
+
+impl ListingParser {
+    fn new(section: &str) -> Self {
+        ListingParser {
+            listing: ObjDumpListing::default(),
+            section: section.to_string(),
+        }
+    }
+
+    /// Adds line `number` of the dump.
+    fn line(
+        &mut self,
+        number: usize,
+        line: Result<ObjDumpLineType, ObjumpError>,
+    ) -> Result<(), ObjumpError> {
+        let listing = &mut self.listing;
+        let section = &mut self.section;
+        match line {
+            Ok(ObjDumpLineType::Symbol(symbol)) => listing.functions.push(ObjDumpFunction {
+                address: symbol.address,
+                name: symbol.name,
+                section: section.clone(),
+                instructions: Vec::new(),
+            }),
+            Ok(ObjDumpLineType::Instruction(instruction)) => {
+                if !is_code_section(section) {
+                    listing.extend_section(section, instruction.address, &instruction.bytes);
+                }
+                match listing.functions.last_mut() {
+                    Some(function) => function.instructions.push(instruction),
+                    None => listing.skipped += 1,
+                }
+            }
+            Ok(ObjDumpLineType::Bytes(bytes)) => {
+                if !is_code_section(section) {
+                    listing.extend_section(section, bytes.address, &bytes.bytes);
+                }
+                match listing
+                    .functions
+                    .last_mut()
+                    .and_then(|function| function.instructions.last_mut())
+                {
+                    Some(instruction) => instruction.bytes.extend(bytes.bytes),
+                    None => listing.skipped += 1,
+                }
+            }
+            Ok(ObjDumpLineType::Data(data)) => {
+                listing.extend_section(section, data.address, &data.bytes)
+            }
+            Ok(ObjDumpLineType::Other(other)) => {
+                if let Some(name) = section_header(&other) {
+                    *section = name.to_string();
+                }
+            }
+            Ok(_) => {}
+            Err(err @ ObjumpError::IOError(_)) => {
+                return Err(ObjumpError::Line(number, Box::new(err)))
+            }
+            Err(err) => {
+                listing.skipped += 1;
+                listing.errors.push((number, err.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ObjDumpListing {
+    /// Groups the instruction lines of an `objdump -d` listing under the symbol
+    /// header that precedes them. Lines the parser rejects are counted in
+    /// `skipped` and recorded in `errors` rather than aborting the whole
+    /// listing. Section contents dumped by `objdump -s`, and the bytes of
+    /// data sections in an `objdump -D` listing, are kept in `sections`.
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, ObjumpError> {
+        let mut parser = ListingParser::new("");
+        for (number, line) in ObjDumpLines::new(reader) {
+            parser.line(number, line)?;
+        }
+        Ok(parser.listing)
+    }
+
+    /// Parses a dump held in memory, such as a mapped file, like
+    /// [`parse`](Self::parse) but on all threads of the rayon pool. The dump
+    /// is split at symbol headers into chunks whose first line number and
+    /// section are found by a first parallel pass, so that chunks parse
+    /// independently and line numbers in `errors` stay those of the whole
+    /// dump. Results are merged in order. With a single thread it is
+    /// [`parse`](Self::parse).
+    pub fn parse_parallel(data: &[u8]) -> Result<Self, ObjumpError> {
+        if rayon::current_num_threads() == 1 {
+            return ObjDumpListing::parse(data);
+        }
+        let size = (data.len() / (rayon::current_num_threads() * 4)).max(CHUNK_SIZE);
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            let end = next_symbol_header(data, start + size).unwrap_or(data.len());
+            chunks.push(&data[start..end]);
+            start = end;
+        }
+
+        let scans: Vec<_> = chunks.par_iter().map(|chunk| scan(chunk)).collect();
+        let mut starts = Vec::with_capacity(scans.len());
+        let mut number = 1;
+        let mut section = "";
+        for scan in scans {
+            let (text, lines, last) = scan.map_err(|line| {
+                let err = io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                );
+                ObjumpError::Line(number + line - 1, Box::new(err.into()))
+            })?;
+            starts.push((text, number, section));
+            number += lines;
+            section = last.unwrap_or(section);
+        }
+
+        let parts = starts
+            .into_par_iter()
+            .map(|(text, number, section)| {
+                let mut parser = ListingParser::new(section);
+                for (index, line) in text.lines().enumerate() {
+                    parser.line(number + index, parse_objdump_line(line))?;
+                }
+                Ok(parser.listing)
+            })
+            .collect::<Result<Vec<_>, ObjumpError>>()?;
+        let mut listing = ObjDumpListing::default();
+        for part in parts {
+            listing.functions.extend(part.functions);
+            for section in part.sections {
+                listing.extend_section(&section.name, section.address, &section.bytes);
+            }
+            listing.skipped += part.skipped;
+            listing.errors.extend(part.errors);
+        }
+        Ok(listing)
+    }
+
+    /// Appends `bytes` at `address` to the image of `section`. objdump
+    /// elides runs of zeros as `...`, so gaps up to [`MAX_SECTION_GAP`] are
+    /// zero-filled. Larger gaps, and lines going back below the end of the
+    /// image, start a new image instead.
+    fn extend_section(&mut self, section: &str, address: u64, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        if !matches!(self.sections.last(),
+            Some(image) if image.name == section
+                && address.checked_sub(image.address + image.bytes.len() as u64)
+                    .is_some_and(|gap| gap <= MAX_SECTION_GAP))
+        {
+            self.sections.push(ObjDumpSection {
+                name: section.to_string(),
+                address,
+                bytes: Vec::new(),
+            });
+        }
+        let Some(image) = self.sections.last_mut() else {
+            return;
+        };
+        let offset = (address - image.address) as usize;
+        image.bytes.resize(offset, 0);
+        image.bytes.extend_from_slice(bytes);
+    }
+
+    /// The section image containing `address`.
+    pub fn section_at(&self, address: u64) -> Option<&ObjDumpSection> {
+        self.sections
+            .iter()
+            .find(|section| section.contains(address))
+    }
+
+    /// Bytes of the sections at `address`, up to the end of the containing
+    /// section.
+    pub fn bytes_at(&self, address: u64) -> Option<&[u8]> {
+        let section = self.section_at(address)?;
+        Some(&section.bytes[(address - section.address) as usize..])
+    }
+
+    /// The little-endian integer of `width` bytes (at most 8) at `address`.
+    pub fn read_integer(&self, address: u64, width: usize) -> Option<u64> {
+        let bytes = self.bytes_at(address)?.get(..width)?;
+        let mut value = [0; 8];
+        value.get_mut(..width)?.copy_from_slice(bytes);
+        Some(u64::from_le_bytes(value))
+    }
+
+    /// Disassembles every executable section of `elf` natively, splitting
+    /// the code at symbol boundaries the way `objdump -d` does. PLT entries
+    /// get synthetic `name@plt` symbols from the PLT relocations. Symbols
+    /// belong to the section their `st_shndx` names, since the sections of
+    /// a relocatable object all start at 0.
+    pub fn disassemble(elf: &ElfFile) -> Self {
+        let mut decoder = X8664Decoder::new();
+        let mut symbols = code_symbols(elf);
+        symbols.extend(plt_symbols(elf, &mut decoder));
+        // Versions do not take part in the ordering; equal aliases keep
+        // their symbol table order.
+        symbols.sort_by(|a, b| (a.0, &a.1, plain_name(&a.2)).cmp(&(b.0, &b.1, plain_name(&b.2))));
+        let mut all = symbols.clone();
+        all.dedup_by_key(|symbol| symbol.0);
+
+        let mut listing = ObjDumpListing::default();
+        for (index, section) in elf
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| section.is_executable())
+        {
+            let end = section.address + section.size;
+            let mut own: Vec<CodeSymbol> = symbols
+                .iter()
+                .filter(|symbol| symbol.3 == index && section.contains(symbol.0))
+                .cloned()
+                .collect();
+            own.dedup_by_key(|symbol| symbol.0);
+            let mut starts: Vec<(u64, String)> = own
+                .iter()
+                .map(|symbol| (symbol.0, symbol.2.clone()))
+                .collect();
+            // objdump names the code before the first symbol relative to it.
+            let name = match starts.first() {
+                Some(first) if first.0 > section.address => {
+                    Some(format!("{}-{:#x}", first.1, first.0 - section.address))
+                }
+                Some(_) => None,
+                None => Some(section.name.clone()),
+            };
+            if let Some(name) = name {
+                starts.insert(0, (section.address, name));
+            }
+            for (index, (address, name)) in starts.iter().enumerate() {
+                let stop = starts.get(index + 1).map_or(end, |next| next.0);
+                let mut function = ObjDumpFunction {
+                    address: *address,
+                    name: name.clone(),
+                    section: section.name.clone(),
+                    instructions: Vec::new(),
+                };
+                let mut address = *address;
+                while address < stop {
+                    let offset = (address - section.address) as usize;
+                    let bytes = &section.data[offset..(stop - section.address) as usize];
+                    let (mut instruction, length) = decoder.decode(bytes, address);
+                    if let Some(X8664ATTOperand::Target(target)) = instruction.operands.last() {
+                        let symbols = match section.contains(*target) {
+                            true => &own,
+                            false => &all,
+                        };
+                        instruction.data = symbolize(symbols, *target);
+                    }
+                    function.instructions.push(ObjDumpInstructionLine {
+                        address,
+                        bytes: bytes[..length].to_vec(),
+                        instruction,
+                        loop_depth: 0,
+                    });
+                    address += length as u64;
+                }
+                listing.functions.push(function);
+            }
+        }
+        listing.sections = elf
+            .sections
+            .iter()
+            .filter(|section| {
+                section.flags & SHF_ALLOC != 0
+                    && section.kind != SHT_NOBITS
+                    && !section.is_executable()
+            })
+            .map(|section| ObjDumpSection {
+                name: section.name.clone(),
+                address: section.address,
+                bytes: section.data.clone(),
+            })
+            .collect();
+        listing
+    }
+}
+
+/// `(address, rank, name, section)` of the symbols that can start a function. Aliases
+/// are ordered like objdump orders them: functions, objects, non-local and
+/// global bindings first, then larger sizes, then by name.
+fn code_symbols(elf: &ElfFile) -> Vec<CodeSymbol> {
+    let table: &[ElfSymbol] = match elf.symbols.is_empty() {
+        true => &elf.dynamic_symbols,
+        false => &elf.symbols,
+    };
+    table
+        .iter()
+        .filter(|symbol| {
+            !symbol.name.is_empty()
+                && symbol.section != 0
+                && !matches!(symbol.kind, STT_SECTION | STT_FILE)
+        })
+        .map(|symbol| {
+            let function = matches!(symbol.kind, STT_FUNC | STT_GNU_IFUNC);
+            let flags = u8::from(!function) << 3
+                | u8::from(symbol.kind != STT_OBJECT) << 2
+                | u8::from(symbol.binding == STB_LOCAL) << 1
+                | u8::from(symbol.binding != STB_GLOBAL);
+            let rank = (flags, Reverse(symbol.size), symbol.name.starts_with('.'));
+            (
+                symbol.value,
+                rank,
+                symbol.name.clone(),
+                symbol.section as usize,
+            )
+        })
+        .collect()
+}
+
+/// Names the PLT stubs after the dynamic symbol their GOT slot resolves.
+fn plt_symbols(elf: &ElfFile, decoder: &mut X8664Decoder) -> Vec<CodeSymbol> {
+    let mut slots = Vec::new();
+    for section in &elf.sections {
+        for relocation in elf.relocations(section) {
+            let name = match relocation.kind {
+                R_X86_64_JUMP_SLOT | R_X86_64_GLOB_DAT => elf
+                    .dynamic_symbols
+                    .get(relocation.symbol as usize)
+                    .map(|symbol| plain_name(&symbol.name).to_string()),
+                R_X86_64_IRELATIVE => Some(format!("*ABS*+{:#x}", relocation.addend)),
+                _ => None,
+            };
+            slots.extend(name.map(|name| (relocation.offset, name)));
+        }
+    }
+
+    let mut symbols = Vec::new();
+    for (index, section) in elf.sections.iter().enumerate() {
+        if !section.is_executable() || !section.name.starts_with(".plt") {
+            continue;
+        }
+        let entry_size = section.entry_size.max(8);
+        let mut offset = 0;
+        while offset < section.data.len() {
+            let address = section.address + offset as u64;
+            let (instruction, length) = decoder.decode(&section.data[offset..], address);
+            offset += length;
+            let Some(X8664ATTOperand::Indirect(target)) = instruction.operands.first() else {
+                continue;
+            };
+            let X8664ATTOperand::Address(memory) = target.as_ref() else {
+                continue;
+            };
+            let rip = matches!(&memory.base, Some(X8664ATTValue::Register(register)) if register.name() == "%rip");
+            if !rip || instruction.opcode.operation() != "jmp" {
+                continue;
+            }
+            let displacement = memory.displacement.as_ref().map_or(0, |d| d.0);
+            let slot = (section.address + offset as u64).wrapping_add_signed(displacement);
+            if let Some((_, name)) = slots.iter().find(|(address, _)| *address == slot) {
+                let start = address - (address - section.address) % entry_size;
+                let name = format!("{}@plt", name);
+                symbols.push((start, (0, Reverse(0), false), name, index));
+            }
+        }
+    }
+    symbols
+}
+
+/// Symbol name without its `@VERSION` or `@@VERSION` suffix.
+fn plain_name(name: &str) -> &str {
+    name.split('@').next().unwrap_or(name)
+}
+
+/// `<name+0xoffset>` for the closest symbol at or before `address`, or
+/// `<name-0xoffset>` for the first symbol when none precedes it.
+fn symbolize(symbols: &[CodeSymbol], address: u64) -> String {
+    let index = symbols.partition_point(|symbol| symbol.0 <= address);
+    match index.checked_sub(1).map(|index| &symbols[index]) {
+        Some((start, _, name, _)) if *start == address => format!("<{}>", name),
+        Some((start, _, name, _)) => format!("<{}+{:#x}>", name, address - start),
+        None => match symbols.first() {
+            Some((start, _, name, _)) => format!("<{}-{:#x}>", name, start - address),
+            None => String::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction(address: u64, bytes: &str, text: &str) -> String {
+        format!("  {:x}:\t{:<21}\t{}\n", address, bytes, text)
+    }
+
+    /// A dump of several [`CHUNK_SIZE`]s with a `.plt` section in the
+    /// middle, and the line numbers of the malformed lines in it.
+    fn large_dump() -> (String, Vec<usize>) {
+        let mut dump = String::from("\nDisassembly of section .text:\n");
+        let mut bad = Vec::new();
+        let mut address = 0x401000;
+        let mut index = 0;
+        while dump.len() < 3 * CHUNK_SIZE {
+            if index == 6000 {
+                dump.push_str("\nDisassembly of section .plt:\n");
+            }
+            dump.push_str(&format!("\n{:016x} <f{}>:\n", address, index));
+            dump.push_str(&instruction(address, "31 c0", "xor    %eax,%eax"));
+            dump.push_str(&instruction(address + 2, "ff c0", "inc    %eax"));
+            if index % 5000 == 1 {
+                bad.push(dump.lines().count() + 1);
+                dump.push_str(&instruction(address + 4, "zz", "nop"));
+            }
+            dump.push_str(&instruction(address + 4, "c3", "ret"));
+            address += 0x10;
+            index += 1;
+        }
+        (dump, bad)
+    }
+
+    #[test]
+    fn parallel_parse_matches_sequential_parse() {
+        let (dump, bad) = large_dump();
+        let sequential = ObjDumpListing::parse(dump.as_bytes()).unwrap();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let parallel = pool
+            .install(|| ObjDumpListing::parse_parallel(dump.as_bytes()))
+            .unwrap();
+        assert_eq!(format!("{:?}", parallel), format!("{:?}", sequential));
+
+        let lines: Vec<usize> = parallel.errors.iter().map(|error| error.0).collect();
+        assert_eq!(lines, bad);
+        assert!(bad.len() > 2);
+        assert_eq!(parallel.skipped, bad.len());
+        assert_eq!(parallel.functions[5999].section, ".text");
+        assert_eq!(parallel.functions.last().unwrap().section, ".plt");
+    }
+
+    /// `objdump -s` output: short last lines are padded before the ASCII
+    /// column, which may itself look like hex.
+    const CONTENTS: &str = "\
+Contents of section .data:
+ 404000 88776655 44332211                    .wfUD3\".
+Contents of section .rodata:
+ 402000 61622063 64203132 20333420 656600    ab cd 12 34 ef.
+ 402020 01000000                             ....
+";
+
+    #[test]
+    fn parses_section_contents() {
+        let listing = ObjDumpListing::parse(CONTENTS.as_bytes()).unwrap();
+        assert!(listing.errors.is_empty());
+        let sections: Vec<_> = listing
+            .sections
+            .iter()
+            .map(|section| (section.name.as_str(), section.address, section.bytes.len()))
+            .collect();
+        assert_eq!(
+            sections,
+            [(".data", 0x404000, 8), (".rodata", 0x402000, 0x24)]
+        );
+
+        assert_eq!(listing.read_integer(0x404000, 8), Some(0x1122334455667788));
+        assert_eq!(listing.read_integer(0x404004, 4), Some(0x11223344));
+        assert_eq!(listing.read_integer(0x404006, 4), None);
+        assert_eq!(
+            listing.bytes_at(0x402000).unwrap()[..15],
+            *b"ab cd 12 34 ef\0"
+        );
+        // Bytes missing between two lines read as zeros.
+        assert_eq!(listing.read_integer(0x402010, 8), Some(0));
+        assert_eq!(listing.read_integer(0x402020, 4), Some(1));
+        assert!(listing.section_at(0x402024).is_none());
+    }
+
+    #[test]
+    fn distant_contents_start_a_new_image() {
+        let contents = "\
+Contents of section .data:
+ 00002000 01000000                             ....
+ 7fff0000 02000000                             ....
+";
+        let listing = ObjDumpListing::parse(contents.as_bytes()).unwrap();
+        let sections: Vec<_> = listing
+            .sections
+            .iter()
+            .map(|section| (section.address, section.bytes.len()))
+            .collect();
+        assert_eq!(sections, [(0x2000, 4), (0x7fff0000, 4)]);
+        assert_eq!(listing.read_integer(0x7fff0000, 4), Some(2));
+        assert!(listing.section_at(0x3000).is_none());
+    }
+
+    #[test]
+    fn contents_going_back_keep_earlier_bytes() {
+        // Two sections of a relocatable object, both at address 0.
+        let contents = "\
+Contents of section .rodata:
+ 0000 01000000 02000000                    ........
+Contents of section .rodata:
+ 0000 03000000                             ....
+";
+        let listing = ObjDumpListing::parse(contents.as_bytes()).unwrap();
+        let sizes: Vec<_> = listing
+            .sections
+            .iter()
+            .map(|section| section.bytes.len())
+            .collect();
+        assert_eq!(sizes, [8, 4]);
+        assert_eq!(listing.read_integer(4, 4), Some(2));
+    }
+}
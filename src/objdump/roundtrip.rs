@@ -0,0 +1,64 @@
+use crate::objdump::line::{parse_objdump_line, ObjDumpLineType};
+use crate::ObjumpError;
+use std::io::BufRead;
+
+#[derive(Debug)]
+pub struct RoundTripMismatch {
+    pub line_number: usize,
+    pub original: String,
+    pub rendered: String,
+}
+
+#[derive(Debug, Default)]
+pub struct RoundTripReport {
+    pub instructions: usize,
+    pub mismatches: Vec<RoundTripMismatch>,
+    pub errors: Vec<(usize, String)>,
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Instruction column of an objdump line, without the trailing `# ...` comment.
+fn instruction_text(line: &str) -> Option<&str> {
+    let line = line.split('#').next().unwrap();
+    let mut columns = line.split('\t').skip(1);
+    let first = columns.next()?;
+    Some(columns.next().unwrap_or(first))
+}
+
+impl RoundTripReport {
+    /// Parses every instruction line of `reader`, renders it back with
+    /// `Display` and records the lines whose text differs from objdump's
+    /// modulo whitespace.
+    pub fn check<R: BufRead>(reader: R) -> Result<Self, ObjumpError> {
+        let mut report = RoundTripReport::default();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            match parse_objdump_line(&line) {
+                Ok(ObjDumpLineType::Instruction(parsed)) => {
+                    report.instructions += 1;
+                    let original = normalize(instruction_text(&line).unwrap_or_default());
+                    let rendered = normalize(&parsed.instruction.to_string());
+                    if original != rendered {
+                        report.mismatches.push(RoundTripMismatch {
+                            line_number: index + 1,
+                            original,
+                            rendered,
+                        });
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => report.errors.push((index + 1, err.to_string())),
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn merge(&mut self, other: RoundTripReport) {
+        self.instructions += other.instructions;
+        self.mismatches.extend(other.mismatches);
+        self.errors.extend(other.errors);
+    }
+}
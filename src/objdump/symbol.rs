@@ -0,0 +1,198 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Name mangling scheme of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mangling {
+    /// `_ZN...17h<hash>E`, Rust before the v0 scheme.
+    RustLegacy,
+    /// `_R...`, Rust symbol mangling v0.
+    RustV0,
+    /// `_Z...`, the C++ Itanium ABI.
+    Itanium,
+}
+
+impl Mangling {
+    /// Scheme `name` is mangled with, if any. Rust legacy names are Itanium
+    /// nested names whose last component is a hash, and are told apart that
+    /// way. Names are only parsed, not demangled.
+    pub fn of(name: &str) -> Option<Mangling> {
+        let (name, _) = split_suffix(name);
+        if name.starts_with("_R") || name.starts_with("__R") {
+            return rustc_demangle::try_demangle(name)
+                .ok()
+                .map(|_| Mangling::RustV0);
+        }
+        if !name.starts_with("_Z") && !name.starts_with("__Z") {
+            return None;
+        }
+        if has_legacy_hash(name) && rustc_demangle::try_demangle(name).is_ok() {
+            return Some(Mangling::RustLegacy);
+        }
+        cpp_demangle::Symbol::new(name)
+            .ok()
+            .map(|_| Mangling::Itanium)
+    }
+
+    /// Demangles `name`, which is mangled with this scheme, keeping any
+    /// `@version` suffix. `strip_hash` drops the `::h<hash>` of Rust legacy
+    /// names and the crate disambiguators of v0 names.
+    pub fn demangle(self, name: &str, strip_hash: bool) -> Option<String> {
+        let (mangled, suffix) = split_suffix(name);
+        let demangled = match self {
+            Mangling::RustLegacy | Mangling::RustV0 => {
+                let demangled = rustc_demangle::try_demangle(mangled).ok()?;
+                match strip_hash {
+                    true => format!("{:#}", demangled),
+                    false => demangled.to_string(),
+                }
+            }
+            Mangling::Itanium => cpp_demangle::Symbol::new(mangled).ok()?.demangle().ok()?,
+        };
+        Some(format!("{}{}", demangled, suffix))
+    }
+}
+
+/// Whether a `_ZN...E` name ends with the `17h<16 hex digits>E` component
+/// rustc appends to legacy symbols, possibly followed by a `.llvm.<n>`
+/// suffix. Components may contain dots themselves (`serde_json..number`).
+fn has_legacy_hash(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    name.match_indices("17h").any(|(start, _)| {
+        let end = start + 19;
+        bytes.len() > end
+            && bytes[start + 3..end].iter().all(u8::is_ascii_hexdigit)
+            && bytes[end] == b'E'
+            && bytes.get(end + 1).is_none_or(|&next| next == b'.')
+    })
+}
+
+/// Splits the version (`@@GLIBC_2.2.5`) or `@plt` suffix objdump appends to
+/// symbol names.
+fn split_suffix(name: &str) -> (&str, &str) {
+    match name.find('@') {
+        Some(index) => name.split_at(index),
+        None => (name, ""),
+    }
+}
+
+/// Demangles a Rust (legacy or v0) or Itanium C++ symbol, keeping any
+/// `@version` suffix. `strip_hash` drops the `::h<hash>` of Rust legacy
+/// names and the crate disambiguators of v0 names. Returns `None` for
+/// names that are not mangled.
+pub fn demangle(name: &str, strip_hash: bool) -> Option<String> {
+    Mangling::of(name)?.demangle(name, strip_hash)
+}
+
+/// Symbol annotation of an instruction, `<name+0x10>`, borrowed from the
+/// instruction text. The name is kept mangled: its scheme is only worked
+/// out, and the name demangled, when rendered or serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolReference<'a> {
+    pub name: &'a str,
+    pub offset: u64,
+}
+
+impl<'a> SymbolReference<'a> {
+    /// Parses the `<name+0x10>` annotation objdump prints after branch
+    /// targets and RIP-relative operands.
+    pub fn parse(data: &'a str) -> Option<Self> {
+        let inner = data.trim().strip_prefix('<')?.strip_suffix('>')?;
+        let (name, offset) = match inner.rsplit_once('+') {
+            Some((name, offset)) if offset.starts_with("0x") => {
+                (name, u64::from_str_radix(&offset[2..], 16).ok()?)
+            }
+            _ => (inner, 0),
+        };
+        Some(SymbolReference { name, offset })
+    }
+
+    pub fn mangling(&self) -> Option<Mangling> {
+        Mangling::of(self.name)
+    }
+
+    /// The annotation with the name demangled when possible.
+    pub fn render(&self, strip_hash: bool) -> String {
+        let demangled = self
+            .mangling()
+            .and_then(|mangling| mangling.demangle(self.name, strip_hash));
+        let name = demangled.as_deref().unwrap_or(self.name);
+        match self.offset {
+            0 => format!("<{}>", name),
+            offset => format!("<{}+{:#x}>", name, offset),
+        }
+    }
+}
+
+impl Serialize for SymbolReference<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mangling = self.mangling();
+        let mut symbol = serializer.serialize_struct("SymbolReference", 3)?;
+        symbol.serialize_field("name", self.name)?;
+        match self.offset {
+            0 => symbol.skip_field("offset")?,
+            offset => symbol.serialize_field("offset", &offset)?,
+        }
+        match mangling {
+            Some(mangling) => symbol.serialize_field("mangling", &mangling)?,
+            None => symbol.skip_field("mangling")?,
+        }
+        symbol.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = concat!(
+        "_ZN73_$LT$serde_json..number..Number$u20$as$u20$",
+        "serde_core..ser..Serialize$GT$9serialize17h32a415181fce6f9eE"
+    );
+
+    #[test]
+    fn mangling_schemes() {
+        assert_eq!(Mangling::of(LEGACY), Some(Mangling::RustLegacy));
+        assert_eq!(
+            Mangling::of("_ZN4core3ptr13drop_in_place17h0123456789abcdefE.llvm.42"),
+            Some(Mangling::RustLegacy)
+        );
+        assert_eq!(
+            Mangling::of("_RNvCs1234_7example4main"),
+            Some(Mangling::RustV0)
+        );
+        assert_eq!(
+            Mangling::of("_ZNSt6vectorIiSaIiEE9push_backERKi@plt"),
+            Some(Mangling::Itanium)
+        );
+        // A C++ name whose last component merely looks like a hash.
+        assert_eq!(Mangling::of("_ZN3foo3barE"), Some(Mangling::Itanium));
+        assert_eq!(Mangling::of("memcpy@@GLIBC_2.14"), None);
+    }
+
+    #[test]
+    fn render_demangles_on_demand() {
+        let data = format!("<{}+0x10>", LEGACY);
+        let symbol = SymbolReference::parse(&data).unwrap();
+        assert_eq!(symbol.name, LEGACY);
+        assert_eq!(symbol.offset, 0x10);
+        assert_eq!(symbol.mangling(), Some(Mangling::RustLegacy));
+        assert_eq!(
+            symbol.render(false),
+            concat!(
+                "<<serde_json::number::Number as serde_core::ser::Serialize>",
+                "::serialize::h32a415181fce6f9e+0x10>"
+            )
+        );
+        assert_eq!(
+            symbol.render(true),
+            "<<serde_json::number::Number as serde_core::ser::Serialize>::serialize+0x10>"
+        );
+        let symbol = SymbolReference::parse("<_ZdlPv@plt>").unwrap();
+        assert_eq!(symbol.render(false), "<operator delete(void*)@plt>");
+        let symbol = SymbolReference::parse("<main+0x4>").unwrap();
+        assert_eq!(symbol.mangling(), None);
+        assert_eq!(symbol.render(true), "<main+0x4>");
+    }
+}
@@ -0,0 +1,211 @@
+use crate::objdump::decode::X8664Decoder;
+use crate::objdump::line::{parse_objdump_line, ObjDumpInstructionLine, ObjDumpLineType};
+use crate::objdump::x8664_att::{X8664ATTInstruction, X8664ATTOperand};
+use crate::ObjumpError;
+use std::io::BufRead;
+
+#[derive(Debug)]
+pub struct VerifyDisagreement {
+    pub line_number: usize,
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    /// First field that differs: `mnemonic`, `operand 2 displacement`, ...
+    pub field: String,
+    pub objdump: String,
+    pub decoded: String,
+}
+
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub instructions: usize,
+    pub disagreements: Vec<VerifyDisagreement>,
+    pub errors: Vec<(usize, String)>,
+}
+
+impl VerifyReport {
+    /// Decodes the bytes of every instruction line of `reader` and records
+    /// the lines where a field of the decoded instruction differs from the
+    /// one parsed from objdump's text, or where the decoder consumes a
+    /// different number of bytes. Symbol
+    /// annotations (`<main+0x10>`) are not compared. The bytes of the next
+    /// line of the same function are visible to the decoder, as they were
+    /// to objdump, so that `(bad)` and truncated `.byte` lines decode alike.
+    pub fn check<R: BufRead>(reader: R) -> Result<Self, ObjumpError> {
+        let mut report = VerifyReport::default();
+        let mut decoder = X8664Decoder::new();
+        let mut pending: Option<(usize, ObjDumpInstructionLine)> = None;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let parsed = parse_objdump_line(&line);
+            if let Ok(ObjDumpLineType::Bytes(bytes)) = &parsed {
+                match pending.as_mut() {
+                    Some((_, instruction)) => instruction.bytes.extend(&bytes.bytes),
+                    None => report
+                        .errors
+                        .push((index + 1, "continuation without instruction".to_string())),
+                }
+                continue;
+            }
+            if let Some((line_number, instruction)) = pending.take() {
+                let following = match &parsed {
+                    Ok(ObjDumpLineType::Instruction(next))
+                        if next.address == instruction.address + instruction.bytes.len() as u64 =>
+                    {
+                        &next.bytes[..]
+                    }
+                    _ => &[],
+                };
+                report.compare(&mut decoder, line_number, instruction, following);
+            }
+            match parsed {
+                Ok(ObjDumpLineType::Instruction(instruction)) => {
+                    pending = Some((index + 1, instruction))
+                }
+                Ok(_) => {}
+                Err(err) => report.errors.push((index + 1, err.to_string())),
+            }
+        }
+        if let Some((line_number, instruction)) = pending {
+            report.compare(&mut decoder, line_number, instruction, &[]);
+        }
+        Ok(report)
+    }
+
+    fn compare(
+        &mut self,
+        decoder: &mut X8664Decoder,
+        line_number: usize,
+        line: ObjDumpInstructionLine,
+        following: &[u8],
+    ) {
+        self.instructions += 1;
+        let bytes = [&line.bytes[..], following].concat();
+        let (decoded, length) = decoder.decode(&bytes, line.address);
+        let field = match length == line.bytes.len() {
+            true => difference(&decoded, &line.instruction),
+            false => Some("length".to_string()),
+        };
+        if let Some(field) = field {
+            let mut rendered = decoded.to_string();
+            if length != line.bytes.len() {
+                rendered = format!("{} ({} of {} bytes)", rendered, length, line.bytes.len());
+            }
+            self.disagreements.push(VerifyDisagreement {
+                line_number,
+                address: line.address,
+                bytes: line.bytes,
+                field,
+                objdump: line.instruction.to_string(),
+                decoded: rendered,
+            });
+        }
+    }
+
+    pub fn merge(&mut self, other: VerifyReport) {
+        self.instructions += other.instructions;
+        self.disagreements.extend(other.disagreements);
+        self.errors.extend(other.errors);
+    }
+}
+
+/// Name of the first field of `decoded` that differs from `parsed`. The
+/// `<symbol>` annotation is not compared.
+fn difference(decoded: &X8664ATTInstruction, parsed: &X8664ATTInstruction) -> Option<String> {
+    if decoded.prefixes != parsed.prefixes {
+        return Some("prefixes".to_string());
+    }
+    if decoded.opcode != parsed.opcode {
+        return Some("mnemonic".to_string());
+    }
+    if decoded.operands.len() != parsed.operands.len() {
+        return Some("operand count".to_string());
+    }
+    decoded
+        .operands
+        .iter()
+        .zip(&parsed.operands)
+        .enumerate()
+        .find_map(|(index, (decoded, parsed))| {
+            operand_difference(decoded, parsed).map(|field| format!("operand {} {}", index, field))
+        })
+}
+
+fn operand_difference(decoded: &X8664ATTOperand, parsed: &X8664ATTOperand) -> Option<&'static str> {
+    match (decoded, parsed) {
+        _ if decoded == parsed => None,
+        (X8664ATTOperand::Register(_), X8664ATTOperand::Register(_)) => Some("register"),
+        (X8664ATTOperand::Immediate(_), X8664ATTOperand::Immediate(_)) => Some("immediate"),
+        (X8664ATTOperand::Target(_), X8664ATTOperand::Target(_)) => Some("target"),
+        (X8664ATTOperand::Address(decoded), X8664ATTOperand::Address(parsed)) => {
+            Some(if decoded.segment != parsed.segment {
+                "segment"
+            } else if decoded.base != parsed.base {
+                "base"
+            } else if decoded.offset != parsed.offset {
+                "index"
+            } else if decoded.scaler != parsed.scaler {
+                "scale"
+            } else {
+                "displacement"
+            })
+        }
+        (X8664ATTOperand::Indirect(decoded), X8664ATTOperand::Indirect(parsed)) => {
+            operand_difference(decoded, parsed)
+        }
+        (X8664ATTOperand::Decorated(decoded, _), X8664ATTOperand::Decorated(parsed, _)) => {
+            operand_difference(decoded, parsed).or(Some("decoration"))
+        }
+        _ => Some("kind"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(dump: &str) -> Vec<(usize, String)> {
+        let report = VerifyReport::check(dump.as_bytes()).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        report
+            .disagreements
+            .into_iter()
+            .map(|disagreement| (disagreement.line_number, disagreement.field))
+            .collect()
+    }
+
+    #[test]
+    fn continuation_bytes_belong_to_the_instruction() {
+        let dump = "
+0000000000401000 <f>:
+  401000:\t48 b8 88 77 66 55 44 \tmovabs $0x1122334455667788,%rax
+  401007:\t33 22 11 
+  40100a:\tc3                   \tret
+";
+        let report = VerifyReport::check(dump.as_bytes()).unwrap();
+        assert_eq!(report.instructions, 2);
+        assert!(
+            report.disagreements.is_empty(),
+            "{:?}",
+            report.disagreements
+        );
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn text_disagreeing_with_the_bytes_names_the_field() {
+        let dump = "
+0000000000401000 <f>:
+  401000:\t48 89 c7             \tmov    %rax,%rsi
+  401003:\t48 8b 44 24 08       \tmov    0x10(%rsp),%rax
+  401008:\t31 c0 c3             \txor    %eax,%eax
+";
+        assert_eq!(
+            fields(dump),
+            [
+                (3, "operand 1 register".to_string()),
+                (4, "operand 0 displacement".to_string()),
+                (5, "length".to_string()),
+            ]
+        );
+    }
+}
@@ -1,13 +1,22 @@
 use crate::ObjumpError;
+use serde::{Serialize, Serializer};
 
-#[derive(Debug)]
+mod intel;
+mod isa;
+
+pub use isa::{X8664ATTCategory, X8664ATTExtension};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct X8664ATTInstruction {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<X8664ATTOpcode>,
     pub opcode: X8664ATTOpcode,
-    operands: Vec<X8664ATTOperand>,
-    data: String,
+    pub operands: Vec<X8664ATTOperand>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub data: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum X8664ATTOpcode {
     Add,
     Insb,
@@ -537,6 +546,292 @@ pub enum X8664ATTOpcode {
 	Fptan,
 	Fcos,
 	Fucompp,
+    Repz,
+    Repnz,
+    Notrack,
+    Bnd,
+    Call,
+    Ret,
+    Jecxz,
+    Push,
+    Xchg,
+    Mov,
+    Lea,
+    Sub,
+    Xor,
+    Cmp,
+    Cmpxchg,
+    Dec,
+    Stos,
+    Test,
+    Pop,
+    Rol,
+    And,
+    Shr,
+    Cmove,
+    Ror,
+    Neg,
+    Sbb,
+    Shl,
+    Imul,
+    Or,
+    Cmovne,
+    Cmovle,
+    Cmovg,
+    Cmovl,
+    Cmovns,
+    Cmovs,
+    Sar,
+    Bswap,
+    Div,
+    Cmovae,
+    Movabs,
+    Bt,
+    Shufpd,
+    Not,
+    Adc,
+    Cmovb,
+    Inc,
+    Fabs,
+    Fchs,
+    Fld1,
+    Fscale,
+    Fucomip,
+    Fldz,
+    Btr,
+    Movapd,
+    Andpd,
+    Andnpd,
+    Orpd,
+    Mulsd,
+    Subsd,
+    Addsd,
+    Ucomisd,
+    Setp,
+    Andps,
+    Andnps,
+    Subss,
+    Mulss,
+    Addss,
+    Bsr,
+    Idiv,
+    Stmxcsr,
+    Cmovge,
+    Cmovbe,
+    Ldmxcsr,
+    Comisd,
+    Xorpd,
+    Fcomip,
+    Cvtss2sd,
+    Mul,
+    Setl,
+    Shld,
+    Shrd,
+    Bts,
+    Movmskpd,
+    Tzcnt,
+    Fcomi,
+    Cvtsi2sd,
+    Divsd,
+    Cmova,
+    Movhlps,
+    Xbegin,
+    Xend,
+    Xadd,
+    Bsf,
+    Movntdq,
+    Sfence,
+    Pminub,
+    Movlpd,
+    Movhpd,
+    Psubb,
+    Pslldq,
+    Psrldq,
+    Bzhi,
+    Kmovq,
+    Vmovdqu8,
+    Vptestnmb,
+    Blsmsk,
+    Vpbroadcastb,
+    Vpcmpneqb,
+    Kortestq,
+    Vzeroupper,
+    Blsr,
+    Vmovdqa64,
+    Vmovdqu64,
+    Kxnorq,
+    Rdpkru,
+    Wrpkru,
+    Cvtsi2ss,
+    Divss,
+    Cvttss2si,
+    Psrlw,
+    Vmovd,
+    Vpmovmskb,
+    Vpor,
+    Sarx,
+    Vmovdqu,
+    Vpand,
+    Movbe,
+    Vmovdqa,
+    Vmovntdq,
+    Lzcnt,
+    Shlx,
+    Vpbroadcastd,
+    Vmovq,
+    Vpxor,
+    Vpminub,
+    Vpaddb,
+    Vpcmpgtb,
+    Vmovaps,
+    Shrx,
+    Vpcmpeqd,
+    Vpminud,
+    Xtest,
+    Vzeroall,
+    Kmovd,
+    Vpternlogd,
+    Vpcmpnequb,
+    Vpxorq,
+    Vptestmb,
+    Kortestd,
+    Vpsubb,
+    Vpcmpltub,
+    Ktestd,
+    Kord,
+    Kunpckdq,
+    Vpxord,
+    Vptestnmd,
+    Vpcmpneqd,
+    Vptestmd,
+    Kunpckbw,
+    Vmovdqu32,
+    Vmovups,
+    Vbroadcastss,
+    Palignr,
+    Pcmpistri,
+    Pshufb,
+    Pminud,
+    Endbr64,
+    Fwait,
+    Fnclex,
+    Fstcw,
+    Fyl2xp1,
+    F2xm1,
+    Btc,
+    Setnp,
+    Fpatan,
+    Unpcklpd,
+    Cvttsd2si,
+    Cmpnlesd,
+    Sets,
+    Cmpnless,
+    Cvtsd2ss,
+    Fsqrt,
+    Fstsw,
+    Fldl2e,
+    Fldln2,
+    Fyl2x,
+    Fprem1,
+    Fcmove,
+    Fldl2t,
+    Sqrtsd,
+    Cmpltsd,
+    Cmplesd,
+    Vfmadd213sd,
+    Vfmaddsd,
+    Cvtsd2si,
+    Movupd,
+    Addpd,
+    Maxsd,
+    Minsd,
+    Cmovnp,
+    Sqrtss,
+    Cvtsi2ssl,
+    Vfmadd213ss,
+    Vfmaddss,
+    Cvtss2si,
+    Maxss,
+    Minss,
+    Cmpless,
+    Cvtdq2pd,
+    Cvtsi2sdl,
+    Cvttpd2dq,
+    Roundsd,
+    Roundss,
+    Vaddsd,
+    Vmulsd,
+    Vmovsd,
+    Vfmadd231sd,
+    Vandpd,
+    Vcomisd,
+    Vxorpd,
+    Vsubsd,
+    Vfmadd132sd,
+    Vfnmadd132sd,
+    Vfnmadd213sd,
+    Vdivsd,
+    Vstmxcsr,
+    Vcmpltsd,
+    Vblendvpd,
+    Vandnpd,
+    Vorpd,
+    Vcmpnltsd,
+    Vldmxcsr,
+    Vfmsub132sd,
+    Vcvttsd2si,
+    Vxorps,
+    Vcvtsi2sd,
+    Vfnmadd231sd,
+    Vfmsub231sd,
+    Vcmpnlesd,
+    Vcmplesd,
+    Mulpd,
+    Cvtpd2ps,
+    Unpckhpd,
+    Vcvtss2sd,
+    Vcvtsd2ss,
+    Vcomiss,
+    Vmovss,
+    Vmulss,
+    Vucomiss,
+    Vsubss,
+    Vdivss,
+    Vmovddup,
+    Vunpcklpd,
+    Vmovapd,
+    Vmulpd,
+    Vfmadd213pd,
+    Vfmadd132pd,
+    Vcvtpd2ps,
+    Vextractps,
+    Vpermilpd,
+    Vfnmaddsd,
+    Vfmsubsd,
+    Movzbw,
+    Vperm2f128,
+    Vpsrld,
+    Vpackusdw,
+    Vpalignr,
+    Vpunpckhbw,
+    Vpunpcklbw,
+    Vpermq,
+    Vbroadcastf128,
+    Decw,
+    Cmovno,
+    Xgetbv,
+    Incw,
+    Vmulps,
+    Vshufps,
+    Vpinsrd,
+    Vpunpcklqdq,
+    Vpmulld,
+    Vpaddd,
+    Vpsrldq,
+    Bad,
+    Byte,
+    Word,
+    Long,
     Unknown(String),
 }
 
@@ -1071,34 +1366,1297 @@ impl From<&str> for X8664ATTOpcode {
             "fptan" => X8664ATTOpcode::Fptan,
             "fcos" => X8664ATTOpcode::Fcos,
             "fucompp" => X8664ATTOpcode::Fucompp,
+            "repz" => X8664ATTOpcode::Repz,
+            "repnz" => X8664ATTOpcode::Repnz,
+            "notrack" => X8664ATTOpcode::Notrack,
+            "bnd" => X8664ATTOpcode::Bnd,
+            "call" => X8664ATTOpcode::Call,
+            "ret" => X8664ATTOpcode::Ret,
+            "jecxz" => X8664ATTOpcode::Jecxz,
+            "push" => X8664ATTOpcode::Push,
+            "xchg" => X8664ATTOpcode::Xchg,
+            "mov" => X8664ATTOpcode::Mov,
+            "lea" => X8664ATTOpcode::Lea,
+            "sub" => X8664ATTOpcode::Sub,
+            "xor" => X8664ATTOpcode::Xor,
+            "cmp" => X8664ATTOpcode::Cmp,
+            "cmpxchg" => X8664ATTOpcode::Cmpxchg,
+            "dec" => X8664ATTOpcode::Dec,
+            "stos" => X8664ATTOpcode::Stos,
+            "test" => X8664ATTOpcode::Test,
+            "pop" => X8664ATTOpcode::Pop,
+            "rol" => X8664ATTOpcode::Rol,
+            "and" => X8664ATTOpcode::And,
+            "shr" => X8664ATTOpcode::Shr,
+            "cmove" => X8664ATTOpcode::Cmove,
+            "ror" => X8664ATTOpcode::Ror,
+            "neg" => X8664ATTOpcode::Neg,
+            "sbb" => X8664ATTOpcode::Sbb,
+            "shl" => X8664ATTOpcode::Shl,
+            "imul" => X8664ATTOpcode::Imul,
+            "or" => X8664ATTOpcode::Or,
+            "cmovne" => X8664ATTOpcode::Cmovne,
+            "cmovle" => X8664ATTOpcode::Cmovle,
+            "cmovg" => X8664ATTOpcode::Cmovg,
+            "cmovl" => X8664ATTOpcode::Cmovl,
+            "cmovns" => X8664ATTOpcode::Cmovns,
+            "cmovs" => X8664ATTOpcode::Cmovs,
+            "sar" => X8664ATTOpcode::Sar,
+            "bswap" => X8664ATTOpcode::Bswap,
+            "div" => X8664ATTOpcode::Div,
+            "cmovae" => X8664ATTOpcode::Cmovae,
+            "movabs" => X8664ATTOpcode::Movabs,
+            "bt" => X8664ATTOpcode::Bt,
+            "shufpd" => X8664ATTOpcode::Shufpd,
+            "not" => X8664ATTOpcode::Not,
+            "adc" => X8664ATTOpcode::Adc,
+            "cmovb" => X8664ATTOpcode::Cmovb,
+            "inc" => X8664ATTOpcode::Inc,
+            "fabs" => X8664ATTOpcode::Fabs,
+            "fchs" => X8664ATTOpcode::Fchs,
+            "fld1" => X8664ATTOpcode::Fld1,
+            "fscale" => X8664ATTOpcode::Fscale,
+            "fucomip" => X8664ATTOpcode::Fucomip,
+            "fldz" => X8664ATTOpcode::Fldz,
+            "btr" => X8664ATTOpcode::Btr,
+            "movapd" => X8664ATTOpcode::Movapd,
+            "andpd" => X8664ATTOpcode::Andpd,
+            "andnpd" => X8664ATTOpcode::Andnpd,
+            "orpd" => X8664ATTOpcode::Orpd,
+            "mulsd" => X8664ATTOpcode::Mulsd,
+            "subsd" => X8664ATTOpcode::Subsd,
+            "addsd" => X8664ATTOpcode::Addsd,
+            "ucomisd" => X8664ATTOpcode::Ucomisd,
+            "setp" => X8664ATTOpcode::Setp,
+            "andps" => X8664ATTOpcode::Andps,
+            "andnps" => X8664ATTOpcode::Andnps,
+            "subss" => X8664ATTOpcode::Subss,
+            "mulss" => X8664ATTOpcode::Mulss,
+            "addss" => X8664ATTOpcode::Addss,
+            "bsr" => X8664ATTOpcode::Bsr,
+            "idiv" => X8664ATTOpcode::Idiv,
+            "stmxcsr" => X8664ATTOpcode::Stmxcsr,
+            "cmovge" => X8664ATTOpcode::Cmovge,
+            "cmovbe" => X8664ATTOpcode::Cmovbe,
+            "ldmxcsr" => X8664ATTOpcode::Ldmxcsr,
+            "comisd" => X8664ATTOpcode::Comisd,
+            "xorpd" => X8664ATTOpcode::Xorpd,
+            "fcomip" => X8664ATTOpcode::Fcomip,
+            "cvtss2sd" => X8664ATTOpcode::Cvtss2sd,
+            "mul" => X8664ATTOpcode::Mul,
+            "setl" => X8664ATTOpcode::Setl,
+            "shld" => X8664ATTOpcode::Shld,
+            "shrd" => X8664ATTOpcode::Shrd,
+            "bts" => X8664ATTOpcode::Bts,
+            "movmskpd" => X8664ATTOpcode::Movmskpd,
+            "tzcnt" => X8664ATTOpcode::Tzcnt,
+            "fcomi" => X8664ATTOpcode::Fcomi,
+            "cvtsi2sd" => X8664ATTOpcode::Cvtsi2sd,
+            "divsd" => X8664ATTOpcode::Divsd,
+            "cmova" => X8664ATTOpcode::Cmova,
+            "movhlps" => X8664ATTOpcode::Movhlps,
+            "xbegin" => X8664ATTOpcode::Xbegin,
+            "xend" => X8664ATTOpcode::Xend,
+            "xadd" => X8664ATTOpcode::Xadd,
+            "bsf" => X8664ATTOpcode::Bsf,
+            "movntdq" => X8664ATTOpcode::Movntdq,
+            "sfence" => X8664ATTOpcode::Sfence,
+            "pminub" => X8664ATTOpcode::Pminub,
+            "movlpd" => X8664ATTOpcode::Movlpd,
+            "movhpd" => X8664ATTOpcode::Movhpd,
+            "psubb" => X8664ATTOpcode::Psubb,
+            "pslldq" => X8664ATTOpcode::Pslldq,
+            "psrldq" => X8664ATTOpcode::Psrldq,
+            "bzhi" => X8664ATTOpcode::Bzhi,
+            "kmovq" => X8664ATTOpcode::Kmovq,
+            "vmovdqu8" => X8664ATTOpcode::Vmovdqu8,
+            "vptestnmb" => X8664ATTOpcode::Vptestnmb,
+            "blsmsk" => X8664ATTOpcode::Blsmsk,
+            "vpbroadcastb" => X8664ATTOpcode::Vpbroadcastb,
+            "vpcmpneqb" => X8664ATTOpcode::Vpcmpneqb,
+            "kortestq" => X8664ATTOpcode::Kortestq,
+            "vzeroupper" => X8664ATTOpcode::Vzeroupper,
+            "blsr" => X8664ATTOpcode::Blsr,
+            "vmovdqa64" => X8664ATTOpcode::Vmovdqa64,
+            "vmovdqu64" => X8664ATTOpcode::Vmovdqu64,
+            "kxnorq" => X8664ATTOpcode::Kxnorq,
+            "rdpkru" => X8664ATTOpcode::Rdpkru,
+            "wrpkru" => X8664ATTOpcode::Wrpkru,
+            "cvtsi2ss" => X8664ATTOpcode::Cvtsi2ss,
+            "divss" => X8664ATTOpcode::Divss,
+            "cvttss2si" => X8664ATTOpcode::Cvttss2si,
+            "psrlw" => X8664ATTOpcode::Psrlw,
+            "vmovd" => X8664ATTOpcode::Vmovd,
+            "vpmovmskb" => X8664ATTOpcode::Vpmovmskb,
+            "vpor" => X8664ATTOpcode::Vpor,
+            "sarx" => X8664ATTOpcode::Sarx,
+            "vmovdqu" => X8664ATTOpcode::Vmovdqu,
+            "vpand" => X8664ATTOpcode::Vpand,
+            "movbe" => X8664ATTOpcode::Movbe,
+            "vmovdqa" => X8664ATTOpcode::Vmovdqa,
+            "vmovntdq" => X8664ATTOpcode::Vmovntdq,
+            "lzcnt" => X8664ATTOpcode::Lzcnt,
+            "shlx" => X8664ATTOpcode::Shlx,
+            "vpbroadcastd" => X8664ATTOpcode::Vpbroadcastd,
+            "vmovq" => X8664ATTOpcode::Vmovq,
+            "vpxor" => X8664ATTOpcode::Vpxor,
+            "vpminub" => X8664ATTOpcode::Vpminub,
+            "vpaddb" => X8664ATTOpcode::Vpaddb,
+            "vpcmpgtb" => X8664ATTOpcode::Vpcmpgtb,
+            "vmovaps" => X8664ATTOpcode::Vmovaps,
+            "shrx" => X8664ATTOpcode::Shrx,
+            "vpcmpeqd" => X8664ATTOpcode::Vpcmpeqd,
+            "vpminud" => X8664ATTOpcode::Vpminud,
+            "xtest" => X8664ATTOpcode::Xtest,
+            "vzeroall" => X8664ATTOpcode::Vzeroall,
+            "kmovd" => X8664ATTOpcode::Kmovd,
+            "vpternlogd" => X8664ATTOpcode::Vpternlogd,
+            "vpcmpnequb" => X8664ATTOpcode::Vpcmpnequb,
+            "vpxorq" => X8664ATTOpcode::Vpxorq,
+            "vptestmb" => X8664ATTOpcode::Vptestmb,
+            "kortestd" => X8664ATTOpcode::Kortestd,
+            "vpsubb" => X8664ATTOpcode::Vpsubb,
+            "vpcmpltub" => X8664ATTOpcode::Vpcmpltub,
+            "ktestd" => X8664ATTOpcode::Ktestd,
+            "kord" => X8664ATTOpcode::Kord,
+            "kunpckdq" => X8664ATTOpcode::Kunpckdq,
+            "vpxord" => X8664ATTOpcode::Vpxord,
+            "vptestnmd" => X8664ATTOpcode::Vptestnmd,
+            "vpcmpneqd" => X8664ATTOpcode::Vpcmpneqd,
+            "vptestmd" => X8664ATTOpcode::Vptestmd,
+            "kunpckbw" => X8664ATTOpcode::Kunpckbw,
+            "vmovdqu32" => X8664ATTOpcode::Vmovdqu32,
+            "vmovups" => X8664ATTOpcode::Vmovups,
+            "vbroadcastss" => X8664ATTOpcode::Vbroadcastss,
+            "palignr" => X8664ATTOpcode::Palignr,
+            "pcmpistri" => X8664ATTOpcode::Pcmpistri,
+            "pshufb" => X8664ATTOpcode::Pshufb,
+            "pminud" => X8664ATTOpcode::Pminud,
+            "endbr64" => X8664ATTOpcode::Endbr64,
+            "fwait" => X8664ATTOpcode::Fwait,
+            "fnclex" => X8664ATTOpcode::Fnclex,
+            "fstcw" => X8664ATTOpcode::Fstcw,
+            "fyl2xp1" => X8664ATTOpcode::Fyl2xp1,
+            "f2xm1" => X8664ATTOpcode::F2xm1,
+            "btc" => X8664ATTOpcode::Btc,
+            "setnp" => X8664ATTOpcode::Setnp,
+            "fpatan" => X8664ATTOpcode::Fpatan,
+            "unpcklpd" => X8664ATTOpcode::Unpcklpd,
+            "cvttsd2si" => X8664ATTOpcode::Cvttsd2si,
+            "cmpnlesd" => X8664ATTOpcode::Cmpnlesd,
+            "sets" => X8664ATTOpcode::Sets,
+            "cmpnless" => X8664ATTOpcode::Cmpnless,
+            "cvtsd2ss" => X8664ATTOpcode::Cvtsd2ss,
+            "fsqrt" => X8664ATTOpcode::Fsqrt,
+            "fstsw" => X8664ATTOpcode::Fstsw,
+            "fldl2e" => X8664ATTOpcode::Fldl2e,
+            "fldln2" => X8664ATTOpcode::Fldln2,
+            "fyl2x" => X8664ATTOpcode::Fyl2x,
+            "fprem1" => X8664ATTOpcode::Fprem1,
+            "fcmove" => X8664ATTOpcode::Fcmove,
+            "fldl2t" => X8664ATTOpcode::Fldl2t,
+            "sqrtsd" => X8664ATTOpcode::Sqrtsd,
+            "cmpltsd" => X8664ATTOpcode::Cmpltsd,
+            "cmplesd" => X8664ATTOpcode::Cmplesd,
+            "vfmadd213sd" => X8664ATTOpcode::Vfmadd213sd,
+            "vfmaddsd" => X8664ATTOpcode::Vfmaddsd,
+            "cvtsd2si" => X8664ATTOpcode::Cvtsd2si,
+            "movupd" => X8664ATTOpcode::Movupd,
+            "addpd" => X8664ATTOpcode::Addpd,
+            "maxsd" => X8664ATTOpcode::Maxsd,
+            "minsd" => X8664ATTOpcode::Minsd,
+            "cmovnp" => X8664ATTOpcode::Cmovnp,
+            "sqrtss" => X8664ATTOpcode::Sqrtss,
+            "cvtsi2ssl" => X8664ATTOpcode::Cvtsi2ssl,
+            "vfmadd213ss" => X8664ATTOpcode::Vfmadd213ss,
+            "vfmaddss" => X8664ATTOpcode::Vfmaddss,
+            "cvtss2si" => X8664ATTOpcode::Cvtss2si,
+            "maxss" => X8664ATTOpcode::Maxss,
+            "minss" => X8664ATTOpcode::Minss,
+            "cmpless" => X8664ATTOpcode::Cmpless,
+            "cvtdq2pd" => X8664ATTOpcode::Cvtdq2pd,
+            "cvtsi2sdl" => X8664ATTOpcode::Cvtsi2sdl,
+            "cvttpd2dq" => X8664ATTOpcode::Cvttpd2dq,
+            "roundsd" => X8664ATTOpcode::Roundsd,
+            "roundss" => X8664ATTOpcode::Roundss,
+            "vaddsd" => X8664ATTOpcode::Vaddsd,
+            "vmulsd" => X8664ATTOpcode::Vmulsd,
+            "vmovsd" => X8664ATTOpcode::Vmovsd,
+            "vfmadd231sd" => X8664ATTOpcode::Vfmadd231sd,
+            "vandpd" => X8664ATTOpcode::Vandpd,
+            "vcomisd" => X8664ATTOpcode::Vcomisd,
+            "vxorpd" => X8664ATTOpcode::Vxorpd,
+            "vsubsd" => X8664ATTOpcode::Vsubsd,
+            "vfmadd132sd" => X8664ATTOpcode::Vfmadd132sd,
+            "vfnmadd132sd" => X8664ATTOpcode::Vfnmadd132sd,
+            "vfnmadd213sd" => X8664ATTOpcode::Vfnmadd213sd,
+            "vdivsd" => X8664ATTOpcode::Vdivsd,
+            "vstmxcsr" => X8664ATTOpcode::Vstmxcsr,
+            "vcmpltsd" => X8664ATTOpcode::Vcmpltsd,
+            "vblendvpd" => X8664ATTOpcode::Vblendvpd,
+            "vandnpd" => X8664ATTOpcode::Vandnpd,
+            "vorpd" => X8664ATTOpcode::Vorpd,
+            "vcmpnltsd" => X8664ATTOpcode::Vcmpnltsd,
+            "vldmxcsr" => X8664ATTOpcode::Vldmxcsr,
+            "vfmsub132sd" => X8664ATTOpcode::Vfmsub132sd,
+            "vcvttsd2si" => X8664ATTOpcode::Vcvttsd2si,
+            "vxorps" => X8664ATTOpcode::Vxorps,
+            "vcvtsi2sd" => X8664ATTOpcode::Vcvtsi2sd,
+            "vfnmadd231sd" => X8664ATTOpcode::Vfnmadd231sd,
+            "vfmsub231sd" => X8664ATTOpcode::Vfmsub231sd,
+            "vcmpnlesd" => X8664ATTOpcode::Vcmpnlesd,
+            "vcmplesd" => X8664ATTOpcode::Vcmplesd,
+            "mulpd" => X8664ATTOpcode::Mulpd,
+            "cvtpd2ps" => X8664ATTOpcode::Cvtpd2ps,
+            "unpckhpd" => X8664ATTOpcode::Unpckhpd,
+            "vcvtss2sd" => X8664ATTOpcode::Vcvtss2sd,
+            "vcvtsd2ss" => X8664ATTOpcode::Vcvtsd2ss,
+            "vcomiss" => X8664ATTOpcode::Vcomiss,
+            "vmovss" => X8664ATTOpcode::Vmovss,
+            "vmulss" => X8664ATTOpcode::Vmulss,
+            "vucomiss" => X8664ATTOpcode::Vucomiss,
+            "vsubss" => X8664ATTOpcode::Vsubss,
+            "vdivss" => X8664ATTOpcode::Vdivss,
+            "vmovddup" => X8664ATTOpcode::Vmovddup,
+            "vunpcklpd" => X8664ATTOpcode::Vunpcklpd,
+            "vmovapd" => X8664ATTOpcode::Vmovapd,
+            "vmulpd" => X8664ATTOpcode::Vmulpd,
+            "vfmadd213pd" => X8664ATTOpcode::Vfmadd213pd,
+            "vfmadd132pd" => X8664ATTOpcode::Vfmadd132pd,
+            "vcvtpd2ps" => X8664ATTOpcode::Vcvtpd2ps,
+            "vextractps" => X8664ATTOpcode::Vextractps,
+            "vpermilpd" => X8664ATTOpcode::Vpermilpd,
+            "vfnmaddsd" => X8664ATTOpcode::Vfnmaddsd,
+            "vfmsubsd" => X8664ATTOpcode::Vfmsubsd,
+            "movzbw" => X8664ATTOpcode::Movzbw,
+            "vperm2f128" => X8664ATTOpcode::Vperm2f128,
+            "vpsrld" => X8664ATTOpcode::Vpsrld,
+            "vpackusdw" => X8664ATTOpcode::Vpackusdw,
+            "vpalignr" => X8664ATTOpcode::Vpalignr,
+            "vpunpckhbw" => X8664ATTOpcode::Vpunpckhbw,
+            "vpunpcklbw" => X8664ATTOpcode::Vpunpcklbw,
+            "vpermq" => X8664ATTOpcode::Vpermq,
+            "vbroadcastf128" => X8664ATTOpcode::Vbroadcastf128,
+            "decw" => X8664ATTOpcode::Decw,
+            "cmovno" => X8664ATTOpcode::Cmovno,
+            "xgetbv" => X8664ATTOpcode::Xgetbv,
+            "incw" => X8664ATTOpcode::Incw,
+            "vmulps" => X8664ATTOpcode::Vmulps,
+            "vshufps" => X8664ATTOpcode::Vshufps,
+            "vpinsrd" => X8664ATTOpcode::Vpinsrd,
+            "vpunpcklqdq" => X8664ATTOpcode::Vpunpcklqdq,
+            "vpmulld" => X8664ATTOpcode::Vpmulld,
+            "vpaddd" => X8664ATTOpcode::Vpaddd,
+            "vpsrldq" => X8664ATTOpcode::Vpsrldq,
+            "(bad)" => X8664ATTOpcode::Bad,
+            ".byte" => X8664ATTOpcode::Byte,
+            ".word" => X8664ATTOpcode::Word,
+            ".long" => X8664ATTOpcode::Long,
             _ => X8664ATTOpcode::Unknown(opcode.to_string()),
         }
     }
 }
+impl std::fmt::Display for X8664ATTOpcode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.mnemonic())
+    }
+}
+
+impl Serialize for X8664ATTOpcode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.mnemonic())
+    }
+}
+
+impl X8664ATTOpcode {
+    pub fn mnemonic(&self) -> &str {
+        match self {
+            X8664ATTOpcode::Add => "add",
+            X8664ATTOpcode::Insb => "insb",
+            X8664ATTOpcode::Imull => "imull",
+            X8664ATTOpcode::Subl => "subl",
+            X8664ATTOpcode::Js => "js",
+            X8664ATTOpcode::Outsl => "outsl",
+            X8664ATTOpcode::Xorb => "xorb",
+            X8664ATTOpcode::Addb => "addb",
+            X8664ATTOpcode::Adcb => "adcb",
+            X8664ATTOpcode::Addl => "addl",
+            X8664ATTOpcode::Pushq => "pushq",
+            X8664ATTOpcode::Andl => "andl",
+            X8664ATTOpcode::Cmpb => "cmpb",
+            X8664ATTOpcode::Orl => "orl",
+            X8664ATTOpcode::Cmpl => "cmpl",
+            X8664ATTOpcode::Sbbb => "sbbb",
+            X8664ATTOpcode::Orb => "orb",
+            X8664ATTOpcode::Adcl => "adcl",
+            X8664ATTOpcode::Lock => "lock",
+            X8664ATTOpcode::Movb => "movb",
+            X8664ATTOpcode::Rolb => "rolb",
+            X8664ATTOpcode::Xchgl => "xchgl",
+            X8664ATTOpcode::Jne => "jne",
+            X8664ATTOpcode::Andb => "andb",
+            X8664ATTOpcode::Outl => "outl",
+            X8664ATTOpcode::Loopne => "loopne",
+            X8664ATTOpcode::Addq => "addq",
+            X8664ATTOpcode::Movabsl => "movabsl",
+            X8664ATTOpcode::Lodsl => "lodsl",
+            X8664ATTOpcode::Lretl => "lretl",
+            X8664ATTOpcode::Subb => "subb",
+            X8664ATTOpcode::Popq => "popq",
+            X8664ATTOpcode::Movslq => "movslq",
+            X8664ATTOpcode::Jae => "jae",
+            X8664ATTOpcode::Xorl => "xorl",
+            X8664ATTOpcode::Jb => "jb",
+            X8664ATTOpcode::Outsb => "outsb",
+            X8664ATTOpcode::Addr32 => "addr32",
+            X8664ATTOpcode::Jo => "jo",
+            X8664ATTOpcode::Je => "je",
+            X8664ATTOpcode::Insl => "insl",
+            X8664ATTOpcode::Ja => "ja",
+            X8664ATTOpcode::Gs => "gs",
+            X8664ATTOpcode::Outsw => "outsw",
+            X8664ATTOpcode::Jns => "jns",
+            X8664ATTOpcode::Jbe => "jbe",
+            X8664ATTOpcode::Imulw => "imulw",
+            X8664ATTOpcode::Jp => "jp",
+            X8664ATTOpcode::Movsl => "movsl",
+            X8664ATTOpcode::Scasl => "scasl",
+            X8664ATTOpcode::Int => "int",
+            X8664ATTOpcode::Movl => "movl",
+            X8664ATTOpcode::Jl => "jl",
+            X8664ATTOpcode::Clc => "clc",
+            X8664ATTOpcode::Xlatb => "xlatb",
+            X8664ATTOpcode::Outb => "outb",
+            X8664ATTOpcode::Jge => "jge",
+            X8664ATTOpcode::Nop => "nop",
+            X8664ATTOpcode::Testb => "testb",
+            X8664ATTOpcode::Shlb => "shlb",
+            X8664ATTOpcode::Fsubs => "fsubs",
+            X8664ATTOpcode::Movabsb => "movabsb",
+            X8664ATTOpcode::Shrb => "shrb",
+            X8664ATTOpcode::Callq => "callq",
+            X8664ATTOpcode::Cwtl => "cwtl",
+            X8664ATTOpcode::Fsubrs => "fsubrs",
+            X8664ATTOpcode::Rep => "rep",
+            X8664ATTOpcode::Hlt => "hlt",
+            X8664ATTOpcode::Incl => "incl",
+            X8664ATTOpcode::Jnp => "jnp",
+            X8664ATTOpcode::Xorq => "xorq",
+            X8664ATTOpcode::Fdivs => "fdivs",
+            X8664ATTOpcode::Sbbl => "sbbl",
+            X8664ATTOpcode::Rcrb => "rcrb",
+            X8664ATTOpcode::Rorb => "rorb",
+            X8664ATTOpcode::Loope => "loope",
+            X8664ATTOpcode::Stosl => "stosl",
+            X8664ATTOpcode::Testl => "testl",
+            X8664ATTOpcode::Enter => "enter",
+            X8664ATTOpcode::Sarb => "sarb",
+            X8664ATTOpcode::Jno => "jno",
+            X8664ATTOpcode::Fnsave => "fnsave",
+            X8664ATTOpcode::Retq => "retq",
+            X8664ATTOpcode::Cmpq => "cmpq",
+            X8664ATTOpcode::Inb => "inb",
+            X8664ATTOpcode::Ds => "ds",
+            X8664ATTOpcode::Fdivrs => "fdivrs",
+            X8664ATTOpcode::Xchgb => "xchgb",
+            X8664ATTOpcode::Fadds => "fadds",
+            X8664ATTOpcode::Fimull => "fimull",
+            X8664ATTOpcode::Movw => "movw",
+            X8664ATTOpcode::Lodsb => "lodsb",
+            X8664ATTOpcode::Leal => "leal",
+            X8664ATTOpcode::Andq => "andq",
+            X8664ATTOpcode::Fmuls => "fmuls",
+            X8664ATTOpcode::Jmp => "jmp",
+            X8664ATTOpcode::Fiadds => "fiadds",
+            X8664ATTOpcode::Cli => "cli",
+            X8664ATTOpcode::Cmc => "cmc",
+            X8664ATTOpcode::Stc => "stc",
+            X8664ATTOpcode::Incb => "incb",
+            X8664ATTOpcode::Std => "std",
+            X8664ATTOpcode::Repne => "repne",
+            X8664ATTOpcode::Sti => "sti",
+            X8664ATTOpcode::Fldl => "fldl",
+            X8664ATTOpcode::Popfq => "popfq",
+            X8664ATTOpcode::Subq => "subq",
+            X8664ATTOpcode::Movq => "movq",
+            X8664ATTOpcode::Testq => "testq",
+            X8664ATTOpcode::Jmpq => "jmpq",
+            X8664ATTOpcode::Nopl => "nopl",
+            X8664ATTOpcode::Testw => "testw",
+            X8664ATTOpcode::Incq => "incq",
+            X8664ATTOpcode::Rolw => "rolw",
+            X8664ATTOpcode::Andw => "andw",
+            X8664ATTOpcode::Tzcntl => "tzcntl",
+            X8664ATTOpcode::Leaq => "leaq",
+            X8664ATTOpcode::Nopw => "nopw",
+            X8664ATTOpcode::Movzbl => "movzbl",
+            X8664ATTOpcode::Bsrq => "bsrq",
+            X8664ATTOpcode::Notl => "notl",
+            X8664ATTOpcode::Shrq => "shrq",
+            X8664ATTOpcode::Shlq => "shlq",
+            X8664ATTOpcode::Cmovaeq => "cmovaeq",
+            X8664ATTOpcode::Movups => "movups",
+            X8664ATTOpcode::Movaps => "movaps",
+            X8664ATTOpcode::Orq => "orq",
+            X8664ATTOpcode::Setb => "setb",
+            X8664ATTOpcode::Sete => "sete",
+            X8664ATTOpcode::Decq => "decq",
+            X8664ATTOpcode::Adcq => "adcq",
+            X8664ATTOpcode::Cmovaq => "cmovaq",
+            X8664ATTOpcode::Movabsq => "movabsq",
+            X8664ATTOpcode::Shll => "shll",
+            X8664ATTOpcode::Notq => "notq",
+            X8664ATTOpcode::Imulq => "imulq",
+            X8664ATTOpcode::Xorps => "xorps",
+            X8664ATTOpcode::Pause => "pause",
+            X8664ATTOpcode::Cmpxchgl => "cmpxchgl",
+            X8664ATTOpcode::Cmovneq => "cmovneq",
+            X8664ATTOpcode::Ud2 => "ud2",
+            X8664ATTOpcode::Decl => "decl",
+            X8664ATTOpcode::Jle => "jle",
+            X8664ATTOpcode::Sarq => "sarq",
+            X8664ATTOpcode::Cqto => "cqto",
+            X8664ATTOpcode::Idivq => "idivq",
+            X8664ATTOpcode::Shrl => "shrl",
+            X8664ATTOpcode::Setne => "setne",
+            X8664ATTOpcode::Jg => "jg",
+            X8664ATTOpcode::Cmovbq => "cmovbq",
+            X8664ATTOpcode::Cmoveq => "cmoveq",
+            X8664ATTOpcode::Negq => "negq",
+            X8664ATTOpcode::Movzwl => "movzwl",
+            X8664ATTOpcode::Sbbq => "sbbq",
+            X8664ATTOpcode::Mulq => "mulq",
+            X8664ATTOpcode::Setae => "setae",
+            X8664ATTOpcode::Movd => "movd",
+            X8664ATTOpcode::Punpcklbw => "punpcklbw",
+            X8664ATTOpcode::Pshuflw => "pshuflw",
+            X8664ATTOpcode::Pshufd => "pshufd",
+            X8664ATTOpcode::Movdqa => "movdqa",
+            X8664ATTOpcode::Movdqu => "movdqu",
+            X8664ATTOpcode::Pcmpeqb => "pcmpeqb",
+            X8664ATTOpcode::Pand => "pand",
+            X8664ATTOpcode::Pmovmskb => "pmovmskb",
+            X8664ATTOpcode::Btq => "btq",
+            X8664ATTOpcode::Cmpw => "cmpw",
+            X8664ATTOpcode::Cmovgeq => "cmovgeq",
+            X8664ATTOpcode::Cmovel => "cmovel",
+            X8664ATTOpcode::Cmoval => "cmoval",
+            X8664ATTOpcode::Pinsrw => "pinsrw",
+            X8664ATTOpcode::Pxor => "pxor",
+            X8664ATTOpcode::Movss => "movss",
+            X8664ATTOpcode::Movsd => "movsd",
+            X8664ATTOpcode::Btl => "btl",
+            X8664ATTOpcode::Divb => "divb",
+            X8664ATTOpcode::Negb => "negb",
+            X8664ATTOpcode::Cmovbl => "cmovbl",
+            X8664ATTOpcode::Cmovbeq => "cmovbeq",
+            X8664ATTOpcode::Setbe => "setbe",
+            X8664ATTOpcode::Movsbq => "movsbq",
+            X8664ATTOpcode::Notb => "notb",
+            X8664ATTOpcode::Divq => "divq",
+            X8664ATTOpcode::Divl => "divl",
+            X8664ATTOpcode::Pextrw => "pextrw",
+            X8664ATTOpcode::Psllw => "psllw",
+            X8664ATTOpcode::Por => "por",
+            X8664ATTOpcode::Punpcklwd => "punpcklwd",
+            X8664ATTOpcode::Punpckldq => "punpckldq",
+            X8664ATTOpcode::Cmpxchgq => "cmpxchgq",
+            X8664ATTOpcode::Cmovsq => "cmovsq",
+            X8664ATTOpcode::Cmovnel => "cmovnel",
+            X8664ATTOpcode::Cmovael => "cmovael",
+            X8664ATTOpcode::Xaddl => "xaddl",
+            X8664ATTOpcode::Movswq => "movswq",
+            X8664ATTOpcode::Setg => "setg",
+            X8664ATTOpcode::Movlps => "movlps",
+            X8664ATTOpcode::Punpcklqdq => "punpcklqdq",
+            X8664ATTOpcode::Movlhps => "movlhps",
+            X8664ATTOpcode::Decb => "decb",
+            X8664ATTOpcode::Bswapl => "bswapl",
+            X8664ATTOpcode::Orw => "orw",
+            X8664ATTOpcode::Seta => "seta",
+            X8664ATTOpcode::Divw => "divw",
+            X8664ATTOpcode::Adcw => "adcw",
+            X8664ATTOpcode::Movswl => "movswl",
+            X8664ATTOpcode::Negl => "negl",
+            X8664ATTOpcode::Xorw => "xorw",
+            X8664ATTOpcode::Paddd => "paddd",
+            X8664ATTOpcode::Pmuludq => "pmuludq",
+            X8664ATTOpcode::Psrld => "psrld",
+            X8664ATTOpcode::Psubd => "psubd",
+            X8664ATTOpcode::Psllq => "psllq",
+            X8664ATTOpcode::Unpcklps => "unpcklps",
+            X8664ATTOpcode::Mull => "mull",
+            X8664ATTOpcode::Seto => "seto",
+            X8664ATTOpcode::Bsrl => "bsrl",
+            X8664ATTOpcode::Paddq => "paddq",
+            X8664ATTOpcode::Pandn => "pandn",
+            X8664ATTOpcode::Pcmpgtb => "pcmpgtb",
+            X8664ATTOpcode::Setge => "setge",
+            X8664ATTOpcode::Pcmpeqd => "pcmpeqd",
+            X8664ATTOpcode::Psrlq => "psrlq",
+            X8664ATTOpcode::Punpckhqdq => "punpckhqdq",
+            X8664ATTOpcode::Btsq => "btsq",
+            X8664ATTOpcode::Pcmpgtd => "pcmpgtd",
+            X8664ATTOpcode::Movmskps => "movmskps",
+            X8664ATTOpcode::Cmovsl => "cmovsl",
+            X8664ATTOpcode::Shrdq => "shrdq",
+            X8664ATTOpcode::Shldq => "shldq",
+            X8664ATTOpcode::Insw => "insw",
+            X8664ATTOpcode::Cld => "cld",
+            X8664ATTOpcode::Ljmpl => "ljmpl",
+            X8664ATTOpcode::Lcalll => "lcalll",
+            X8664ATTOpcode::Sahf => "sahf",
+            X8664ATTOpcode::Lahf => "lahf",
+            X8664ATTOpcode::Scasb => "scasb",
+            X8664ATTOpcode::Sarl => "sarl",
+            X8664ATTOpcode::Leave => "leave",
+            X8664ATTOpcode::Fdivr => "fdivr",
+            X8664ATTOpcode::Inl => "inl",
+            X8664ATTOpcode::Rorl => "rorl",
+            X8664ATTOpcode::Cmpsl => "cmpsl",
+            X8664ATTOpcode::Vpaddw => "vpaddw",
+            X8664ATTOpcode::Roll => "roll",
+            X8664ATTOpcode::Cmpsb => "cmpsb",
+            X8664ATTOpcode::Fs => "fs",
+            X8664ATTOpcode::Filds => "filds",
+            X8664ATTOpcode::Pushw => "pushw",
+            X8664ATTOpcode::Fdivrp => "fdivrp",
+            X8664ATTOpcode::Ficomps => "ficomps",
+            X8664ATTOpcode::Es => "es",
+            X8664ATTOpcode::Fbld => "fbld",
+            X8664ATTOpcode::Movsb => "movsb",
+            X8664ATTOpcode::Pushfq => "pushfq",
+            X8664ATTOpcode::Int3 => "int3",
+            X8664ATTOpcode::Faddl => "faddl",
+            X8664ATTOpcode::Stosb => "stosb",
+            X8664ATTOpcode::Cltd => "cltd",
+            X8664ATTOpcode::Wait => "wait",
+            X8664ATTOpcode::Rex64 => "rex64",
+            X8664ATTOpcode::Loop => "loop",
+            X8664ATTOpcode::Fiaddl => "fiaddl",
+            X8664ATTOpcode::Data16 => "data16",
+            X8664ATTOpcode::Ss => "ss",
+            X8664ATTOpcode::Cs => "cs",
+            X8664ATTOpcode::Flds => "flds",
+            X8664ATTOpcode::Fildl => "fildl",
+            X8664ATTOpcode::Iretl => "iretl",
+            X8664ATTOpcode::Rclb => "rclb",
+            X8664ATTOpcode::Fcomp => "fcomp",
+            X8664ATTOpcode::Unpckhps => "unpckhps",
+            X8664ATTOpcode::Vpandn => "vpandn",
+            X8664ATTOpcode::Fcmovu => "fcmovu",
+            X8664ATTOpcode::Cltq => "cltq",
+            X8664ATTOpcode::Fisttps => "fisttps",
+            X8664ATTOpcode::Fdiv => "fdiv",
+            X8664ATTOpcode::Fistps => "fistps",
+            X8664ATTOpcode::Lsll => "lsll",
+            X8664ATTOpcode::Sldtw => "sldtw",
+            X8664ATTOpcode::Fsincos => "fsincos",
+            X8664ATTOpcode::Jrcxz => "jrcxz",
+            X8664ATTOpcode::Idivl => "idivl",
+            X8664ATTOpcode::Fdivl => "fdivl",
+            X8664ATTOpcode::Fcomps => "fcomps",
+            X8664ATTOpcode::Idivb => "idivb",
+            X8664ATTOpcode::Fisttpl => "fisttpl",
+            X8664ATTOpcode::Fmull => "fmull",
+            X8664ATTOpcode::Fsubl => "fsubl",
+            X8664ATTOpcode::Fcoml => "fcoml",
+            X8664ATTOpcode::Fbstp => "fbstp",
+            X8664ATTOpcode::Fcoms => "fcoms",
+            X8664ATTOpcode::Fsubr => "fsubr",
+            X8664ATTOpcode::Pmaxsw => "pmaxsw",
+            X8664ATTOpcode::Shufps => "shufps",
+            X8664ATTOpcode::Prefetch => "prefetch",
+            X8664ATTOpcode::Fnstsw => "fnstsw",
+            X8664ATTOpcode::Iretq => "iretq",
+            X8664ATTOpcode::Paddw => "paddw",
+            X8664ATTOpcode::Ljmpq => "ljmpq",
+            X8664ATTOpcode::Ficoml => "ficoml",
+            X8664ATTOpcode::Sgdtq => "sgdtq",
+            X8664ATTOpcode::Packuswb => "packuswb",
+            X8664ATTOpcode::Fisttpll => "fisttpll",
+            X8664ATTOpcode::Addw => "addw",
+            X8664ATTOpcode::Monitorx => "monitorx",
+            X8664ATTOpcode::Fidivl => "fidivl",
+            X8664ATTOpcode::Ficompl => "ficompl",
+            X8664ATTOpcode::Lodsq => "lodsq",
+            X8664ATTOpcode::Getsec => "getsec",
+            X8664ATTOpcode::Pslld => "pslld",
+            X8664ATTOpcode::Fldt => "fldt",
+            X8664ATTOpcode::Imulb => "imulb",
+            X8664ATTOpcode::Rcrl => "rcrl",
+            X8664ATTOpcode::Fstpl => "fstpl",
+            X8664ATTOpcode::Fildll => "fildll",
+            X8664ATTOpcode::Ficoms => "ficoms",
+            X8664ATTOpcode::Fdivrl => "fdivrl",
+            X8664ATTOpcode::Rcll => "rcll",
+            X8664ATTOpcode::Sysretl => "sysretl",
+            X8664ATTOpcode::Fistl => "fistl",
+            X8664ATTOpcode::Fstps => "fstps",
+            X8664ATTOpcode::Fidivrs => "fidivrs",
+            X8664ATTOpcode::Frstor => "frstor",
+            X8664ATTOpcode::Fists => "fists",
+            X8664ATTOpcode::Fimuls => "fimuls",
+            X8664ATTOpcode::Fucomp => "fucomp",
+            X8664ATTOpcode::Xchgq => "xchgq",
+            X8664ATTOpcode::Fldenv => "fldenv",
+            X8664ATTOpcode::Fucom => "fucom",
+            X8664ATTOpcode::Fsubrl => "fsubrl",
+            X8664ATTOpcode::Fsts => "fsts",
+            X8664ATTOpcode::Fmul => "fmul",
+            X8664ATTOpcode::Fmulp => "fmulp",
+            X8664ATTOpcode::Emms => "emms",
+            X8664ATTOpcode::Movsq => "movsq",
+            X8664ATTOpcode::Fst => "fst",
+            X8664ATTOpcode::Fisubrs => "fisubrs",
+            X8664ATTOpcode::Fld => "fld",
+            X8664ATTOpcode::Fadd => "fadd",
+            X8664ATTOpcode::Invd => "invd",
+            X8664ATTOpcode::Ffree => "ffree",
+            X8664ATTOpcode::Lretq => "lretq",
+            X8664ATTOpcode::Scasq => "scasq",
+            X8664ATTOpcode::Scasw => "scasw",
+            X8664ATTOpcode::Xrelease => "xrelease",
+            X8664ATTOpcode::Fldcw => "fldcw",
+            X8664ATTOpcode::Fisubs => "fisubs",
+            X8664ATTOpcode::Clts => "clts",
+            X8664ATTOpcode::Wbinvd => "wbinvd",
+            X8664ATTOpcode::Fcmovbe => "fcmovbe",
+            X8664ATTOpcode::Fcmovb => "fcmovb",
+            X8664ATTOpcode::Punpckhdq => "punpckhdq",
+            X8664ATTOpcode::Fcmovnb => "fcmovnb",
+            X8664ATTOpcode::Fstl => "fstl",
+            X8664ATTOpcode::Outw => "outw",
+            X8664ATTOpcode::Mulb => "mulb",
+            X8664ATTOpcode::Larl => "larl",
+            X8664ATTOpcode::Sbbw => "sbbw",
+            X8664ATTOpcode::Rolq => "rolq",
+            X8664ATTOpcode::Fucompi => "fucompi",
+            X8664ATTOpcode::Cmpsq => "cmpsq",
+            X8664ATTOpcode::Fcompl => "fcompl",
+            X8664ATTOpcode::Lgdtq => "lgdtq",
+            X8664ATTOpcode::Fisubrl => "fisubrl",
+            X8664ATTOpcode::Xchgw => "xchgw",
+            X8664ATTOpcode::Femms => "femms",
+            X8664ATTOpcode::Fnstenv => "fnstenv",
+            X8664ATTOpcode::Rdmsr => "rdmsr",
+            X8664ATTOpcode::Paddusw => "paddusw",
+            X8664ATTOpcode::Frndint => "frndint",
+            X8664ATTOpcode::Stosq => "stosq",
+            X8664ATTOpcode::Verw => "verw",
+            X8664ATTOpcode::Lldtw => "lldtw",
+            X8664ATTOpcode::Cmovbel => "cmovbel",
+            X8664ATTOpcode::Callw => "callw",
+            X8664ATTOpcode::Shrdl => "shrdl",
+            X8664ATTOpcode::Verr => "verr",
+            X8664ATTOpcode::Strw => "strw",
+            X8664ATTOpcode::Ltrw => "ltrw",
+            X8664ATTOpcode::Syscall => "syscall",
+            X8664ATTOpcode::Packssdw => "packssdw",
+            X8664ATTOpcode::Cmovol => "cmovol",
+            X8664ATTOpcode::Bsfl => "bsfl",
+            X8664ATTOpcode::Strl => "strl",
+            X8664ATTOpcode::Setns => "setns",
+            X8664ATTOpcode::Smsww => "smsww",
+            X8664ATTOpcode::Cmovnpl => "cmovnpl",
+            X8664ATTOpcode::Fidivrl => "fidivrl",
+            X8664ATTOpcode::Fistpl => "fistpl",
+            X8664ATTOpcode::Paddsb => "paddsb",
+            X8664ATTOpcode::Strq => "strq",
+            X8664ATTOpcode::Vphaddw => "vphaddw",
+            X8664ATTOpcode::Sldtq => "sldtq",
+            X8664ATTOpcode::Sldtl => "sldtl",
+            X8664ATTOpcode::Fsubp => "fsubp",
+            X8664ATTOpcode::Fucomi => "fucomi",
+            X8664ATTOpcode::Rsqrtps => "rsqrtps",
+            X8664ATTOpcode::Fstp => "fstp",
+            X8664ATTOpcode::Fcmovnu => "fcmovnu",
+            X8664ATTOpcode::Prefetcht0 => "prefetcht0",
+            X8664ATTOpcode::Prefetchnta => "prefetchnta",
+            X8664ATTOpcode::Popw => "popw",
+            X8664ATTOpcode::Lidtq => "lidtq",
+            X8664ATTOpcode::Rorq => "rorq",
+            X8664ATTOpcode::Fistpll => "fistpll",
+            X8664ATTOpcode::Fnstcw => "fnstcw",
+            X8664ATTOpcode::Psubw => "psubw",
+            X8664ATTOpcode::Fcmovne => "fcmovne",
+            X8664ATTOpcode::Fcmovnbe => "fcmovnbe",
+            X8664ATTOpcode::Fidivs => "fidivs",
+            X8664ATTOpcode::Vpshrdvd => "vpshrdvd",
+            X8664ATTOpcode::Fsub => "fsub",
+            X8664ATTOpcode::Leaw => "leaw",
+            X8664ATTOpcode::Fprem => "fprem",
+            X8664ATTOpcode::Psubsw => "psubsw",
+            X8664ATTOpcode::Cmpsw => "cmpsw",
+            X8664ATTOpcode::Stosw => "stosw",
+            X8664ATTOpcode::Fincstp => "fincstp",
+            X8664ATTOpcode::Cmovpl => "cmovpl",
+            X8664ATTOpcode::Cvtps2pi => "cvtps2pi",
+            X8664ATTOpcode::Rdtsc => "rdtsc",
+            X8664ATTOpcode::Ftst => "ftst",
+            X8664ATTOpcode::Faddp => "faddp",
+            X8664ATTOpcode::Shldl => "shldl",
+            X8664ATTOpcode::Fcompp => "fcompp",
+            X8664ATTOpcode::Pmulhuw => "pmulhuw",
+            X8664ATTOpcode::Fstpt => "fstpt",
+            X8664ATTOpcode::Paddb => "paddb",
+            X8664ATTOpcode::Psubq => "psubq",
+            X8664ATTOpcode::Xacquire => "xacquire",
+            X8664ATTOpcode::Cvtdq2ps => "cvtdq2ps",
+            X8664ATTOpcode::Fxam => "fxam",
+            X8664ATTOpcode::Vandps => "vandps",
+            X8664ATTOpcode::Iretw => "iretw",
+            X8664ATTOpcode::Fcom => "fcom",
+            X8664ATTOpcode::Psubusw => "psubusw",
+            X8664ATTOpcode::Pminsw => "pminsw",
+            X8664ATTOpcode::Lgsl => "lgsl",
+            X8664ATTOpcode::Fisubl => "fisubl",
+            X8664ATTOpcode::Vroundsd => "vroundsd",
+            X8664ATTOpcode::Fdivp => "fdivp",
+            X8664ATTOpcode::Vpshufb => "vpshufb",
+            X8664ATTOpcode::Movntq => "movntq",
+            X8664ATTOpcode::Sysenter => "sysenter",
+            X8664ATTOpcode::Cmovgel => "cmovgel",
+            X8664ATTOpcode::Movsbl => "movsbl",
+            X8664ATTOpcode::Paddusb => "paddusb",
+            X8664ATTOpcode::Psraw => "psraw",
+            X8664ATTOpcode::Movntil => "movntil",
+            X8664ATTOpcode::Cmovnol => "cmovnol",
+            X8664ATTOpcode::Mulps => "mulps",
+            X8664ATTOpcode::Divps => "divps",
+            X8664ATTOpcode::Comiss => "comiss",
+            X8664ATTOpcode::Btsl => "btsl",
+            X8664ATTOpcode::Cpuid => "cpuid",
+            X8664ATTOpcode::Subps => "subps",
+            X8664ATTOpcode::Rcpps => "rcpps",
+            X8664ATTOpcode::Wrmsr => "wrmsr",
+            X8664ATTOpcode::Setno => "setno",
+            X8664ATTOpcode::Movhps => "movhps",
+            X8664ATTOpcode::Xaddb => "xaddb",
+            X8664ATTOpcode::Pmullw => "pmullw",
+            X8664ATTOpcode::Sqrtps => "sqrtps",
+            X8664ATTOpcode::Pmaxub => "pmaxub",
+            X8664ATTOpcode::Punpckhwd => "punpckhwd",
+            X8664ATTOpcode::Paddsw => "paddsw",
+            X8664ATTOpcode::Movntps => "movntps",
+            X8664ATTOpcode::Punpckhbw => "punpckhbw",
+            X8664ATTOpcode::Fxsave => "fxsave",
+            X8664ATTOpcode::Pcmpgtw => "pcmpgtw",
+            X8664ATTOpcode::Cvtps2pd => "cvtps2pd",
+            X8664ATTOpcode::Pshufw => "pshufw",
+            X8664ATTOpcode::Prefetcht1 => "prefetcht1",
+            X8664ATTOpcode::Fxtract => "fxtract",
+            X8664ATTOpcode::Movbel => "movbel",
+            X8664ATTOpcode::Pmulhw => "pmulhw",
+            X8664ATTOpcode::Prefetchw => "prefetchw",
+            X8664ATTOpcode::Xabort => "xabort",
+            X8664ATTOpcode::Ffreep => "ffreep",
+            X8664ATTOpcode::Lcallq => "lcallq",
+            X8664ATTOpcode::Pcmpeqw => "pcmpeqw",
+            X8664ATTOpcode::Fcompi => "fcompi",
+            X8664ATTOpcode::Ucomiss => "ucomiss",
+            X8664ATTOpcode::Addps => "addps",
+            X8664ATTOpcode::Setle => "setle",
+            X8664ATTOpcode::Vpcmpeqb => "vpcmpeqb",
+            X8664ATTOpcode::Vucomisd => "vucomisd",
+            X8664ATTOpcode::Orps => "orps",
+            X8664ATTOpcode::Vpmaddubsw => "vpmaddubsw",
+            X8664ATTOpcode::Vaddss => "vaddss",
+            X8664ATTOpcode::Vunpckhpd => "vunpckhpd",
+            X8664ATTOpcode::Vpslld => "vpslld",
+            X8664ATTOpcode::Vpcmpgtd => "vpcmpgtd",
+            X8664ATTOpcode::Vaddps => "vaddps",
+            X8664ATTOpcode::Vphsubw => "vphsubw",
+            X8664ATTOpcode::Subw => "subw",
+            X8664ATTOpcode::Vpdpwssd => "vpdpwssd",
+            X8664ATTOpcode::Vpblendmb => "vpblendmb",
+            X8664ATTOpcode::Vpsrlvd => "vpsrlvd",
+            X8664ATTOpcode::Vpacksswb => "vpacksswb",
+            X8664ATTOpcode::Vminps => "vminps",
+            X8664ATTOpcode::Vmaxsh => "vmaxsh",
+            X8664ATTOpcode::Vpermi2b => "vpermi2b",
+            X8664ATTOpcode::Vpminsd => "vpminsd",
+            X8664ATTOpcode::Vmaxps => "vmaxps",
+            X8664ATTOpcode::Vpblendmd => "vpblendmd",
+            X8664ATTOpcode::Vmaxph => "vmaxph",
+            X8664ATTOpcode::Vpunpcklwd => "vpunpcklwd",
+            X8664ATTOpcode::Vpunpckhwd => "vpunpckhwd",
+            X8664ATTOpcode::Vpunpckldq => "vpunpckldq",
+            X8664ATTOpcode::Vpermd => "vpermd",
+            X8664ATTOpcode::Vpshrdd => "vpshrdd",
+            X8664ATTOpcode::Vpackuswb => "vpackuswb",
+            X8664ATTOpcode::Vinserti32x4 => "vinserti32x4",
+            X8664ATTOpcode::Vpminsb => "vpminsb",
+            X8664ATTOpcode::Vpermi2ps => "vpermi2ps",
+            X8664ATTOpcode::Lodsw => "lodsw",
+            X8664ATTOpcode::Fxch => "fxch",
+            X8664ATTOpcode::Cmovlel => "cmovlel",
+            X8664ATTOpcode::Cwtd => "cwtd",
+            X8664ATTOpcode::Retw => "retw",
+            X8664ATTOpcode::Btcl => "btcl",
+            X8664ATTOpcode::Fsubrp => "fsubrp",
+            X8664ATTOpcode::Fldlg2 => "fldlg2",
+            X8664ATTOpcode::Fptan => "fptan",
+            X8664ATTOpcode::Fcos => "fcos",
+            X8664ATTOpcode::Fucompp => "fucompp",
+            X8664ATTOpcode::Repz => "repz",
+            X8664ATTOpcode::Repnz => "repnz",
+            X8664ATTOpcode::Notrack => "notrack",
+            X8664ATTOpcode::Bnd => "bnd",
+            X8664ATTOpcode::Call => "call",
+            X8664ATTOpcode::Ret => "ret",
+            X8664ATTOpcode::Jecxz => "jecxz",
+            X8664ATTOpcode::Push => "push",
+            X8664ATTOpcode::Xchg => "xchg",
+            X8664ATTOpcode::Mov => "mov",
+            X8664ATTOpcode::Lea => "lea",
+            X8664ATTOpcode::Sub => "sub",
+            X8664ATTOpcode::Xor => "xor",
+            X8664ATTOpcode::Cmp => "cmp",
+            X8664ATTOpcode::Cmpxchg => "cmpxchg",
+            X8664ATTOpcode::Dec => "dec",
+            X8664ATTOpcode::Stos => "stos",
+            X8664ATTOpcode::Test => "test",
+            X8664ATTOpcode::Pop => "pop",
+            X8664ATTOpcode::Rol => "rol",
+            X8664ATTOpcode::And => "and",
+            X8664ATTOpcode::Shr => "shr",
+            X8664ATTOpcode::Cmove => "cmove",
+            X8664ATTOpcode::Ror => "ror",
+            X8664ATTOpcode::Neg => "neg",
+            X8664ATTOpcode::Sbb => "sbb",
+            X8664ATTOpcode::Shl => "shl",
+            X8664ATTOpcode::Imul => "imul",
+            X8664ATTOpcode::Or => "or",
+            X8664ATTOpcode::Cmovne => "cmovne",
+            X8664ATTOpcode::Cmovle => "cmovle",
+            X8664ATTOpcode::Cmovg => "cmovg",
+            X8664ATTOpcode::Cmovl => "cmovl",
+            X8664ATTOpcode::Cmovns => "cmovns",
+            X8664ATTOpcode::Cmovs => "cmovs",
+            X8664ATTOpcode::Sar => "sar",
+            X8664ATTOpcode::Bswap => "bswap",
+            X8664ATTOpcode::Div => "div",
+            X8664ATTOpcode::Cmovae => "cmovae",
+            X8664ATTOpcode::Movabs => "movabs",
+            X8664ATTOpcode::Bt => "bt",
+            X8664ATTOpcode::Shufpd => "shufpd",
+            X8664ATTOpcode::Not => "not",
+            X8664ATTOpcode::Adc => "adc",
+            X8664ATTOpcode::Cmovb => "cmovb",
+            X8664ATTOpcode::Inc => "inc",
+            X8664ATTOpcode::Fabs => "fabs",
+            X8664ATTOpcode::Fchs => "fchs",
+            X8664ATTOpcode::Fld1 => "fld1",
+            X8664ATTOpcode::Fscale => "fscale",
+            X8664ATTOpcode::Fucomip => "fucomip",
+            X8664ATTOpcode::Fldz => "fldz",
+            X8664ATTOpcode::Btr => "btr",
+            X8664ATTOpcode::Movapd => "movapd",
+            X8664ATTOpcode::Andpd => "andpd",
+            X8664ATTOpcode::Andnpd => "andnpd",
+            X8664ATTOpcode::Orpd => "orpd",
+            X8664ATTOpcode::Mulsd => "mulsd",
+            X8664ATTOpcode::Subsd => "subsd",
+            X8664ATTOpcode::Addsd => "addsd",
+            X8664ATTOpcode::Ucomisd => "ucomisd",
+            X8664ATTOpcode::Setp => "setp",
+            X8664ATTOpcode::Andps => "andps",
+            X8664ATTOpcode::Andnps => "andnps",
+            X8664ATTOpcode::Subss => "subss",
+            X8664ATTOpcode::Mulss => "mulss",
+            X8664ATTOpcode::Addss => "addss",
+            X8664ATTOpcode::Bsr => "bsr",
+            X8664ATTOpcode::Idiv => "idiv",
+            X8664ATTOpcode::Stmxcsr => "stmxcsr",
+            X8664ATTOpcode::Cmovge => "cmovge",
+            X8664ATTOpcode::Cmovbe => "cmovbe",
+            X8664ATTOpcode::Ldmxcsr => "ldmxcsr",
+            X8664ATTOpcode::Comisd => "comisd",
+            X8664ATTOpcode::Xorpd => "xorpd",
+            X8664ATTOpcode::Fcomip => "fcomip",
+            X8664ATTOpcode::Cvtss2sd => "cvtss2sd",
+            X8664ATTOpcode::Mul => "mul",
+            X8664ATTOpcode::Setl => "setl",
+            X8664ATTOpcode::Shld => "shld",
+            X8664ATTOpcode::Shrd => "shrd",
+            X8664ATTOpcode::Bts => "bts",
+            X8664ATTOpcode::Movmskpd => "movmskpd",
+            X8664ATTOpcode::Tzcnt => "tzcnt",
+            X8664ATTOpcode::Fcomi => "fcomi",
+            X8664ATTOpcode::Cvtsi2sd => "cvtsi2sd",
+            X8664ATTOpcode::Divsd => "divsd",
+            X8664ATTOpcode::Cmova => "cmova",
+            X8664ATTOpcode::Movhlps => "movhlps",
+            X8664ATTOpcode::Xbegin => "xbegin",
+            X8664ATTOpcode::Xend => "xend",
+            X8664ATTOpcode::Xadd => "xadd",
+            X8664ATTOpcode::Bsf => "bsf",
+            X8664ATTOpcode::Movntdq => "movntdq",
+            X8664ATTOpcode::Sfence => "sfence",
+            X8664ATTOpcode::Pminub => "pminub",
+            X8664ATTOpcode::Movlpd => "movlpd",
+            X8664ATTOpcode::Movhpd => "movhpd",
+            X8664ATTOpcode::Psubb => "psubb",
+            X8664ATTOpcode::Pslldq => "pslldq",
+            X8664ATTOpcode::Psrldq => "psrldq",
+            X8664ATTOpcode::Bzhi => "bzhi",
+            X8664ATTOpcode::Kmovq => "kmovq",
+            X8664ATTOpcode::Vmovdqu8 => "vmovdqu8",
+            X8664ATTOpcode::Vptestnmb => "vptestnmb",
+            X8664ATTOpcode::Blsmsk => "blsmsk",
+            X8664ATTOpcode::Vpbroadcastb => "vpbroadcastb",
+            X8664ATTOpcode::Vpcmpneqb => "vpcmpneqb",
+            X8664ATTOpcode::Kortestq => "kortestq",
+            X8664ATTOpcode::Vzeroupper => "vzeroupper",
+            X8664ATTOpcode::Blsr => "blsr",
+            X8664ATTOpcode::Vmovdqa64 => "vmovdqa64",
+            X8664ATTOpcode::Vmovdqu64 => "vmovdqu64",
+            X8664ATTOpcode::Kxnorq => "kxnorq",
+            X8664ATTOpcode::Rdpkru => "rdpkru",
+            X8664ATTOpcode::Wrpkru => "wrpkru",
+            X8664ATTOpcode::Cvtsi2ss => "cvtsi2ss",
+            X8664ATTOpcode::Divss => "divss",
+            X8664ATTOpcode::Cvttss2si => "cvttss2si",
+            X8664ATTOpcode::Psrlw => "psrlw",
+            X8664ATTOpcode::Vmovd => "vmovd",
+            X8664ATTOpcode::Vpmovmskb => "vpmovmskb",
+            X8664ATTOpcode::Vpor => "vpor",
+            X8664ATTOpcode::Sarx => "sarx",
+            X8664ATTOpcode::Vmovdqu => "vmovdqu",
+            X8664ATTOpcode::Vpand => "vpand",
+            X8664ATTOpcode::Movbe => "movbe",
+            X8664ATTOpcode::Vmovdqa => "vmovdqa",
+            X8664ATTOpcode::Vmovntdq => "vmovntdq",
+            X8664ATTOpcode::Lzcnt => "lzcnt",
+            X8664ATTOpcode::Shlx => "shlx",
+            X8664ATTOpcode::Vpbroadcastd => "vpbroadcastd",
+            X8664ATTOpcode::Vmovq => "vmovq",
+            X8664ATTOpcode::Vpxor => "vpxor",
+            X8664ATTOpcode::Vpminub => "vpminub",
+            X8664ATTOpcode::Vpaddb => "vpaddb",
+            X8664ATTOpcode::Vpcmpgtb => "vpcmpgtb",
+            X8664ATTOpcode::Vmovaps => "vmovaps",
+            X8664ATTOpcode::Shrx => "shrx",
+            X8664ATTOpcode::Vpcmpeqd => "vpcmpeqd",
+            X8664ATTOpcode::Vpminud => "vpminud",
+            X8664ATTOpcode::Xtest => "xtest",
+            X8664ATTOpcode::Vzeroall => "vzeroall",
+            X8664ATTOpcode::Kmovd => "kmovd",
+            X8664ATTOpcode::Vpternlogd => "vpternlogd",
+            X8664ATTOpcode::Vpcmpnequb => "vpcmpnequb",
+            X8664ATTOpcode::Vpxorq => "vpxorq",
+            X8664ATTOpcode::Vptestmb => "vptestmb",
+            X8664ATTOpcode::Kortestd => "kortestd",
+            X8664ATTOpcode::Vpsubb => "vpsubb",
+            X8664ATTOpcode::Vpcmpltub => "vpcmpltub",
+            X8664ATTOpcode::Ktestd => "ktestd",
+            X8664ATTOpcode::Kord => "kord",
+            X8664ATTOpcode::Kunpckdq => "kunpckdq",
+            X8664ATTOpcode::Vpxord => "vpxord",
+            X8664ATTOpcode::Vptestnmd => "vptestnmd",
+            X8664ATTOpcode::Vpcmpneqd => "vpcmpneqd",
+            X8664ATTOpcode::Vptestmd => "vptestmd",
+            X8664ATTOpcode::Kunpckbw => "kunpckbw",
+            X8664ATTOpcode::Vmovdqu32 => "vmovdqu32",
+            X8664ATTOpcode::Vmovups => "vmovups",
+            X8664ATTOpcode::Vbroadcastss => "vbroadcastss",
+            X8664ATTOpcode::Palignr => "palignr",
+            X8664ATTOpcode::Pcmpistri => "pcmpistri",
+            X8664ATTOpcode::Pshufb => "pshufb",
+            X8664ATTOpcode::Pminud => "pminud",
+            X8664ATTOpcode::Endbr64 => "endbr64",
+            X8664ATTOpcode::Fwait => "fwait",
+            X8664ATTOpcode::Fnclex => "fnclex",
+            X8664ATTOpcode::Fstcw => "fstcw",
+            X8664ATTOpcode::Fyl2xp1 => "fyl2xp1",
+            X8664ATTOpcode::F2xm1 => "f2xm1",
+            X8664ATTOpcode::Btc => "btc",
+            X8664ATTOpcode::Setnp => "setnp",
+            X8664ATTOpcode::Fpatan => "fpatan",
+            X8664ATTOpcode::Unpcklpd => "unpcklpd",
+            X8664ATTOpcode::Cvttsd2si => "cvttsd2si",
+            X8664ATTOpcode::Cmpnlesd => "cmpnlesd",
+            X8664ATTOpcode::Sets => "sets",
+            X8664ATTOpcode::Cmpnless => "cmpnless",
+            X8664ATTOpcode::Cvtsd2ss => "cvtsd2ss",
+            X8664ATTOpcode::Fsqrt => "fsqrt",
+            X8664ATTOpcode::Fstsw => "fstsw",
+            X8664ATTOpcode::Fldl2e => "fldl2e",
+            X8664ATTOpcode::Fldln2 => "fldln2",
+            X8664ATTOpcode::Fyl2x => "fyl2x",
+            X8664ATTOpcode::Fprem1 => "fprem1",
+            X8664ATTOpcode::Fcmove => "fcmove",
+            X8664ATTOpcode::Fldl2t => "fldl2t",
+            X8664ATTOpcode::Sqrtsd => "sqrtsd",
+            X8664ATTOpcode::Cmpltsd => "cmpltsd",
+            X8664ATTOpcode::Cmplesd => "cmplesd",
+            X8664ATTOpcode::Vfmadd213sd => "vfmadd213sd",
+            X8664ATTOpcode::Vfmaddsd => "vfmaddsd",
+            X8664ATTOpcode::Cvtsd2si => "cvtsd2si",
+            X8664ATTOpcode::Movupd => "movupd",
+            X8664ATTOpcode::Addpd => "addpd",
+            X8664ATTOpcode::Maxsd => "maxsd",
+            X8664ATTOpcode::Minsd => "minsd",
+            X8664ATTOpcode::Cmovnp => "cmovnp",
+            X8664ATTOpcode::Sqrtss => "sqrtss",
+            X8664ATTOpcode::Cvtsi2ssl => "cvtsi2ssl",
+            X8664ATTOpcode::Vfmadd213ss => "vfmadd213ss",
+            X8664ATTOpcode::Vfmaddss => "vfmaddss",
+            X8664ATTOpcode::Cvtss2si => "cvtss2si",
+            X8664ATTOpcode::Maxss => "maxss",
+            X8664ATTOpcode::Minss => "minss",
+            X8664ATTOpcode::Cmpless => "cmpless",
+            X8664ATTOpcode::Cvtdq2pd => "cvtdq2pd",
+            X8664ATTOpcode::Cvtsi2sdl => "cvtsi2sdl",
+            X8664ATTOpcode::Cvttpd2dq => "cvttpd2dq",
+            X8664ATTOpcode::Roundsd => "roundsd",
+            X8664ATTOpcode::Roundss => "roundss",
+            X8664ATTOpcode::Vaddsd => "vaddsd",
+            X8664ATTOpcode::Vmulsd => "vmulsd",
+            X8664ATTOpcode::Vmovsd => "vmovsd",
+            X8664ATTOpcode::Vfmadd231sd => "vfmadd231sd",
+            X8664ATTOpcode::Vandpd => "vandpd",
+            X8664ATTOpcode::Vcomisd => "vcomisd",
+            X8664ATTOpcode::Vxorpd => "vxorpd",
+            X8664ATTOpcode::Vsubsd => "vsubsd",
+            X8664ATTOpcode::Vfmadd132sd => "vfmadd132sd",
+            X8664ATTOpcode::Vfnmadd132sd => "vfnmadd132sd",
+            X8664ATTOpcode::Vfnmadd213sd => "vfnmadd213sd",
+            X8664ATTOpcode::Vdivsd => "vdivsd",
+            X8664ATTOpcode::Vstmxcsr => "vstmxcsr",
+            X8664ATTOpcode::Vcmpltsd => "vcmpltsd",
+            X8664ATTOpcode::Vblendvpd => "vblendvpd",
+            X8664ATTOpcode::Vandnpd => "vandnpd",
+            X8664ATTOpcode::Vorpd => "vorpd",
+            X8664ATTOpcode::Vcmpnltsd => "vcmpnltsd",
+            X8664ATTOpcode::Vldmxcsr => "vldmxcsr",
+            X8664ATTOpcode::Vfmsub132sd => "vfmsub132sd",
+            X8664ATTOpcode::Vcvttsd2si => "vcvttsd2si",
+            X8664ATTOpcode::Vxorps => "vxorps",
+            X8664ATTOpcode::Vcvtsi2sd => "vcvtsi2sd",
+            X8664ATTOpcode::Vfnmadd231sd => "vfnmadd231sd",
+            X8664ATTOpcode::Vfmsub231sd => "vfmsub231sd",
+            X8664ATTOpcode::Vcmpnlesd => "vcmpnlesd",
+            X8664ATTOpcode::Vcmplesd => "vcmplesd",
+            X8664ATTOpcode::Mulpd => "mulpd",
+            X8664ATTOpcode::Cvtpd2ps => "cvtpd2ps",
+            X8664ATTOpcode::Unpckhpd => "unpckhpd",
+            X8664ATTOpcode::Vcvtss2sd => "vcvtss2sd",
+            X8664ATTOpcode::Vcvtsd2ss => "vcvtsd2ss",
+            X8664ATTOpcode::Vcomiss => "vcomiss",
+            X8664ATTOpcode::Vmovss => "vmovss",
+            X8664ATTOpcode::Vmulss => "vmulss",
+            X8664ATTOpcode::Vucomiss => "vucomiss",
+            X8664ATTOpcode::Vsubss => "vsubss",
+            X8664ATTOpcode::Vdivss => "vdivss",
+            X8664ATTOpcode::Vmovddup => "vmovddup",
+            X8664ATTOpcode::Vunpcklpd => "vunpcklpd",
+            X8664ATTOpcode::Vmovapd => "vmovapd",
+            X8664ATTOpcode::Vmulpd => "vmulpd",
+            X8664ATTOpcode::Vfmadd213pd => "vfmadd213pd",
+            X8664ATTOpcode::Vfmadd132pd => "vfmadd132pd",
+            X8664ATTOpcode::Vcvtpd2ps => "vcvtpd2ps",
+            X8664ATTOpcode::Vextractps => "vextractps",
+            X8664ATTOpcode::Vpermilpd => "vpermilpd",
+            X8664ATTOpcode::Vfnmaddsd => "vfnmaddsd",
+            X8664ATTOpcode::Vfmsubsd => "vfmsubsd",
+            X8664ATTOpcode::Movzbw => "movzbw",
+            X8664ATTOpcode::Vperm2f128 => "vperm2f128",
+            X8664ATTOpcode::Vpsrld => "vpsrld",
+            X8664ATTOpcode::Vpackusdw => "vpackusdw",
+            X8664ATTOpcode::Vpalignr => "vpalignr",
+            X8664ATTOpcode::Vpunpckhbw => "vpunpckhbw",
+            X8664ATTOpcode::Vpunpcklbw => "vpunpcklbw",
+            X8664ATTOpcode::Vpermq => "vpermq",
+            X8664ATTOpcode::Vbroadcastf128 => "vbroadcastf128",
+            X8664ATTOpcode::Decw => "decw",
+            X8664ATTOpcode::Cmovno => "cmovno",
+            X8664ATTOpcode::Xgetbv => "xgetbv",
+            X8664ATTOpcode::Incw => "incw",
+            X8664ATTOpcode::Vmulps => "vmulps",
+            X8664ATTOpcode::Vshufps => "vshufps",
+            X8664ATTOpcode::Vpinsrd => "vpinsrd",
+            X8664ATTOpcode::Vpunpcklqdq => "vpunpcklqdq",
+            X8664ATTOpcode::Vpmulld => "vpmulld",
+            X8664ATTOpcode::Vpaddd => "vpaddd",
+            X8664ATTOpcode::Vpsrldq => "vpsrldq",
+            X8664ATTOpcode::Bad => "(bad)",
+            X8664ATTOpcode::Byte => ".byte",
+            X8664ATTOpcode::Word => ".word",
+            X8664ATTOpcode::Long => ".long",
+            X8664ATTOpcode::Unknown(opcode) => opcode,
+        }
+    }
+
+    /// Assembler data directive objdump prints for bytes it does not
+    /// disassemble, such as the tail of a truncated instruction.
+    pub fn is_data(&self) -> bool {
+        matches!(
+            self,
+            X8664ATTOpcode::Byte | X8664ATTOpcode::Word | X8664ATTOpcode::Long
+        )
+    }
+
+    /// Size in bytes of each value of a data directive.
+    pub fn data_size(&self) -> Option<usize> {
+        match self {
+            X8664ATTOpcode::Byte => Some(1),
+            X8664ATTOpcode::Word => Some(2),
+            X8664ATTOpcode::Long => Some(4),
+            _ => None,
+        }
+    }
+
+    pub fn is_prefix(&self) -> bool {
+        matches!(
+            self,
+            X8664ATTOpcode::Lock
+                | X8664ATTOpcode::Rep
+                | X8664ATTOpcode::Repz
+                | X8664ATTOpcode::Repne
+                | X8664ATTOpcode::Repnz
+                | X8664ATTOpcode::Cs
+                | X8664ATTOpcode::Ds
+                | X8664ATTOpcode::Es
+                | X8664ATTOpcode::Fs
+                | X8664ATTOpcode::Gs
+                | X8664ATTOpcode::Ss
+                | X8664ATTOpcode::Data16
+                | X8664ATTOpcode::Addr32
+                | X8664ATTOpcode::Rex64
+                | X8664ATTOpcode::Xacquire
+                | X8664ATTOpcode::Xrelease
+                | X8664ATTOpcode::Notrack
+                | X8664ATTOpcode::Bnd
+        ) || matches!(self, X8664ATTOpcode::Unknown(prefix) if prefix.starts_with("rex"))
+    }
 
-#[derive(Debug)]
-enum X8664ATTOperand {
+    pub fn flow(&self) -> X8664ATTFlow {
+        match self {
+            X8664ATTOpcode::Jmp
+            | X8664ATTOpcode::Jmpq
+            | X8664ATTOpcode::Ljmpl
+            | X8664ATTOpcode::Ljmpq => X8664ATTFlow::Jump,
+            X8664ATTOpcode::Ja
+            | X8664ATTOpcode::Jae
+            | X8664ATTOpcode::Jb
+            | X8664ATTOpcode::Jbe
+            | X8664ATTOpcode::Je
+            | X8664ATTOpcode::Jne
+            | X8664ATTOpcode::Jg
+            | X8664ATTOpcode::Jge
+            | X8664ATTOpcode::Jl
+            | X8664ATTOpcode::Jle
+            | X8664ATTOpcode::Jo
+            | X8664ATTOpcode::Jno
+            | X8664ATTOpcode::Jp
+            | X8664ATTOpcode::Jnp
+            | X8664ATTOpcode::Js
+            | X8664ATTOpcode::Jns
+            | X8664ATTOpcode::Jrcxz
+            | X8664ATTOpcode::Jecxz
+            | X8664ATTOpcode::Loop
+            | X8664ATTOpcode::Loope
+            | X8664ATTOpcode::Loopne => X8664ATTFlow::ConditionalJump,
+            X8664ATTOpcode::Call
+            | X8664ATTOpcode::Callq
+            | X8664ATTOpcode::Callw
+            | X8664ATTOpcode::Lcalll
+            | X8664ATTOpcode::Lcallq => X8664ATTFlow::Call,
+            X8664ATTOpcode::Ret
+            | X8664ATTOpcode::Retq
+            | X8664ATTOpcode::Retw
+            | X8664ATTOpcode::Lretl
+            | X8664ATTOpcode::Lretq
+            | X8664ATTOpcode::Iretw
+            | X8664ATTOpcode::Iretl
+            | X8664ATTOpcode::Iretq
+            | X8664ATTOpcode::Sysretl => X8664ATTFlow::Return,
+            X8664ATTOpcode::Hlt
+            | X8664ATTOpcode::Ud2
+            | X8664ATTOpcode::Bad
+            | X8664ATTOpcode::Byte
+            | X8664ATTOpcode::Word
+            | X8664ATTOpcode::Long => X8664ATTFlow::Halt,
+            _ => X8664ATTFlow::Sequential,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X8664ATTFlow {
+    Sequential,
+    Jump,
+    ConditionalJump,
+    Call,
+    Return,
+    Halt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum X8664ATTOperand {
     Register(X8664ATTRegister),
     Immediate(X8664ATTImmediate),
     Address(X8664ATTAddress),
+    Target(#[serde(serialize_with = "crate::objdump::hex::address")] u64),
+    Indirect(Box<X8664ATTOperand>),
+    Decorated(Box<X8664ATTOperand>, String),
+    Rounding(String),
+    /// Value of a `.byte`/`.word`/`.long` directive.
+    Literal(#[serde(serialize_with = "crate::objdump::hex::address")] u64),
+    /// Operand objdump could not decode, printed as `(bad)`.
+    Bad,
+}
+
+impl std::fmt::Display for X8664ATTOperand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            X8664ATTOperand::Register(register) => write!(f, "{}", register),
+            X8664ATTOperand::Immediate(immediate) => write!(f, "{}", immediate),
+            X8664ATTOperand::Address(address) => write!(f, "{}", address),
+            X8664ATTOperand::Target(target) => write!(f, "{:x}", target),
+            X8664ATTOperand::Indirect(operand) => write!(f, "*{}", operand),
+            X8664ATTOperand::Decorated(operand, decoration) => {
+                write!(f, "{}{}", operand, decoration)
+            }
+            X8664ATTOperand::Rounding(rounding) => f.write_str(rounding),
+            X8664ATTOperand::Literal(value) => write!(f, "{:#x}", value),
+            X8664ATTOperand::Bad => f.write_str("(bad)"),
+        }
+    }
 }
 
 impl TryFrom<&str> for X8664ATTOperand {
     type Error = ObjumpError;
 
     fn try_from(operand: &str) -> Result<Self, ObjumpError> {
-        if operand.starts_with("%") {
+        if operand == "(bad)" {
+            Ok(X8664ATTOperand::Bad)
+        } else if operand.starts_with('{') {
+            Ok(X8664ATTOperand::Rounding(operand.to_string()))
+        } else if let Some(index) = operand.find('{') {
+            Ok(X8664ATTOperand::Decorated(
+                Box::new(operand[..index].try_into()?),
+                operand[index..].to_string(),
+            ))
+        } else if let Some(operand) = operand.strip_prefix('*') {
+            Ok(X8664ATTOperand::Indirect(Box::new(operand.try_into()?)))
+        } else if operand.starts_with('%') && !operand.contains(':') {
             Ok(X8664ATTOperand::Register(operand.into()))
-        } else if operand.starts_with("$") {
+        } else if operand.starts_with('$') {
             Ok(X8664ATTOperand::Immediate(operand.try_into()?))
+        } else if !operand.is_empty() && operand.chars().all(|c| c.is_ascii_hexdigit()) {
+            Ok(X8664ATTOperand::Target(u64::from_str_radix(operand, 16)?))
         } else {
             Ok(X8664ATTOperand::Address(operand.try_into()?))
         }
     }
 }
 
-#[derive(Debug)]
-enum X8664ATTRegister {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum X8664ATTRegister {
     Rax,
     Rbx,
     Rcx,
@@ -1142,60 +2700,189 @@ impl From<&str> for X8664ATTRegister {
     }
 }
 
-#[derive(Debug)]
-struct X8664ATTAddress {
-    displacement: Option<X8664ATTInteger>,
-    base: Option<X8664ATTValue>,
-    offset: X8664ATTValue,
-    scaler: Option<X8664ATTInteger>,
+impl std::fmt::Display for X8664ATTRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for X8664ATTRegister {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl X8664ATTRegister {
+    pub fn name(&self) -> &str {
+        match self {
+            X8664ATTRegister::Rax => "%rax",
+            X8664ATTRegister::Rbx => "%rbx",
+            X8664ATTRegister::Rcx => "%rcx",
+            X8664ATTRegister::Rdx => "%rdx",
+            X8664ATTRegister::Rsi => "%rsi",
+            X8664ATTRegister::Rdi => "%rdi",
+            X8664ATTRegister::Rbp => "%rbp",
+            X8664ATTRegister::Rsp => "%rsp",
+            X8664ATTRegister::R8 => "%r8",
+            X8664ATTRegister::R9 => "%r9",
+            X8664ATTRegister::R10 => "%r10",
+            X8664ATTRegister::R11 => "%r11",
+            X8664ATTRegister::R12 => "%r12",
+            X8664ATTRegister::R13 => "%r13",
+            X8664ATTRegister::R14 => "%r14",
+            X8664ATTRegister::R15 => "%r15",
+            X8664ATTRegister::Unknown(register) => register,
+        }
+    }
+
+    /// The 64-bit register a general purpose register is part of: `%rax`
+    /// for `%eax`, `%ax`, `%al` and `%ah`. Other registers keep their name.
+    pub fn family(&self) -> &str {
+        const FAMILIES: [[&str; 5]; 16] = [
+            ["%rax", "%eax", "%ax", "%al", "%ah"],
+            ["%rbx", "%ebx", "%bx", "%bl", "%bh"],
+            ["%rcx", "%ecx", "%cx", "%cl", "%ch"],
+            ["%rdx", "%edx", "%dx", "%dl", "%dh"],
+            ["%rsi", "%esi", "%si", "%sil", "%sil"],
+            ["%rdi", "%edi", "%di", "%dil", "%dil"],
+            ["%rbp", "%ebp", "%bp", "%bpl", "%bpl"],
+            ["%rsp", "%esp", "%sp", "%spl", "%spl"],
+            ["%r8", "%r8d", "%r8w", "%r8b", "%r8b"],
+            ["%r9", "%r9d", "%r9w", "%r9b", "%r9b"],
+            ["%r10", "%r10d", "%r10w", "%r10b", "%r10b"],
+            ["%r11", "%r11d", "%r11w", "%r11b", "%r11b"],
+            ["%r12", "%r12d", "%r12w", "%r12b", "%r12b"],
+            ["%r13", "%r13d", "%r13w", "%r13b", "%r13b"],
+            ["%r14", "%r14d", "%r14w", "%r14b", "%r14b"],
+            ["%r15", "%r15d", "%r15w", "%r15b", "%r15b"],
+        ];
+        let name = self.name();
+        FAMILIES
+            .iter()
+            .find(|names| names.contains(&name))
+            .map_or(name, |names| names[0])
+    }
+
+    /// Width in bits of the SIMD register file this register belongs to, if any.
+    pub fn vector_width(&self) -> Option<u32> {
+        match self {
+            X8664ATTRegister::Unknown(register) if register.starts_with("%xmm") => Some(128),
+            X8664ATTRegister::Unknown(register) if register.starts_with("%ymm") => Some(256),
+            X8664ATTRegister::Unknown(register) if register.starts_with("%zmm") => Some(512),
+            _ => None,
+        }
+    }
+
+    /// Mask registers and the upper sixteen vector registers only exist with EVEX.
+    pub fn is_evex_only(&self) -> bool {
+        match self {
+            X8664ATTRegister::Unknown(register) => {
+                register.starts_with("%k")
+                    || register.starts_with("%zmm")
+                    || register
+                        .strip_prefix("%xmm")
+                        .or_else(|| register.strip_prefix("%ymm"))
+                        .and_then(|number| number.parse::<u32>().ok())
+                        .is_some_and(|number| number >= 16)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_mmx(&self) -> bool {
+        matches!(self, X8664ATTRegister::Unknown(register) if register.starts_with("%mm"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct X8664ATTAddress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment: Option<X8664ATTRegister>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub displacement: Option<X8664ATTInteger>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<X8664ATTValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<X8664ATTValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scaler: Option<X8664ATTInteger>,
+}
+
+impl std::fmt::Display for X8664ATTAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(segment) = &self.segment {
+            write!(f, "{}:", segment)?;
+        }
+        let indexed = self.base.is_some() || self.offset.is_some() || self.scaler.is_some();
+        match &self.displacement {
+            // objdump prints absolute addresses unsigned, e.g. `%fs:0xfffffffffffffff8`.
+            Some(displacement) if !indexed => write!(f, "{:#x}", displacement.0 as u64)?,
+            Some(displacement) => write!(f, "{}", displacement)?,
+            None => {}
+        }
+        if indexed {
+            write!(f, "(")?;
+            if let Some(base) = &self.base {
+                write!(f, "{}", base)?;
+            }
+            if let Some(offset) = &self.offset {
+                write!(f, ",{}", offset)?;
+            }
+            if let Some(scaler) = &self.scaler {
+                write!(f, ",{}", scaler.0)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<&str> for X8664ATTAddress {
     type Error = ObjumpError;
 
     fn try_from(memory: &str) -> Result<Self, ObjumpError> {
-        let mut parts = memory.split("(");
+        let (segment, memory) = match memory.split_once(':') {
+            Some((segment, memory)) => (Some(X8664ATTRegister::from(segment)), memory),
+            None => (None, memory),
+        };
+
+        let mut parts = memory.splitn(2, '(');
         let displacement = match parts.next() {
-            Some(displacement) => {
-                if displacement.is_empty() {
-                    None
-                } else {
-                    Some(X8664ATTInteger::try_from(displacement)?)
-                }
-            }
-            None => None,
+            Some("") | None => None,
+            Some(displacement) => Some(X8664ATTInteger::try_from(displacement)?),
         };
 
-        let mut memory = parts.next().unwrap();
-        memory = memory.trim_end_matches(")");
-        let mut parts = memory.split(",");
-        let base = match parts.next() {
-            Some(base) => {
-                if base.is_empty() {
-                    None
-                } else {
-                    Some(X8664ATTValue::try_from(base)?)
-                }
+        let memory = match parts.next() {
+            Some(memory) => memory
+                .strip_suffix(')')
+                .ok_or(ObjumpError::InvalidInstruction(memory.to_string()))?,
+            None => {
+                return Ok(X8664ATTAddress {
+                    segment,
+                    displacement,
+                    base: None,
+                    offset: None,
+                    scaler: None,
+                })
             }
-            None => None,
         };
 
-        let offset = parts
-            .next()
-            .ok_or(ObjumpError::InvalidInstruction(memory.to_string()))?
-            .try_into()?;
+        let mut parts = memory.split(',');
+        let base = match parts.next() {
+            Some("") | None => None,
+            Some(base) => Some(X8664ATTValue::try_from(base)?),
+        };
+        let offset = match parts.next() {
+            Some("") | None => None,
+            Some(offset) => Some(X8664ATTValue::try_from(offset)?),
+        };
         let scaler = match parts.next() {
-            Some(scaler) => {
-                if scaler.is_empty() {
-                    None
-                } else {
-                    Some(X8664ATTInteger::try_from(scaler)?)
-                }
-            }
-            None => None,
+            Some("") | None => None,
+            Some(scaler) => Some(X8664ATTInteger::try_from(scaler)?),
         };
 
         Ok(X8664ATTAddress {
+            segment,
             displacement,
             base,
             offset,
@@ -1204,17 +2891,27 @@ impl TryFrom<&str> for X8664ATTAddress {
     }
 }
 
-#[derive(Debug)]
-enum X8664ATTValue {
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum X8664ATTValue {
     Register(X8664ATTRegister),
     Immediate(X8664ATTImmediate),
 }
 
+impl std::fmt::Display for X8664ATTValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            X8664ATTValue::Register(register) => write!(f, "{}", register),
+            X8664ATTValue::Immediate(immediate) => write!(f, "{}", immediate),
+        }
+    }
+}
+
 impl TryFrom<&str> for X8664ATTValue {
     type Error = ObjumpError;
 
     fn try_from(value: &str) -> Result<Self, ObjumpError> {
-        if value.starts_with("$") {
+        if value.starts_with('$') {
             Ok(X8664ATTValue::Immediate(X8664ATTImmediate::try_from(
                 value,
             )?))
@@ -1224,62 +2921,249 @@ impl TryFrom<&str> for X8664ATTValue {
     }
 }
 
-#[derive(Debug)]
-struct X8664ATTImmediate(u64);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct X8664ATTImmediate(pub u64);
+
+impl std::fmt::Display for X8664ATTImmediate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "${:#x}", self.0)
+    }
+}
+
+impl Serialize for X8664ATTImmediate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::objdump::hex::address(&self.0, serializer)
+    }
+}
 
 impl TryFrom<&str> for X8664ATTImmediate {
     type Error = ObjumpError;
 
     fn try_from(immediate: &str) -> Result<Self, ObjumpError> {
-        if immediate.starts_with("$0x") {
-            Ok(X8664ATTImmediate(u64::from_str_radix(&immediate[3..], 16)?))
-        } else if immediate.starts_with("$0b") {
-            Ok(X8664ATTImmediate(u64::from_str_radix(&immediate[3..], 2)?))
+        if let Some(immediate) = immediate.strip_prefix("$0x") {
+            Ok(X8664ATTImmediate(u64::from_str_radix(immediate, 16)?))
+        } else if let Some(immediate) = immediate.strip_prefix("$0b") {
+            Ok(X8664ATTImmediate(u64::from_str_radix(immediate, 2)?))
         } else {
-            Ok(X8664ATTImmediate(u64::from_str_radix(&immediate[1..], 10)?))
+            Ok(X8664ATTImmediate(immediate[1..].parse()?))
         }
     }
 }
 
-#[derive(Debug)]
-struct X8664ATTInteger(u64);
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct X8664ATTInteger(pub i64);
+
+impl std::fmt::Display for X8664ATTInteger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 < 0 {
+            write!(f, "-{:#x}", self.0.unsigned_abs())
+        } else {
+            write!(f, "{:#x}", self.0)
+        }
+    }
+}
 
 impl TryFrom<&str> for X8664ATTInteger {
     type Error = ObjumpError;
 
     fn try_from(integer: &str) -> Result<Self, ObjumpError> {
-        if integer.starts_with("0x") {
-            Ok(X8664ATTInteger(u64::from_str_radix(&integer[2..], 16)?))
-        } else if integer.starts_with("0b") {
-            Ok(X8664ATTInteger(u64::from_str_radix(&integer[2..], 2)?))
+        let (negative, integer) = match integer.strip_prefix('-') {
+            Some(integer) => (true, integer),
+            None => (false, integer),
+        };
+        let value = if let Some(integer) = integer.strip_prefix("0x") {
+            u64::from_str_radix(integer, 16)?
+        } else if let Some(integer) = integer.strip_prefix("0b") {
+            u64::from_str_radix(integer, 2)?
         } else {
-            Ok(X8664ATTInteger(u64::from_str_radix(integer, 10)?))
+            integer.parse()?
+        } as i64;
+        if negative {
+            Ok(X8664ATTInteger(value.wrapping_neg()))
+        } else {
+            Ok(X8664ATTInteger(value))
         }
     }
 }
 
-impl Into<u64> for X8664ATTInteger {
-    fn into(self) -> u64 {
-        self.0
+impl From<X8664ATTInteger> for i64 {
+    fn from(integer: X8664ATTInteger) -> Self {
+        integer.0
     }
 }
 
-pub fn parse_x8664_att_instruction(line: &str) -> Result<X8664ATTInstruction, ObjumpError> {
-    let parts: Vec<&str> = line.split_whitespace().collect();
-    let opcode = X8664ATTOpcode::from(parts[0]);
-    let mut operands = Vec::new();
-    let mut data = String::new();
-    for part in &parts[1..] {
-        if part.starts_with("<") {
-            data = part.to_string();
-            break;
-        } else {
-            operands.push(X8664ATTOperand::try_from(*part)?);
+/// Splits an operand list on the commas that separate operands, leaving the
+/// ones inside `(base,index,scale)` and `{...}` decorations alone.
+fn split_operands(operands: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in operands.char_indices() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(operands[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
         }
     }
+    let last = operands[start..].trim();
+    if !last.is_empty() {
+        result.push(last);
+    }
+    result
+}
+
+pub fn parse_x8664_att_instruction(line: &str) -> Result<X8664ATTInstruction, ObjumpError> {
+    let (line, data) = match line.find('<') {
+        Some(index) => (&line[..index], line[index..].trim().to_string()),
+        None => (line, String::new()),
+    };
+
+    let mut prefixes = Vec::new();
+    let mut rest = line.trim();
+    let opcode = loop {
+        let (mnemonic, remaining) = match rest.split_once(char::is_whitespace) {
+            Some((mnemonic, remaining)) => (mnemonic, remaining.trim_start()),
+            None => (rest, ""),
+        };
+        if mnemonic.is_empty() {
+            return Err(ObjumpError::InvalidInstruction(line.to_string()));
+        }
+        let opcode = X8664ATTOpcode::from(mnemonic);
+        rest = remaining;
+        if !(opcode.is_prefix() && rest.starts_with(|c: char| c.is_ascii_lowercase())) {
+            break opcode;
+        }
+        prefixes.push(opcode);
+    };
+
+    let operands = match opcode.is_data() {
+        true => split_operands(rest)
+            .into_iter()
+            .map(|value| {
+                let digits = value.trim_start_matches("0x");
+                Ok(X8664ATTOperand::Literal(u64::from_str_radix(digits, 16)?))
+            })
+            .collect::<Result<Vec<_>, ObjumpError>>()?,
+        false => split_operands(rest)
+            .into_iter()
+            .map(X8664ATTOperand::try_from)
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
     Ok(X8664ATTInstruction {
+        prefixes,
         opcode,
         operands,
         data,
     })
-}
\ No newline at end of file
+}
+
+/// Renders GNU objdump's AT&T layout: prefixes, the mnemonic padded to six
+/// columns, comma-separated operands and the `<symbol>` annotation.
+impl std::fmt::Display for X8664ATTInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for prefix in &self.prefixes {
+            write!(f, "{} ", prefix)?;
+        }
+        if self.operands.is_empty() && self.data.is_empty() {
+            return f.write_str(self.opcode.mnemonic());
+        }
+        write!(f, "{:<6} ", self.opcode.mnemonic())?;
+        for (index, operand) in self.operands.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", operand)?;
+        }
+        if !self.data.is_empty() {
+            write!(f, " {}", self.data)?;
+        }
+        Ok(())
+    }
+}
+
+impl X8664ATTInstruction {
+    /// Whether objdump failed to decode the instruction or one of its
+    /// operands.
+    pub fn is_bad(&self) -> bool {
+        self.opcode == X8664ATTOpcode::Bad || self.operands.contains(&X8664ATTOperand::Bad)
+    }
+
+    /// Values of a data directive, `None` for real instructions.
+    pub fn data_values(&self) -> Option<Vec<u64>> {
+        self.opcode.data_size()?;
+        Some(
+            self.operands
+                .iter()
+                .filter_map(|operand| match operand {
+                    X8664ATTOperand::Literal(value) => Some(*value),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    /// Direct target of a jump or call, when it is encoded in the instruction.
+    pub fn branch_target(&self) -> Option<u64> {
+        match self.opcode.flow() {
+            X8664ATTFlow::Jump | X8664ATTFlow::ConditionalJump | X8664ATTFlow::Call => {
+                match self.operands.first() {
+                    Some(X8664ATTOperand::Target(target)) => Some(*target),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Widest SIMD register touched by the instruction, in bits.
+    pub fn vector_width(&self) -> Option<u32> {
+        self.operands
+            .iter()
+            .filter_map(|operand| match operand {
+                X8664ATTOperand::Register(register) => register.vector_width(),
+                X8664ATTOperand::Decorated(operand, _) => match operand.as_ref() {
+                    X8664ATTOperand::Register(register) => register.vector_width(),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_instructions_round_trip() {
+        for text in ["(bad)", "vpaddd (bad),%zmm1,%zmm2"] {
+            let instruction = parse_x8664_att_instruction(text).unwrap();
+            assert!(instruction.is_bad());
+            assert_eq!(instruction.data_values(), None);
+            assert_eq!(instruction.to_string(), text);
+        }
+        assert!(!parse_x8664_att_instruction("ret").unwrap().is_bad());
+    }
+
+    #[test]
+    fn data_directives_round_trip() {
+        let byte = parse_x8664_att_instruction(".byte 0xc4").unwrap();
+        assert!(byte.opcode.is_data());
+        assert!(!byte.is_bad());
+        assert_eq!(byte.data_values(), Some(vec![0xc4]));
+        assert_eq!(byte.to_string(), ".byte  0xc4");
+
+        let long = parse_x8664_att_instruction(".long 0x12345678,0x9").unwrap();
+        assert_eq!(long.opcode.data_size(), Some(4));
+        assert_eq!(long.data_values(), Some(vec![0x12345678, 0x9]));
+        assert_eq!(long.to_string(), ".long  0x12345678,0x9");
+
+        assert!(parse_x8664_att_instruction(".byte 0xzz").is_err());
+    }
+}
@@ -0,0 +1,365 @@
+use super::{
+    X8664ATTAddress, X8664ATTFlow, X8664ATTInstruction, X8664ATTOperand, X8664ATTRegister,
+    X8664ATTValue,
+};
+
+/// Width in bits of a register given its AT&T name.
+fn register_bits(register: &X8664ATTRegister) -> Option<u32> {
+    let name = register.name().trim_start_matches('%');
+    if let Some(width) = register.vector_width() {
+        return Some(width);
+    }
+    let bits = match name {
+        "al" | "bl" | "cl" | "dl" | "ah" | "bh" | "ch" | "dh" | "sil" | "dil" | "bpl" | "spl" => 8,
+        "ax" | "bx" | "cx" | "dx" | "si" | "di" | "bp" | "sp" => 16,
+        "eax" | "ebx" | "ecx" | "edx" | "esi" | "edi" | "ebp" | "esp" | "eip" => 32,
+        "rax" | "rbx" | "rcx" | "rdx" | "rsi" | "rdi" | "rbp" | "rsp" | "rip" => 64,
+        _ if name.starts_with("mm") => 64,
+        _ if name.starts_with("st") => 80,
+        _ if name.starts_with('r') && name[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+            match name.chars().last() {
+                Some('b') => 8,
+                Some('w') => 16,
+                Some('d') => 32,
+                _ => 64,
+            }
+        }
+        _ => return None,
+    };
+    Some(bits)
+}
+
+fn size_keyword(bits: u32) -> Option<&'static str> {
+    match bits {
+        8 => Some("BYTE"),
+        16 => Some("WORD"),
+        32 => Some("DWORD"),
+        64 => Some("QWORD"),
+        80 => Some("TBYTE"),
+        128 => Some("XMMWORD"),
+        256 => Some("YMMWORD"),
+        512 => Some("ZMMWORD"),
+        _ => None,
+    }
+}
+
+/// Memory operand size encoded by an AT&T suffix. x87 suffixes depend on
+/// whether the operation works on floats (`flds`, `fldl`, `fldt`) or
+/// integers (`filds`, `fildl`, `fildll`).
+fn suffix_bits(suffix: &str, operation: &str) -> Option<u32> {
+    let x87_integer = operation.starts_with("fi");
+    let x87_float = operation.starts_with('f') && !x87_integer;
+    match suffix {
+        "b" => Some(8),
+        "w" => Some(16),
+        "s" if x87_integer => Some(16),
+        "s" => Some(32),
+        "l" if x87_float => Some(64),
+        "l" => Some(32),
+        "q" | "ll" => Some(64),
+        "t" => Some(80),
+        _ => None,
+    }
+}
+
+/// Intel names for the AT&T mnemonics that differ beyond the size suffix.
+fn intel_mnemonic(operation: &str) -> &str {
+    match operation {
+        "cbtw" => "cbw",
+        "cwtl" => "cwde",
+        "cltq" => "cdqe",
+        "cwtd" => "cwd",
+        "cltd" => "cdq",
+        "cqto" => "cqo",
+        "lcall" => "call",
+        "ljmp" => "jmp",
+        _ => operation,
+    }
+}
+
+/// The operation with `sub`/`subr` and `div`/`divr` exchanged, for x87
+/// register forms whose destination is `%st(i)`: AT&T mnemonics keep the
+/// historical UnixWare inversion there, Intel ones do not.
+fn x87_reversed(operation: &str) -> Option<&'static str> {
+    match operation {
+        "fsub" => Some("fsubr"),
+        "fsubr" => Some("fsub"),
+        "fsubp" => Some("fsubrp"),
+        "fsubrp" => Some("fsubp"),
+        "fdiv" => Some("fdivr"),
+        "fdivr" => Some("fdiv"),
+        "fdivp" => Some("fdivrp"),
+        "fdivrp" => Some("fdivp"),
+        _ => None,
+    }
+}
+
+impl X8664ATTInstruction {
+    /// Size in bits of the memory operand, taken from the AT&T suffix when
+    /// there is one and from the other operands otherwise.
+    fn memory_bits(&self) -> Option<u32> {
+        let mnemonic = self.opcode.mnemonic();
+        let operation = self.opcode.operation();
+        match operation {
+            "fldcw" | "fnstcw" | "fstcw" | "fnstsw" | "fstsw" => return Some(16),
+            "ldmxcsr" | "stmxcsr" | "vldmxcsr" | "vstmxcsr" => return Some(32),
+            "movzx" | "movsx" => return mnemonic.get(4..5).and_then(|s| suffix_bits(s, operation)),
+            "movsxd" => return Some(32),
+            "lea" => return None,
+            _ if operation.starts_with("set") || operation.starts_with("prefetch") => {
+                return Some(8)
+            }
+            _ => {}
+        }
+        if self.opcode.flow() != X8664ATTFlow::Sequential
+            || operation == "push"
+            || operation == "pop"
+        {
+            return Some(64);
+        }
+        if mnemonic != operation && mnemonic.starts_with(operation) {
+            let suffix = &mnemonic[operation.len()..];
+            return suffix_bits(suffix, operation);
+        }
+        let element = operation
+            .trim_start_matches('v')
+            .strip_prefix("pinsr")
+            .or_else(|| operation.trim_start_matches('v').strip_prefix("pextr"))
+            .or_else(|| operation.strip_prefix("vpbroadcast"));
+        match element {
+            Some("b") => return Some(8),
+            Some("w") => return Some(16),
+            Some("d") => return Some(32),
+            Some("q") => return Some(64),
+            _ => {}
+        }
+        // Conversions read their memory operand in the source format.
+        let source = match operation.split_once('2') {
+            Some((source, _)) if operation.contains("cvt") => source,
+            _ => operation,
+        };
+        if source.ends_with("ss") || operation == "movd" || operation == "vmovd" {
+            return Some(32);
+        }
+        let half_move = matches!(
+            operation.trim_start_matches('v'),
+            "movhps" | "movhpd" | "movlps" | "movlpd"
+        );
+        if source.ends_with("sd") || half_move || operation == "movq" || operation == "vmovq" {
+            return Some(64);
+        }
+        self.operands
+            .iter()
+            .filter_map(|operand| match operand {
+                X8664ATTOperand::Register(register) => register_bits(register),
+                X8664ATTOperand::Decorated(operand, _) => match operand.as_ref() {
+                    X8664ATTOperand::Register(register) => register_bits(register),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .max()
+    }
+
+    /// Renders the instruction in the Intel syntax GNU objdump prints with
+    /// `-M intel`: destination first, no sigils, `SIZE PTR [base+index*scale+disp]`
+    /// memory operands and Intel mnemonics (`movzx`, `cdqe`, `cqo`, ...).
+    pub fn to_intel(&self) -> String {
+        let mut text = String::new();
+        for prefix in &self.prefixes {
+            text.push_str(prefix.mnemonic());
+            text.push(' ');
+        }
+        // `movq %xmm1,%rax` keeps its suffix: it is a different instruction.
+        let vector_move = self.opcode.operation() == "mov"
+            && self.operands.iter().any(|operand| {
+                matches!(operand, X8664ATTOperand::Register(register)
+                    if register.vector_width().is_some() || register.is_mmx())
+            });
+        let x87_destination = matches!(
+            self.operands.as_slice(),
+            [X8664ATTOperand::Register(_), X8664ATTOperand::Register(destination)]
+                if destination.name().starts_with("%st(") && destination.name() != "%st(0)"
+        );
+        let mnemonic = match vector_move {
+            true => self.opcode.mnemonic(),
+            false => match x87_reversed(self.opcode.operation()) {
+                Some(reversed) if x87_destination => reversed,
+                _ => intel_mnemonic(self.opcode.operation()),
+            },
+        };
+        if self.operands.is_empty() && self.data.is_empty() {
+            text.push_str(mnemonic);
+            return text;
+        }
+        text.push_str(mnemonic);
+        // objdump pads the prefixes and mnemonic together: `rep stos QWORD PTR`.
+        while text.len() < 6 {
+            text.push(' ');
+        }
+        text.push(' ');
+
+        let bits = self.memory_bits();
+        let mut operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|operand| intel_operand(operand, bits))
+            .collect();
+        match self.opcode.operation() {
+            _ if self.opcode.is_data() => {}
+            "enter" => {}
+            "shl" | "shr" | "sal" | "sar" | "rol" | "ror" | "rcl" | "rcr"
+                if operands.len() == 1 =>
+            {
+                operands.push("1".to_string())
+            }
+            _ => operands.reverse(),
+        }
+        text.push_str(&operands.join(","));
+        if !self.data.is_empty() {
+            text.push(' ');
+            text.push_str(&self.data);
+        }
+        text
+    }
+}
+
+fn intel_register(register: &X8664ATTRegister) -> &str {
+    register.name().trim_start_matches('%')
+}
+
+fn intel_value(value: &X8664ATTValue) -> String {
+    match value {
+        X8664ATTValue::Register(register) => intel_register(register).to_string(),
+        X8664ATTValue::Immediate(immediate) => format!("{:#x}", immediate.0),
+    }
+}
+
+fn intel_address(address: &X8664ATTAddress) -> String {
+    let mut text = String::new();
+    if let Some(segment) = &address.segment {
+        text.push_str(intel_register(segment));
+        text.push(':');
+    }
+    if address.base.is_none() && address.offset.is_none() {
+        let displacement = address.displacement.as_ref().map_or(0, |d| d.0 as u64);
+        if address.segment.is_none() {
+            text.push_str("ds:");
+        }
+        text.push_str(&format!("{:#x}", displacement));
+        return text;
+    }
+    text.push('[');
+    let mut terms = Vec::new();
+    if let Some(base) = &address.base {
+        terms.push(intel_value(base));
+    }
+    if let Some(offset) = &address.offset {
+        let scale = address.scaler.as_ref().map_or(1, |scaler| scaler.0);
+        terms.push(format!("{}*{}", intel_value(offset), scale));
+    }
+    text.push_str(&terms.join("+"));
+    let rip_relative = matches!(
+        &address.base,
+        Some(X8664ATTValue::Register(register)) if register.name() == "%rip"
+    );
+    if let Some(displacement) = &address.displacement {
+        // objdump keeps RIP-relative displacements unsigned in Intel syntax.
+        if displacement.0 < 0 && !rip_relative {
+            text.push_str(&format!("-{:#x}", displacement.0.unsigned_abs()));
+        } else {
+            text.push_str(&format!("+{:#x}", displacement.0 as u64));
+        }
+    }
+    text.push(']');
+    text
+}
+
+fn intel_operand(operand: &X8664ATTOperand, bits: Option<u32>) -> String {
+    match operand {
+        X8664ATTOperand::Register(register) => intel_register(register).to_string(),
+        X8664ATTOperand::Immediate(immediate) => format!("{:#x}", immediate.0),
+        X8664ATTOperand::Address(address) => match bits.and_then(size_keyword) {
+            Some(size) => format!("{} PTR {}", size, intel_address(address)),
+            None => intel_address(address),
+        },
+        X8664ATTOperand::Target(target) => format!("{:x}", target),
+        X8664ATTOperand::Indirect(operand) => intel_operand(operand, bits),
+        X8664ATTOperand::Decorated(operand, decoration) => {
+            format!(
+                "{}{}",
+                intel_operand(operand, bits),
+                decoration.replace('%', "")
+            )
+        }
+        X8664ATTOperand::Rounding(rounding) => rounding.clone(),
+        X8664ATTOperand::Literal(value) => format!("{:#x}", value),
+        X8664ATTOperand::Bad => "(bad)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objdump::x8664_att::parse_x8664_att_instruction;
+
+    /// AT&T instructions and the Intel text `objdump -M intel` prints for
+    /// the same bytes.
+    const CASES: &[(&str, &str)] = &[
+        ("fdivrp %st,%st(1)", "fdivp  st(1),st"),
+        ("fdivp  %st,%st(1)", "fdivrp st(1),st"),
+        ("fsubrp %st,%st(1)", "fsubp  st(1),st"),
+        ("fsubp  %st,%st(1)", "fsubrp st(1),st"),
+        ("fsub   %st,%st(1)", "fsubr  st(1),st"),
+        ("fsubr  %st,%st(2)", "fsub   st(2),st"),
+        ("fdiv   %st,%st(3)", "fdivr  st(3),st"),
+        ("fdivr  %st,%st(3)", "fdiv   st(3),st"),
+        ("fsub   %st(1),%st", "fsub   st,st(1)"),
+        ("fdivr  %st(2),%st", "fdivr  st,st(2)"),
+        ("fnstcw 0x4e(%rsp)", "fnstcw WORD PTR [rsp+0x4e]"),
+        ("fldcw  0x4c(%rsp)", "fldcw  WORD PTR [rsp+0x4c]"),
+        ("fnstsw (%rax)", "fnstsw WORD PTR [rax]"),
+        ("stmxcsr 0x2c(%rsp)", "stmxcsr DWORD PTR [rsp+0x2c]"),
+        ("ldmxcsr 0x1c0(%rdx)", "ldmxcsr DWORD PTR [rdx+0x1c0]"),
+        (
+            "vpbroadcastb (%rax),%zmm3",
+            "vpbroadcastb zmm3,BYTE PTR [rax]",
+        ),
+        (
+            "vpbroadcastd (%rdi),%ymm1",
+            "vpbroadcastd ymm1,DWORD PTR [rdi]",
+        ),
+        ("movzbl (%rdi),%eax", "movzx  eax,BYTE PTR [rdi]"),
+        ("movswq 0x2(%rsi),%rdx", "movsx  rdx,WORD PTR [rsi+0x2]"),
+        ("cltq", "cdqe"),
+        ("cqto", "cqo"),
+        ("lea    0x10(%rip),%rdi", "lea    rdi,[rip+0x10]"),
+        (
+            "mov    %rax,-0x8(%rbp,%rcx,8)",
+            "mov    QWORD PTR [rbp+rcx*8-0x8],rax",
+        ),
+        ("flds   0x4(%rsp)", "fld    DWORD PTR [rsp+0x4]"),
+        ("fildll (%rax)", "fild   QWORD PTR [rax]"),
+        (
+            "rep stos %rax,%es:(%rdi)",
+            "rep stos QWORD PTR es:[rdi],rax",
+        ),
+        (
+            "lock cmpxchg %edx,(%rdi)",
+            "lock cmpxchg DWORD PTR [rdi],edx",
+        ),
+        ("lock incl (%rax)", "lock inc DWORD PTR [rax]"),
+        ("notrack jmp *%rax", "notrack jmp rax"),
+        (
+            "cs nopw 0x0(%rax,%rax,1)",
+            "cs nop WORD PTR [rax+rax*1+0x0]",
+        ),
+    ];
+
+    #[test]
+    fn matches_objdump() {
+        for (att, intel) in CASES {
+            let instruction = parse_x8664_att_instruction(att).unwrap();
+            assert_eq!(instruction.to_intel(), *intel, "{}", att);
+        }
+    }
+}
@@ -0,0 +1,515 @@
+use super::{X8664ATTInstruction, X8664ATTOpcode, X8664ATTOperand};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum X8664ATTCategory {
+    DataTransfer,
+    Stack,
+    Arithmetic,
+    Logic,
+    Shift,
+    Bit,
+    Compare,
+    Conversion,
+    Branch,
+    String,
+    Flags,
+    X87,
+    Simd,
+    System,
+    Nop,
+    Prefix,
+    /// Assembler data directives (`.byte`, `.word`, `.long`).
+    Data,
+    /// Bytes objdump could not decode, `(bad)`.
+    Invalid,
+    Other,
+}
+
+impl X8664ATTCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            X8664ATTCategory::DataTransfer => "data-transfer",
+            X8664ATTCategory::Stack => "stack",
+            X8664ATTCategory::Arithmetic => "arithmetic",
+            X8664ATTCategory::Logic => "logic",
+            X8664ATTCategory::Shift => "shift",
+            X8664ATTCategory::Bit => "bit",
+            X8664ATTCategory::Compare => "compare",
+            X8664ATTCategory::Conversion => "conversion",
+            X8664ATTCategory::Branch => "branch",
+            X8664ATTCategory::String => "string",
+            X8664ATTCategory::Flags => "flags",
+            X8664ATTCategory::X87 => "x87",
+            X8664ATTCategory::Simd => "simd",
+            X8664ATTCategory::System => "system",
+            X8664ATTCategory::Nop => "nop",
+            X8664ATTCategory::Prefix => "prefix",
+            X8664ATTCategory::Data => "data",
+            X8664ATTCategory::Invalid => "invalid",
+            X8664ATTCategory::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum X8664ATTExtension {
+    Base,
+    X87,
+    Mmx,
+    Sse,
+    Sse2,
+    Sse3,
+    Ssse3,
+    Sse41,
+    Sse42,
+    Cmpxchg16b,
+    LahfSahf,
+    Popcnt,
+    Lzcnt,
+    Bmi1,
+    Bmi2,
+    Movbe,
+    Avx,
+    Avx2,
+    Fma,
+    F16c,
+    Avx512,
+    Aes,
+    Pclmul,
+    Sha,
+    Rdrand,
+    Adx,
+    Other,
+}
+
+impl X8664ATTExtension {
+    pub fn name(&self) -> &'static str {
+        match self {
+            X8664ATTExtension::Base => "base",
+            X8664ATTExtension::X87 => "x87",
+            X8664ATTExtension::Mmx => "mmx",
+            X8664ATTExtension::Sse => "sse",
+            X8664ATTExtension::Sse2 => "sse2",
+            X8664ATTExtension::Sse3 => "sse3",
+            X8664ATTExtension::Ssse3 => "ssse3",
+            X8664ATTExtension::Sse41 => "sse4.1",
+            X8664ATTExtension::Sse42 => "sse4.2",
+            X8664ATTExtension::Cmpxchg16b => "cmpxchg16b",
+            X8664ATTExtension::LahfSahf => "lahf-sahf",
+            X8664ATTExtension::Popcnt => "popcnt",
+            X8664ATTExtension::Lzcnt => "lzcnt",
+            X8664ATTExtension::Bmi1 => "bmi1",
+            X8664ATTExtension::Bmi2 => "bmi2",
+            X8664ATTExtension::Movbe => "movbe",
+            X8664ATTExtension::Avx => "avx",
+            X8664ATTExtension::Avx2 => "avx2",
+            X8664ATTExtension::Fma => "fma",
+            X8664ATTExtension::F16c => "f16c",
+            X8664ATTExtension::Avx512 => "avx-512",
+            X8664ATTExtension::Aes => "aes",
+            X8664ATTExtension::Pclmul => "pclmul",
+            X8664ATTExtension::Sha => "sha",
+            X8664ATTExtension::Rdrand => "rdrand",
+            X8664ATTExtension::Adx => "adx",
+            X8664ATTExtension::Other => "other",
+        }
+    }
+}
+
+const CONDITIONS: &[&str] = &[
+    "o", "no", "b", "c", "nae", "ae", "nb", "nc", "e", "z", "ne", "nz", "be", "na", "a", "nbe",
+    "s", "ns", "p", "pe", "np", "po", "l", "nge", "ge", "nl", "le", "ng", "g", "nle",
+];
+
+/// Mnemonics that take an AT&T `b`/`w`/`l`/`q` operand-size suffix.
+#[rustfmt::skip]
+const SIZED: &[&str] = &[
+    "mov", "movabs", "movbe", "movnti", "add", "adc", "sub", "sbb", "and", "or", "xor", "not",
+    "neg", "inc", "dec", "cmp", "test", "imul", "mul", "div", "idiv", "lea", "push", "pop",
+    "pushf", "popf", "shl", "shr", "sal", "sar", "rol", "ror", "rcl", "rcr", "shld", "shrd",
+    "xchg", "xadd", "cmpxchg", "bt", "bts", "btr", "btc", "bsf", "bsr", "bswap", "tzcnt",
+    "lzcnt", "popcnt", "call", "ret", "jmp", "lcall", "ljmp", "lret", "iret", "sysret", "nop",
+    "lods", "stos", "scas", "movs", "cmps", "ins", "outs", "in", "out", "lar", "lsl", "sldt",
+    "str", "smsw", "lgs", "lfs", "lss", "lgdt", "lidt", "sgdt", "sidt", "lldt", "ltr", "enter",
+    "leave", "crc32", "adcx", "adox", "andn", "bextr", "blsi", "blsmsk", "blsr", "bzhi", "mulx",
+    "pdep", "pext", "rorx", "sarx", "shlx", "shrx", "cvtsi2sd", "cvtsi2ss", "vcvtsi2sd",
+    "vcvtsi2ss", "vcvtusi2sd", "vcvtusi2ss", "rdrand", "rdseed", "syscall", "sysexit",
+];
+
+/// x87 mnemonics that take an `s`/`l`/`t`/`ll` memory-operand suffix.
+const X87_SIZED: &[&str] = &[
+    "fadd", "fsub", "fsubr", "fmul", "fdiv", "fdivr", "fld", "fst", "fstp", "fcom", "fcomp",
+    "fild", "fist", "fistp", "fisttp", "fiadd", "fisub", "fisubr", "fimul", "fidiv", "fidivr",
+    "ficom", "ficomp",
+];
+
+fn strip_condition<'a>(mnemonic: &'a str, stem: &str) -> Option<&'a str> {
+    let condition = mnemonic.strip_prefix(stem)?;
+    if CONDITIONS.contains(&condition) {
+        return Some(mnemonic);
+    }
+    let unsized_condition = condition.strip_suffix(['w', 'l', 'q'])?;
+    CONDITIONS
+        .contains(&unsized_condition)
+        .then(|| &mnemonic[..mnemonic.len() - 1])
+}
+
+fn operation(mnemonic: &str) -> &str {
+    match mnemonic {
+        "movzbw" | "movzbl" | "movzbq" | "movzwl" | "movzwq" => return "movzx",
+        "movsbw" | "movsbl" | "movsbq" | "movswl" | "movswq" => return "movsx",
+        "movslq" => return "movsxd",
+        _ => {}
+    }
+    if let Some(operation) = strip_condition(mnemonic, "cmov") {
+        return operation;
+    }
+    if strip_condition(mnemonic, "j").is_some() || strip_condition(mnemonic, "set").is_some() {
+        return mnemonic;
+    }
+    if SIZED.contains(&mnemonic) || X87_SIZED.contains(&mnemonic) {
+        return mnemonic;
+    }
+    if let Some(stem) = mnemonic.strip_suffix(['b', 'w', 'l', 'q']) {
+        if SIZED.contains(&stem) {
+            return stem;
+        }
+    }
+    if mnemonic.starts_with('f') {
+        if let Some(stem) = mnemonic.strip_suffix("ll") {
+            if X87_SIZED.contains(&stem) {
+                return stem;
+            }
+        }
+        if let Some(stem) = mnemonic.strip_suffix(['s', 'l', 't']) {
+            if X87_SIZED.contains(&stem) {
+                return stem;
+            }
+        }
+    }
+    mnemonic
+}
+
+fn category(operation: &str) -> X8664ATTCategory {
+    match operation {
+        "mov" | "movabs" | "movzx" | "movsx" | "movsxd" | "movbe" | "movnti" | "lea" | "xchg"
+        | "bswap" | "xlatb" | "cmpxchg" | "cmpxchg8b" | "cmpxchg16b" | "xadd" => {
+            X8664ATTCategory::DataTransfer
+        }
+        "push" | "pop" | "pushf" | "popf" | "pushfq" | "popfq" | "enter" | "leave" => {
+            X8664ATTCategory::Stack
+        }
+        "add" | "adc" | "sub" | "sbb" | "imul" | "mul" | "div" | "idiv" | "inc" | "dec" | "neg"
+        | "adcx" | "adox" | "mulx" => X8664ATTCategory::Arithmetic,
+        "and" | "or" | "xor" | "not" | "andn" => X8664ATTCategory::Logic,
+        "shl" | "shr" | "sal" | "sar" | "rol" | "ror" | "rcl" | "rcr" | "shld" | "shrd"
+        | "rorx" | "sarx" | "shlx" | "shrx" => X8664ATTCategory::Shift,
+        "bt" | "bts" | "btr" | "btc" | "bsf" | "bsr" | "tzcnt" | "lzcnt" | "popcnt" | "bextr"
+        | "blsi" | "blsmsk" | "blsr" | "bzhi" | "pdep" | "pext" => X8664ATTCategory::Bit,
+        "cmp" | "test" => X8664ATTCategory::Compare,
+        "cbtw" | "cwtl" | "cltq" | "cwtd" | "cltd" | "cqto" => X8664ATTCategory::Conversion,
+        "call" | "ret" | "jmp" | "lcall" | "ljmp" | "lret" | "iret" | "loop" | "loope"
+        | "loopne" | "jrcxz" | "jecxz" => X8664ATTCategory::Branch,
+        "lods" | "stos" | "scas" | "movs" | "cmps" | "ins" | "outs" => X8664ATTCategory::String,
+        "clc" | "stc" | "cmc" | "cld" | "std" | "cli" | "sti" | "lahf" | "sahf" => {
+            X8664ATTCategory::Flags
+        }
+        "nop" | "pause" | "endbr64" | "endbr32" | "fnop" => X8664ATTCategory::Nop,
+        "syscall" | "sysenter" | "sysexit" | "sysret" | "int" | "int3" | "into" | "hlt"
+        | "cpuid" | "rdtsc" | "rdtscp" | "rdmsr" | "wrmsr" | "in" | "out" | "ud2" | "invd"
+        | "wbinvd" | "clts" | "lgdt" | "lidt" | "sgdt" | "sidt" | "lldt" | "sldt" | "ltr"
+        | "str" | "lar" | "lsl" | "verr" | "verw" | "smsw" | "getsec" | "monitorx" | "rdrand"
+        | "rdseed" | "xgetbv" | "xsave" | "xrstor" | "xsavec" | "xsaveopt" | "xrstors"
+        | "xsaves" | "fxsave" | "fxrstor" | "fxsave64" | "fxrstor64" | "rdpkru" | "wrpkru"
+        | "rdfsbase" | "rdgsbase" | "wrfsbase" | "wrgsbase" | "lgs" | "lfs" | "lss" | "xabort"
+        | "xbegin" | "xend" | "xtest" | "wait" | "swapgs" | "incsspq" | "rdsspq" | "lfence"
+        | "sfence" | "mfence" => X8664ATTCategory::System,
+        _ if strip_condition(operation, "j").is_some() => X8664ATTCategory::Branch,
+        _ if strip_condition(operation, "cmov").is_some() => X8664ATTCategory::DataTransfer,
+        _ if strip_condition(operation, "set").is_some() => X8664ATTCategory::Flags,
+        _ if operation.starts_with('f') => X8664ATTCategory::X87,
+        _ => match extension(operation) {
+            X8664ATTExtension::Base | X8664ATTExtension::Other => X8664ATTCategory::Other,
+            X8664ATTExtension::X87 => X8664ATTCategory::X87,
+            _ => X8664ATTCategory::Simd,
+        },
+    }
+}
+
+#[rustfmt::skip]
+const SSE: &[&str] = &[
+    "addps", "addss", "subps", "subss", "mulps", "mulss", "divps", "divss", "sqrtps", "sqrtss",
+    "rcpps", "rcpss", "rsqrtps", "rsqrtss", "maxps", "maxss", "minps", "minss", "andps",
+    "andnps", "orps", "xorps", "movaps", "movups", "movss", "movlps", "movhps", "movlhps",
+    "movhlps", "movmskps", "movntps", "shufps", "unpcklps", "unpckhps", "cmpps", "cmpss",
+    "comiss", "ucomiss", "cvtsi2ss", "cvtss2si", "cvttss2si", "cvtpi2ps", "cvtps2pi",
+    "cvttps2pi", "ldmxcsr", "stmxcsr", "sfence", "prefetchnta", "prefetcht0", "prefetcht1",
+    "prefetcht2", "pavgb", "pavgw", "pextrw", "pinsrw", "pmaxsw", "pmaxub", "pminsw", "pminub",
+    "pmovmskb", "pmulhuw", "psadbw", "pshufw", "maskmovq", "movntq",
+];
+
+#[rustfmt::skip]
+const SSE2: &[&str] = &[
+    "addpd", "addsd", "subpd", "subsd", "mulpd", "mulsd", "divpd", "divsd", "sqrtpd", "sqrtsd",
+    "maxpd", "maxsd", "minpd", "minsd", "andpd", "andnpd", "orpd", "xorpd", "movapd", "movupd",
+    "movsd", "movlpd", "movhpd", "movmskpd", "movntpd", "shufpd", "unpcklpd", "unpckhpd",
+    "cmppd", "cmpsd", "comisd", "ucomisd", "cvtdq2pd", "cvtdq2ps", "cvtpd2dq", "cvtpd2pi",
+    "cvtpd2ps", "cvtpi2pd", "cvtps2dq", "cvtps2pd", "cvtsd2si", "cvtsd2ss", "cvtsi2sd",
+    "cvtss2sd", "cvttpd2dq", "cvttpd2pi", "cvttps2dq", "cvttsd2si", "movdqa", "movdqu",
+    "movq2dq", "movdq2q", "movntdq", "maskmovdqu", "pshufd", "pshufhw", "pshuflw",
+    "punpcklqdq", "punpckhqdq", "pslldq", "psrldq", "paddq", "psubq", "pmuludq", "clflush",
+    "lfence", "mfence", "pause",
+];
+
+#[rustfmt::skip]
+const SSE3: &[&str] = &[
+    "addsubps", "addsubpd", "haddps", "haddpd", "hsubps", "hsubpd", "movddup", "movshdup",
+    "movsldup", "lddqu", "monitor", "mwait", "fisttp",
+];
+
+#[rustfmt::skip]
+const SSSE3: &[&str] = &[
+    "pshufb", "phaddw", "phaddd", "phaddsw", "phsubw", "phsubd", "phsubsw", "pmaddubsw",
+    "pmulhrsw", "psignb", "psignw", "psignd", "pabsb", "pabsw", "pabsd", "palignr",
+];
+
+#[rustfmt::skip]
+const SSE41: &[&str] = &[
+    "pblendvb", "pblendw", "blendps", "blendpd", "blendvps", "blendvpd", "dpps", "dppd",
+    "insertps", "extractps", "pextrb", "pextrd", "pextrq", "pinsrb", "pinsrd", "pinsrq",
+    "pminsb", "pminsd", "pminuw", "pminud", "pmaxsb", "pmaxsd", "pmaxuw", "pmaxud", "pmulld",
+    "pmuldq", "pcmpeqq", "packusdw", "pmovsxbw", "pmovsxbd", "pmovsxbq", "pmovsxwd",
+    "pmovsxwq", "pmovsxdq", "pmovzxbw", "pmovzxbd", "pmovzxbq", "pmovzxwd", "pmovzxwq",
+    "pmovzxdq", "ptest", "roundps", "roundpd", "roundss", "roundsd", "mpsadbw", "phminposuw",
+    "movntdqa",
+];
+
+#[rustfmt::skip]
+const SSE42: &[&str] = &[
+    "pcmpestri", "pcmpestrm", "pcmpistri", "pcmpistrm", "pcmpgtq", "crc32",
+];
+
+#[rustfmt::skip]
+const AVX2: &[&str] = &[
+    "vpbroadcastb", "vpbroadcastw", "vpbroadcastd", "vpbroadcastq", "vbroadcasti128",
+    "vinserti128", "vextracti128", "vperm2i128", "vpermd", "vpermq", "vpermps", "vpermpd",
+    "vpsllvd", "vpsllvq", "vpsrlvd", "vpsrlvq", "vpsravd", "vpmaskmovd", "vpmaskmovq",
+    "vpgatherdd", "vpgatherdq", "vpgatherqd", "vpgatherqq", "vgatherdps", "vgatherdpd",
+    "vgatherqps", "vgatherqpd", "vpblendd",
+];
+
+#[rustfmt::skip]
+const AVX512_PREFIXES: &[&str] = &[
+    "vpermi2", "vpermt2", "vpblendm", "vblendm", "vpternlog", "vpcompress", "vpexpand",
+    "vcompress", "vexpand", "vprol", "vpror", "vpshld", "vpshrd", "vpconflict", "vplzcnt",
+    "valign", "vfpclass", "vgetexp", "vgetmant", "vrange", "vreduce", "vrndscale", "vscalef",
+    "vrcp14", "vrsqrt14", "vrcp28", "vrsqrt28", "vexp2", "vpmovm2", "vpmovb2m", "vpmovw2m",
+    "vpmovd2m", "vpmovq2m", "vpermb", "vpermw", "vpmultishift", "vpopcnt", "vpshufbitqmb",
+    "vpdpbusd", "vpdpwssd", "vpcmpu", "vpcmpb", "vpcmpw", "vpcmpd", "vpcmpq", "vptestm",
+    "vptestnm", "vpabsq", "vpmaxsq", "vpmaxuq", "vpminsq", "vpminuq", "vpsraq", "vpmullq",
+    "vpscatter", "vscatter", "vdbpsadbw", "vpsllvw", "vpsrlvw", "vpsravw", "vfixupimm",
+];
+
+fn extension(operation: &str) -> X8664ATTExtension {
+    match operation {
+        "popcnt" => return X8664ATTExtension::Popcnt,
+        "lzcnt" => return X8664ATTExtension::Lzcnt,
+        "andn" | "bextr" | "blsi" | "blsmsk" | "blsr" | "tzcnt" => return X8664ATTExtension::Bmi1,
+        "bzhi" | "mulx" | "pdep" | "pext" | "rorx" | "sarx" | "shlx" | "shrx" => {
+            return X8664ATTExtension::Bmi2
+        }
+        "movbe" => return X8664ATTExtension::Movbe,
+        "cmpxchg16b" => return X8664ATTExtension::Cmpxchg16b,
+        "lahf" | "sahf" => return X8664ATTExtension::LahfSahf,
+        "adcx" | "adox" => return X8664ATTExtension::Adx,
+        "rdrand" | "rdseed" => return X8664ATTExtension::Rdrand,
+        "pclmulqdq" | "vpclmulqdq" => return X8664ATTExtension::Pclmul,
+        "emms" => return X8664ATTExtension::Mmx,
+        "femms" | "fxsave" | "fxrstor" | "fxsave64" | "fxrstor64" => {
+            return X8664ATTExtension::Other
+        }
+        _ => {}
+    }
+    if operation.starts_with("aes") || operation.starts_with("vaes") {
+        return X8664ATTExtension::Aes;
+    }
+    if operation.starts_with("sha") {
+        return X8664ATTExtension::Sha;
+    }
+    if operation.starts_with('k') {
+        return X8664ATTExtension::Avx512;
+    }
+    if let Some(legacy) = operation.strip_prefix('v') {
+        if AVX2.contains(&operation) {
+            return X8664ATTExtension::Avx2;
+        }
+        if operation == "vcvtph2ps" || operation == "vcvtps2ph" {
+            return X8664ATTExtension::F16c;
+        }
+        if AVX512_PREFIXES
+            .iter()
+            .any(|prefix| operation.starts_with(prefix))
+            || [
+                "32x4", "64x2", "32x8", "64x4", "2udq", "udq2", "2uqq", "uqq2", "2qq", "qq2", "usi",
+            ]
+            .iter()
+            .any(|infix| operation.contains(infix))
+            || ((operation.ends_with("ph") || operation.ends_with("sh"))
+                && !operation.starts_with("vph"))
+            || (operation.starts_with("vpmov")
+                && !operation.starts_with("vpmovsx")
+                && !operation.starts_with("vpmovzx")
+                && operation != "vpmovmskb")
+            || (operation.starts_with("vmovdq")
+                && operation.ends_with(|c: char| c.is_ascii_digit()))
+        {
+            return X8664ATTExtension::Avx512;
+        }
+        if ["vfmadd", "vfmsub", "vfnmadd", "vfnmsub"]
+            .iter()
+            .any(|prefix| operation.starts_with(prefix))
+        {
+            return X8664ATTExtension::Fma;
+        }
+        if legacy_extension(legacy) != X8664ATTExtension::Base
+            || operation.starts_with("vbroadcast")
+            || operation.starts_with("vinsert")
+            || operation.starts_with("vextract")
+            || operation.starts_with("vperm")
+            || operation.starts_with("vmaskmov")
+            || operation.starts_with("vtest")
+            || operation.starts_with("vzero")
+            || operation.starts_with("vcvt")
+            || operation.starts_with("vcmp")
+            || operation == "vmovd"
+            || operation == "vmovq"
+        {
+            return X8664ATTExtension::Avx;
+        }
+        return X8664ATTExtension::Other;
+    }
+    legacy_extension(operation)
+}
+
+fn legacy_extension(operation: &str) -> X8664ATTExtension {
+    let compare = operation.starts_with("cmp") && operation.len() > 4;
+    if SSE.contains(&operation)
+        || (compare && (operation.ends_with("ps") || operation.ends_with("ss")))
+    {
+        X8664ATTExtension::Sse
+    } else if SSE2.contains(&operation)
+        || (compare && (operation.ends_with("pd") || operation.ends_with("sd")))
+        || (operation.starts_with('p') && is_mmx_era_integer(operation))
+    {
+        X8664ATTExtension::Sse2
+    } else if SSE3.contains(&operation) {
+        X8664ATTExtension::Sse3
+    } else if SSSE3.contains(&operation) {
+        X8664ATTExtension::Ssse3
+    } else if SSE41.contains(&operation) {
+        X8664ATTExtension::Sse41
+    } else if SSE42.contains(&operation) || operation.starts_with("crc32") {
+        X8664ATTExtension::Sse42
+    } else if operation.starts_with('f') {
+        X8664ATTExtension::X87
+    } else {
+        X8664ATTExtension::Base
+    }
+}
+
+/// Integer SIMD operations that date back to MMX and were widened to `%xmm`
+/// by SSE2.
+fn is_mmx_era_integer(operation: &str) -> bool {
+    [
+        "padd", "psub", "pmull", "pmulh", "pmadd", "pcmpeq", "pcmpgt", "pand", "pandn", "por",
+        "pxor", "psll", "psrl", "psra", "punpck", "pack",
+    ]
+    .iter()
+    .any(|prefix| operation.starts_with(prefix))
+        && !matches!(
+            operation,
+            "pmulhrsw" | "pmaddubsw" | "pmulld" | "pcmpeqq" | "pcmpgtq" | "packusdw"
+        )
+}
+
+impl X8664ATTOpcode {
+    /// Mnemonic with any AT&T operand-size suffix removed, so that `movl`,
+    /// `movq` and `mov` all report `mov`. Sign and zero extensions use their
+    /// Intel names (`movzx`, `movsx`, `movsxd`).
+    pub fn operation(&self) -> &str {
+        operation(self.mnemonic())
+    }
+
+    pub fn category(&self) -> X8664ATTCategory {
+        if self.is_prefix() {
+            return X8664ATTCategory::Prefix;
+        }
+        if self.is_data() {
+            return X8664ATTCategory::Data;
+        }
+        if *self == X8664ATTOpcode::Bad {
+            return X8664ATTCategory::Invalid;
+        }
+        category(self.operation())
+    }
+
+    /// ISA extension implied by the mnemonic alone. See
+    /// [`X8664ATTInstruction::extension`] for the operand-aware answer.
+    pub fn extension(&self) -> X8664ATTExtension {
+        if self.is_prefix() {
+            return X8664ATTExtension::Base;
+        }
+        extension(self.operation())
+    }
+}
+
+impl X8664ATTInstruction {
+    /// ISA extension required by the instruction, taking operands into account:
+    /// `%zmm`, `%k` and masked or embedded-rounding operands need AVX-512,
+    /// VEX integer operations on `%ymm` need AVX2, and MMX-era integer
+    /// operations on `%mm` registers stay MMX.
+    pub fn extension(&self) -> X8664ATTExtension {
+        let extension = self.opcode.extension();
+        let mut widest = 0;
+        let mut evex = false;
+        let mut mmx = false;
+        for operand in &self.operands {
+            let register = match operand {
+                X8664ATTOperand::Register(register) => Some(register),
+                X8664ATTOperand::Decorated(operand, _) => {
+                    evex = true;
+                    match operand.as_ref() {
+                        X8664ATTOperand::Register(register) => Some(register),
+                        _ => None,
+                    }
+                }
+                X8664ATTOperand::Rounding(_) => {
+                    evex = true;
+                    None
+                }
+                _ => None,
+            };
+            if let Some(register) = register {
+                widest = widest.max(register.vector_width().unwrap_or(0));
+                evex |= register.is_evex_only();
+                mmx |= register.is_mmx();
+            }
+        }
+        if evex || widest == 512 {
+            return X8664ATTExtension::Avx512;
+        }
+        match extension {
+            X8664ATTExtension::Avx
+                if widest == 256 && self.opcode.operation().starts_with("vp") =>
+            {
+                X8664ATTExtension::Avx2
+            }
+            X8664ATTExtension::Sse | X8664ATTExtension::Sse2 | X8664ATTExtension::Ssse3 if mmx => {
+                X8664ATTExtension::Mmx
+            }
+            X8664ATTExtension::Base if mmx => X8664ATTExtension::Mmx,
+            X8664ATTExtension::Base if widest == 128 => X8664ATTExtension::Sse2,
+            extension => extension,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+/* Source of the objdump corpus in this directory. Regenerate with:
+ *   gcc -O2 -mavx2 -mfma -c corpus.c -o /tmp/corpus.o
+ *   objdump -d /tmp/corpus.o > corpus.dump
+ *   gcc -O2 -fPIE -pie corpus.c -o /tmp/corpus -DMAIN
+ *   objdump -s -d /tmp/corpus > corpus-pie.dump
+ */
+#include <stdatomic.h>
+#include <stdio.h>
+#include <string.h>
+
+long double scale(long double x, long double y) { return x / y - y * x; }
+
+float dot(const float *a, const float *b, int n) {
+    float sum = 0;
+    for (int i = 0; i < n; i++)
+        sum += a[i] * b[i];
+    return sum;
+}
+
+void saxpy(float *restrict y, const float *restrict x, float a, int n) {
+    for (int i = 0; i < n; i++)
+        y[i] = a * x[i] + y[i];
+}
+
+double convert(long n, unsigned u) { return (double)n + (float)u; }
+
+int increment(atomic_int *counter) { return atomic_fetch_add(counter, 1) + 1; }
+
+long exchange(long *slot, long expected, long value) {
+    __atomic_compare_exchange_n(slot, &expected, value, 0, __ATOMIC_SEQ_CST,
+                                __ATOMIC_SEQ_CST);
+    return expected;
+}
+
+void clear(char *buffer, unsigned long size) { __builtin_memset(buffer, 0, size); }
+
+const char *name(int code) {
+    switch (code) {
+    case 0: return "zero";
+    case 1: return "one";
+    case 2: return "two";
+    case 3: return "three";
+    case 4: return "four";
+    case 5: return "five";
+    default: return "many";
+    }
+}
+
+unsigned bits(unsigned long x) {
+    return __builtin_popcountl(x) + __builtin_ctzl(x | 1) + (x >> 3 & 0x7);
+}
+
+short narrow(signed char c, unsigned short s) { return (short)(c * s); }
+
+#ifdef MAIN
+int main(int argc, char **argv) {
+    float a[8] = {1, 2, 3, 4, 5, 6, 7, 8};
+    printf("%s %f %u\n", name(argc), dot(a, a, argc), bits(argc));
+    return strlen(argv[0]) > 3;
+}
+#endif
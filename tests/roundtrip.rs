@@ -0,0 +1,38 @@
+use objump::objdump::roundtrip::RoundTripReport;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Every instruction of the checked-in objdump corpus renders back to the
+/// text objdump printed.
+#[test]
+fn corpus_round_trips() {
+    let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(&data).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("dump") {
+            continue;
+        }
+        let file = std::fs::File::open(&path).unwrap();
+        let report = RoundTripReport::check(BufReader::new(file)).unwrap();
+        assert!(
+            report.instructions > 0,
+            "{}: no instructions",
+            path.display()
+        );
+        assert!(
+            report.mismatches.is_empty(),
+            "{}: {:?}",
+            path.display(),
+            report.mismatches
+        );
+        assert!(
+            report.errors.is_empty(),
+            "{}: {:?}",
+            path.display(),
+            report.errors
+        );
+        checked += 1;
+    }
+    assert!(checked >= 2);
+}